@@ -84,7 +84,11 @@ export function deserialize(document, real, dump) {
     'Extrusion': ExtrudeFeature,
     'Revolution': RevolveFeature,
     'Draft': DraftFeature,
+    'Fillet': FilletFeature,
+    'Chamfer': ChamferFeature,
+    'Shell': ShellFeature,
     'Sweep': SweepFeature,
+    'Loft': LoftFeature,
   }[dump.title]
   const feature = new Klass(document, real)
   feature.setValues(dump.values)
@@ -156,17 +160,53 @@ export class ExtrudeFeature extends Feature {
         type: 'axis',
         optional: true,
       },
+      extent: {
+        title: 'Extent',
+        type: 'select',
+        options: {
+          distance: {
+            title: 'Distance',
+            icon: 'ruler',
+          },
+          symmetric: {
+            title: 'Symmetric',
+            icon: 'arrows-alt-v',
+          },
+          two_sided: {
+            title: 'Two sides',
+            icon: 'exchange-alt',
+          },
+          face: {
+            title: 'Up to face',
+            icon: 'step-forward',
+          },
+          next: {
+            title: 'Up to next',
+            icon: 'fast-forward',
+          },
+          all: {
+            title: 'Through all',
+            icon: 'forward',
+          },
+        },
+      },
       distance: {
         title: 'Distance',
         type: 'length',
       },
-      // limit: {
-      //   type: 'OR',
-      //   settings: [
-      //     { title: 'Distance', type: 'length' },
-      //     { title: '(Up to)', type: 'surface|point' },
-      //   ]
-      // },
+      distance2: {
+        title: 'Second Distance',
+        type: 'length',
+      },
+      target: {
+        title: '(Up to)',
+        type: 'face',
+        optional: true,
+      },
+      taper: {
+        title: 'Taper',
+        type: 'angle',
+      },
       side: {
         title: 'Side',
         type: 'bool',
@@ -176,12 +216,16 @@ export class ExtrudeFeature extends Feature {
 
     this.profiles = null
     this.axis = null
+    this.extent = 'distance'
     this.distance = 1.0
+    this.distance2 = 1.0
+    this.target = null
+    this.taper = 0.0
     this.side = true
   }
 
   isComplete() {
-    return this.profiles && this.profiles().length
+    return this.profiles && this.profiles().length && (this.extent != 'face' || this.target)
   }
 
   updateFeature() {
@@ -189,13 +233,15 @@ export class ExtrudeFeature extends Feature {
     this.profiles().forEach(profile => {
       list.push(profile)
     })
+    const targets = new window.alcWasm.JsFaceRefList()
+    if(this.target) targets.push(this.target())
     const comp_ref = this.document.activeComponent.id
-    const distance = this.distance * (this.side ? 1 : -1)
-    this.real.extrusion(comp_ref, list, distance, this.operation)
+    const distance = this.distance * (this.side || this.extent == 'two_sided' ? 1 : -1)
+    this.real.extrusion(comp_ref, list, this.extent, distance, this.distance2, targets, this.taper, this.operation)
   }
 
   updateGizmos() {
-    if(this.isComplete()) {
+    if(this.isComplete() && this.extent == 'distance') {
       if(this.lengthGizmo) {
         this.lengthGizmo.set(this.distance, this.side)
       } else {
@@ -219,6 +265,11 @@ export class ExtrudeFeature extends Feature {
   confirm() {
     // Refetch profiles in case they've been repaired
     this.profiles().forEach(profile => profile.update())
+    if(this.target) {
+      this.target().free()
+      const target = this.real.face_refs()[0]
+      this.target = target && (() => target)
+    }
   }
 
   dispose() {
@@ -379,39 +430,243 @@ export class DraftFeature extends Feature {
 }
 
 
+export class FilletFeature extends Feature {
+  constructor(document, real) {
+    super(document, real, true, 'Fillet', 'clone', {
+      edges: {
+        title: 'Edges',
+        type: 'edge',
+        multi: true,
+        autoMulti: true,
+      },
+      radius: {
+        title: 'Radius',
+        type: 'length',
+      },
+    })
+
+    this.edges = null
+    this.radius = 1.0
+  }
+
+  isComplete() {
+    return this.edges && this.edges().length
+  }
+
+  updateFeature() {
+    const list = new window.alcWasm.JsEdgeRefList()
+    this.edges().forEach(edge => {
+      list.push(edge)
+    })
+    this.real.fillet(list, this.radius)
+  }
+
+  confirm() {
+    // Refetch edges in case they've been repaired
+    this.edges().forEach(edgeRef => edgeRef.free())
+    const edges = this.real.edge_refs()
+    this.edges = () => edges
+  }
+}
+
+
+export class ChamferFeature extends Feature {
+  constructor(document, real) {
+    super(document, real, true, 'Chamfer', 'screwdriver', {
+      edges: {
+        title: 'Edges',
+        type: 'edge',
+        multi: true,
+        autoMulti: true,
+      },
+      mode: {
+        title: 'Mode',
+        type: 'select',
+        options: {
+          distance: {
+            title: 'Equal distance',
+            icon: 'ruler',
+          },
+          distances: {
+            title: 'Two distances',
+            icon: 'exchange-alt',
+          },
+          angle: {
+            title: 'Distance and angle',
+            icon: 'angle-up',
+          },
+        },
+      },
+      distance: {
+        title: 'Distance',
+        type: 'length',
+      },
+      distance2: {
+        title: 'Second Distance',
+        type: 'length',
+      },
+      angle: {
+        title: 'Angle',
+        type: 'angle',
+      },
+    })
+
+    this.edges = null
+    this.mode = 'distance'
+    this.distance = 1.0
+    this.distance2 = 1.0
+    this.angle = 45.0
+  }
+
+  isComplete() {
+    return this.edges && this.edges().length
+  }
+
+  updateFeature() {
+    const list = new window.alcWasm.JsEdgeRefList()
+    this.edges().forEach(edge => {
+      list.push(edge)
+    })
+    const other = this.mode == 'angle' ? this.angle : this.distance2
+    this.real.chamfer(list, this.mode, this.distance, other)
+  }
+
+  confirm() {
+    // Refetch edges in case they've been repaired
+    this.edges().forEach(edgeRef => edgeRef.free())
+    const edges = this.real.edge_refs()
+    this.edges = () => edges
+  }
+}
+
+
+export class ShellFeature extends Feature {
+  constructor(document, real) {
+    super(document, real, true, 'Shell', 'box', {
+      faces: {
+        title: 'Open Faces',
+        type: 'face',
+        multi: true,
+        autoMulti: true,
+      },
+      thickness: {
+        title: 'Thickness',
+        type: 'length',
+      },
+    })
+
+    this.faces = null
+    this.thickness = 0.1
+  }
+
+  isComplete() {
+    return this.faces && this.faces().length
+  }
+
+  updateFeature() {
+    const list = new window.alcWasm.JsFaceRefList()
+    this.faces().forEach(face => {
+      list.push(face)
+    })
+    this.real.shell(list, this.thickness)
+  }
+
+  confirm() {
+    // Refetch faces in case they've been repaired
+    this.faces().forEach(faceRef => faceRef.free())
+    const faces = this.real.face_refs()
+    this.faces = () => faces
+  }
+}
+
+
 export class SweepFeature extends Feature {
   constructor(document, real) {
     super(document, real, true, 'Sweep', 'edit', {
-      profile: {
+      profiles: {
         title: 'Profile',
         type: 'profile',
+        multi: true,
       },
       rail: {
         title: 'Rail',
         type: 'curve',
+        multi: true,
+        autoMulti: true,
       },
-      bounds: {
-        title: 'Bounds',
-        type: 'bounds',
+      frenet: {
+        title: 'Follow Rail',
+        type: 'bool',
+        icons: ['route', 'lock']
       },
     })
 
-    this.profile = null
+    this.profiles = null
     this.rail = null
-    this.bounds = [0.0, 1.0]
+    this.frenet = true
   }
 
   isComplete() {
-    return this.profile && this.rail
+    return this.profiles && this.profiles().length && this.rail && this.rail().length
   }
 
-  preview() {
-    this.profile.noFree = true
-    return this.profile.extrude_preview(1.0)
+  updateFeature() {
+    const list = new window.alcWasm.JsProfileRefList()
+    this.profiles().forEach(profile => {
+      list.push(profile)
+    })
+    const path = new window.alcWasm.JsCurveRefList()
+    this.rail().forEach(curve => {
+      path.push(curve)
+    })
+    const comp_ref = this.document.activeComponent.id
+    this.real.sweep(comp_ref, list, path, this.frenet, this.operation)
   }
+}
 
-  confirm() {
-    this.profile.extrude(1.0)
+
+export class LoftFeature extends Feature {
+  constructor(document, real) {
+    super(document, real, true, 'Loft', 'layer-group', {
+      profiles: {
+        title: 'Profiles',
+        type: 'profile',
+        multi: true,
+        autoMulti: true,
+      },
+      rail: {
+        title: '(Rail)',
+        type: 'curve',
+        multi: true,
+        optional: true,
+      },
+      ruled: {
+        title: 'Ruled',
+        type: 'bool',
+        icons: ['wave-square', 'route']
+      },
+    })
+
+    this.profiles = null
+    this.rail = null
+    this.ruled = false
+  }
+
+  isComplete() {
+    return this.profiles && this.profiles().length >= 2
+  }
+
+  updateFeature() {
+    const list = new window.alcWasm.JsProfileRefList()
+    this.profiles().forEach(profile => {
+      list.push(profile)
+    })
+    const rail = new window.alcWasm.JsCurveRefList()
+    if(this.rail) this.rail().forEach(curve => {
+      rail.push(curve)
+    })
+    const comp_ref = this.document.activeComponent.id
+    this.real.loft(comp_ref, list, rail, this.ruled, this.operation)
   }
 }
 
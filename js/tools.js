@@ -216,6 +216,12 @@ export class FacePickTool extends PickTool {
   }
 }
 
+export class EdgePickTool extends PickTool {
+  constructor(component, viewport, callback) {
+    super(component, viewport, ['edge'], callback)
+  }
+}
+
 export class PlanePickTool extends PickTool {
   constructor(component, viewport, callback) {
     super(component, viewport, ['plane', 'face'], callback)
@@ -7,13 +7,29 @@ use crate::internal::*;
 use crate::transform::*;
 use crate::geom2d;
 use crate::wire::PolyLine;
-use crate::surface::SurfaceArea;
+use crate::surface::{SurfaceArea, SplineSurface};
 
 pub(crate) mod intersection;
 pub use intersection::CurveIntersection;
 pub use intersection::CurveIntersectionType;
 
 
+// Length error, relative to the control polygon, below which spline lengths stop being subdivided
+const LENGTH_TOLERANCE: f64 = 1.0e-12;
+
+// Upper limit for the number of times intervals of adaptive length integration get halved
+const MAX_LENGTH_SUBDIVISIONS: usize = 24;
+
+// Maximum number of Newton iterations used to find parameters at given lengths
+const LENGTH_ITERATIONS: usize = 30;
+
+// Quadrature segments per full turn, used to measure the length of ellipses
+const ELLIPSE_QUADRATURE_SEGMENTS: usize = 64;
+
+// Quintic pieces per full turn of the spline form of helices
+const HELIX_SPLINE_SEGMENTS: usize = 16;
+
+
 /// Base trait for all parametric curves.
 ///
 /// Curves are parametrized in the range 0-1.
@@ -148,6 +164,9 @@ macro_rules! dispatch_curve {
       Self::Arc(curve) => curve,
       Self::Circle(curve) => curve,
       Self::Spline(curve) => curve,
+      Self::Ellipse(curve) => curve,
+      Self::EllipticalArc(curve) => curve,
+      Self::Helix(curve) => curve,
     }
   };
   ($self:ident.$a:ident) => {
@@ -156,6 +175,9 @@ macro_rules! dispatch_curve {
       Self::Arc(curve) => curve.$a,
       Self::Circle(curve) => curve.$a,
       Self::Spline(curve) => curve.$a,
+      Self::Ellipse(curve) => curve.$a,
+      Self::EllipticalArc(curve) => curve.$a,
+      Self::Helix(curve) => curve.$a,
     }
   };
   ($self:ident.$a:ident = $b:expr) => {
@@ -164,6 +186,9 @@ macro_rules! dispatch_curve {
       Self::Arc(curve) => curve.$a = $b,
       Self::Circle(curve) => curve.$a = $b,
       Self::Spline(curve) => curve.$a = $b,
+      Self::Ellipse(curve) => curve.$a = $b,
+      Self::EllipticalArc(curve) => curve.$a = $b,
+      Self::Helix(curve) => curve.$a = $b,
     }
   };
   ($self:ident.$a:ident($b:expr)) => {
@@ -172,6 +197,9 @@ macro_rules! dispatch_curve {
       Self::Arc(curve) => curve.$a($b),
       Self::Circle(curve) => curve.$a($b),
       Self::Spline(curve) => curve.$a($b),
+      Self::Ellipse(curve) => curve.$a($b),
+      Self::EllipticalArc(curve) => curve.$a($b),
+      Self::Helix(curve) => curve.$a($b),
     }
   };
 }
@@ -187,6 +215,9 @@ pub enum CurveType {
   Arc(Arc),
   Circle(Circle),
   Spline(Spline),
+  Ellipse(Ellipse),
+  EllipticalArc(EllipticalArc),
+  Helix(Helix),
 }
 
 impl CurveType {
@@ -206,10 +237,31 @@ impl CurveType {
     dispatch_curve!(self.id = id)
   }
 
+  /// Exact derivative with respect to the curve parameter.
+  pub fn derivative(&self, t: f64) -> Vec3 {
+    let circular = |plane: &Plane, radius: f64, t: f64| {
+      let angle = t * std::f64::consts::PI * 2.0;
+      (plane.v * angle.cos() - plane.u * angle.sin()) * radius * std::f64::consts::PI * 2.0
+    };
+    match self {
+      Self::Line(line) => line.points.1 - line.points.0,
+      Self::Circle(circle) => circular(&circle.plane, circle.radius, t),
+      Self::Arc(arc) => circular(&arc.plane, arc.radius, arc.param_to_circle(t)) * arc.range(),
+      Self::Spline(spline) => spline.derivatives(t, 1)[1],
+      Self::Ellipse(ellipse) => ellipse.derivative(t),
+      Self::EllipticalArc(arc) => arc.derivative(t),
+      Self::Helix(helix) => helix.derivative(t),
+    }
+  }
+
   pub fn split(&self, cutter: &Self) -> Option<Vec<Self>> {
     dispatch_curve!(self.split_with(cutter))
   }
 
+  pub fn split_at_points(&self, points: &Vec<Point3>) -> Option<Vec<Self>> {
+    dispatch_curve!(self.split_at_points(points))
+  }
+
   pub fn split_multi(&self, others: &Vec<Self>) -> Vec<Self> {
     let mut segments = vec![self.clone()];
     for other in others.iter() {
@@ -302,6 +354,11 @@ impl TrimmedCurve {
     self.trims.0 + t * (self.trims.1 - self.trims.0)
   }
 
+  /// Exact derivative with respect to the trimmed parameter, following the direction of the bounds.
+  pub fn derivative(&self, t: f64) -> Vec3 {
+    self.base.derivative(self.param_to_base(t)) * (self.trims.1 - self.trims.0)
+  }
+
   pub fn param_from_base(&self, t_base: f64) -> f64 {
     (t_base - self.trims.0) / (self.trims.1 - self.trims.0)
   }
@@ -326,6 +383,15 @@ impl TrimmedCurve {
           self.convert_intersection(&mut isect, other);
           CurveIntersectionType::new(isect)
         },
+
+        CurveIntersectionType::Tangent(mut isect)
+        => {
+          self.convert_intersection(&mut isect, other);
+          match CurveIntersectionType::new(isect) {
+            CurveIntersectionType::Cross(isect) => CurveIntersectionType::Tangent(isect),
+            intersection => intersection,
+          }
+        },
       }
     }).collect()
   }
@@ -457,6 +523,9 @@ impl BasisCurve for Line {
       CurveType::Circle(circle) => intersection::line_circle(self, circle),
       CurveType::Arc(arc) => intersection::line_arc(self, arc),
       CurveType::Spline(spline) => intersection::line_spline(self, spline),
+      CurveType::Ellipse(ellipse) => intersection::line_ellipse(self, ellipse),
+      CurveType::EllipticalArc(arc) => intersection::line_elliptical_arc(self, arc),
+      CurveType::Helix(_) => intersection::curve_curve(&self.clone().into_enum(), other),
     }
   }
 
@@ -486,6 +555,20 @@ impl Transformable for Line {
 }
 
 
+// Offset closed curve params such that the produced arc parameters are in the 0-1 range
+// instead of wrapping into this range, for arcs with bounds < 0 or > 1
+fn unwrap_param(t: f64, bounds: (f64, f64)) -> f64 {
+  let ordered = sort_tuple2(bounds.0, bounds.1);
+  if ordered.1 > 1.0 && t <= ordered.1 % 1.0 + EPSILON {
+    t + 1.0
+  } else if ordered.0 < 0.0 && 1.0 - t <= ordered.0.abs() % 1.0 + EPSILON {
+    t - 1.0
+  } else {
+    t
+  }
+}
+
+
 /// Circular arc between two parameters on a [Circle].
 ///
 /// # Examples
@@ -565,6 +648,11 @@ impl Arc {
     self.bounds = (self.bounds.1 - 1.0, self.bounds.0);
   }
 
+  // Exact representation as a rational quadratic spline
+  pub fn to_spline(&self) -> Spline {
+    Ellipse::from_plane(self.plane.clone(), (self.radius, self.radius)).spline_between(self.bounds.0, self.bounds.1)
+  }
+
   // fn overflow(t: f64) -> f64 {
   //   if t > 1.0 {
   //     t % 1.0
@@ -585,17 +673,7 @@ impl Curve for Arc {
 
   fn unsample(&self, p: Point3) -> f64 {
     let circle = Circle::from_plane(self.plane.clone(), self.radius);
-    let mut t = circle.unsample(p);
-    // Offset circle param such that the produced arc parameters are in the 0-1 range
-    // instead of wrapping into this range, for arcs with bounds < 0 or > 1
-    let ordered = sort_tuple2(self.bounds.0, self.bounds.1);
-    t = if ordered.1 > 1.0 && t <= ordered.1 % 1.0 + EPSILON {
-      t + 1.0
-    } else if ordered.0 < 0.0 && 1.0 - t <= ordered.0.abs() % 1.0 + EPSILON {
-      t - 1.0
-    } else {
-      t
-    };
+    let t = unwrap_param(circle.unsample(p), self.bounds);
     self.param_from_circle(t)
   }
 
@@ -623,9 +701,10 @@ impl BasisCurve for Arc {
   fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
     match other {
       CurveType::Line(line) => invert_intersections(intersection::line_arc(line, self)),
-      CurveType::Circle(_circle) => vec![],
-      CurveType::Arc(_arc) => vec![],
-      CurveType::Spline(_spline) => vec![],
+      CurveType::Circle(circle) => invert_intersections(intersection::circle_arc(circle, self)),
+      CurveType::Arc(arc) => intersection::arc_arc(self, arc),
+      CurveType::Spline(spline) => intersection::arc_spline(self, spline),
+      CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => intersection::curve_curve(&self.clone().into_enum(), other),
     }
   }
 
@@ -762,9 +841,10 @@ impl BasisCurve for Circle {
   fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
     match other {
       CurveType::Line(line) => invert_intersections(intersection::line_circle(line, self)),
-      CurveType::Circle(_circle) => vec![],
-      CurveType::Arc(_arc) => vec![],
-      CurveType::Spline(_spline) => vec![],
+      CurveType::Circle(circle) => intersection::circle_circle(self, circle),
+      CurveType::Arc(arc) => intersection::circle_arc(self, arc),
+      CurveType::Spline(spline) => intersection::circle_spline(self, spline),
+      CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => intersection::curve_curve(&self.clone().into_enum(), other),
     }
   }
 
@@ -782,10 +862,10 @@ impl Splittable for Circle {
     if points.len() >= 2 {
       let mut params: Vec<f64> = points.iter().map(|p| self.unsample(*p) ).collect();
       params.sort_by(|a, b| a.partial_cmp(b).unwrap() );
-      let first_arc = Arc::new(self.plane.origin, self.radius, params[0], params[1]);
-      let second_arc = Arc::new(self.plane.origin, self.radius, params[1] - 1.0, params[0]);
+      let first_arc = Arc::from_plane(self.plane.clone(), self.radius, params[0], params[1]);
+      let second_arc = Arc::from_plane(self.plane.clone(), self.radius, params[1] - 1.0, params[0]);
       if points.len() > 2 {
-        let remaining_points = points.iter().skip(2).cloned().collect();
+        let remaining_points = params.iter().skip(2).map(|t| self.sample(*t) ).collect();
         let mut arcs = vec![first_arc.into_enum()];
         arcs.append(&mut second_arc.split_at_points(&remaining_points).unwrap());
         Some(arcs)
@@ -811,6 +891,548 @@ impl Transformable for Circle {
 }
 
 
+/// A full ellipse.
+///
+/// Its radii extend along the `u` and `v` axes of its plane.
+/// Cannot be split at a single parameter. Yields [EllipticalArc]s when split.
+/// Sampling the curve at parameters outside the 0-1 range will result in wrapping around the ellipse.
+///
+/// # Examples
+/// ```
+/// use shapex::*;
+///
+/// let ellipse = Ellipse::new(Point3::origin(), (2.0, 1.0));
+/// assert_eq!(ellipse.sample(0.0), Point3::new(2.0, 0.0, 0.0));
+/// assert!(ellipse.sample(0.5).almost(ellipse.sample(1.5)));
+/// ```
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ellipse {
+  pub id: Uuid,
+  pub plane: Plane,
+  pub radii: (f64, f64),
+}
+
+impl Ellipse {
+  pub fn new(center: Point3, radii: (f64, f64)) -> Self {
+    let plane = Plane::from_point(center);
+    Self::from_plane(plane, radii)
+  }
+
+  pub fn from_plane(plane: Plane, radii: (f64, f64)) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      plane,
+      radii,
+    }
+  }
+
+  pub fn derivative(&self, t: f64) -> Vec3 {
+    let angle = t * std::f64::consts::PI * 2.0;
+    (self.plane.u * (-angle.sin() * self.radii.0) + self.plane.v * (angle.cos() * self.radii.1)) * std::f64::consts::PI * 2.0
+  }
+
+  fn second_derivative(&self, t: f64) -> Vec3 {
+    let angle = t * std::f64::consts::PI * 2.0;
+    (self.plane.u * (angle.cos() * self.radii.0) + self.plane.v * (angle.sin() * self.radii.1)) * -(std::f64::consts::PI * 2.0).powi(2)
+  }
+
+  // Exact representation as a rational quadratic spline
+  pub fn to_spline(&self) -> Spline {
+    self.spline_between(0.0, 1.0)
+  }
+
+  // Rational quadratic spline between two parameters, using pieces of less than a quarter turn
+  fn spline_between(&self, start: f64, end: f64) -> Spline {
+    let n = ((end - start).abs() * 4.0).ceil().max(1.0) as usize;
+    let step = (end - start) / n as f64;
+    let weight = (step * std::f64::consts::PI).cos();
+    let mut controls = vec![self.sample(start)];
+    let mut weights = vec![1.0];
+    let mut knots = vec![0.0; 3];
+    for i in 0..n {
+      let middle = self.sample(start + step * (i as f64 + 0.5));
+      controls.push(self.plane.origin + (middle - self.plane.origin) / weight);
+      controls.push(self.sample(start + step * (i + 1) as f64));
+      weights.append(&mut vec![weight, 1.0]);
+      knots.append(&mut vec![(i + 1) as f64; 2]);
+    }
+    knots.push(n as f64);
+    Spline {
+      id: Uuid::new_v4(),
+      degree: 2,
+      controls,
+      knots,
+      weights,
+    }
+  }
+}
+
+impl Curve for Ellipse {
+  fn sample(&self, t: f64) -> Point3 {
+    let t = t * std::f64::consts::PI * 2.0;
+    self.plane.sample(t.cos() * self.radii.0, t.sin() * self.radii.1)
+  }
+
+  fn unsample(&self, p: Point3) -> f64 {
+    let Point2 { x, y } = self.plane.unsample(p);
+    let atan2 = (y / self.radii.1).atan2(x / self.radii.0) / std::f64::consts::PI / 2.0;
+    if atan2 < 0.0 {
+      1.0 + atan2
+    } else {
+      atan2
+    }
+  }
+
+  fn tangent_at(&self, t: f64) -> Vec3 {
+    self.derivative(t).normalize()
+  }
+
+  fn curvature_at(&self, t: f64) -> f64 {
+    let derivative = self.derivative(t);
+    derivative.cross(self.second_derivative(t)).magnitude() / derivative.magnitude().powi(3)
+  }
+
+  fn tesselate(&self) -> Vec<Point3> {
+    self.tesselate_fixed(80)
+  }
+
+  fn is_closed(&self) -> bool { true }
+
+  // Gauss-Legendre quadrature of the speed, which is smooth and periodic
+  fn length_between(&self, start: f64, end: f64) -> f64 {
+    let segments = ((end - start).abs() * ELLIPSE_QUADRATURE_SEGMENTS as f64).ceil().max(1.0) as usize;
+    quadrature(start, end, segments).map(|(t, weight)| self.derivative(t).magnitude() * weight ).sum::<f64>().abs()
+  }
+
+  fn endpoints(&self) -> (Point3, Point3) {
+    let zero = self.sample(0.0);
+    (zero, zero)
+  }
+}
+
+impl BasisCurve for Ellipse {
+  fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
+    match other {
+      CurveType::Line(line) => invert_intersections(intersection::line_ellipse(line, self)),
+      _ => intersection::curve_curve(&self.clone().into_enum(), other),
+    }
+  }
+
+  fn into_enum(self) -> CurveType {
+    CurveType::Ellipse(self)
+  }
+}
+
+impl Splittable for Ellipse {
+  fn split_at(&self, _t: f64) -> Option<(Self, Self)> {
+    None
+  }
+
+  fn split_at_points(&self, points: &Vec<Point3>) -> Option<Vec<CurveType>> {
+    if points.len() >= 2 {
+      let mut params: Vec<f64> = points.iter().map(|p| self.unsample(*p) ).collect();
+      params.sort_by(|a, b| a.partial_cmp(b).unwrap() );
+      let first_arc = EllipticalArc::from_plane(self.plane.clone(), self.radii, params[0], params[1]);
+      let second_arc = EllipticalArc::from_plane(self.plane.clone(), self.radii, params[1] - 1.0, params[0]);
+      if points.len() > 2 {
+        let remaining_points = params.iter().skip(2).map(|t| self.sample(*t) ).collect();
+        let mut arcs = vec![first_arc.into_enum()];
+        arcs.append(&mut second_arc.split_at_points(&remaining_points).unwrap());
+        Some(arcs)
+      } else {
+        Some(vec![first_arc.into_enum(), second_arc.into_enum()])
+      }
+    } else {
+      None
+    }
+  }
+}
+
+impl SurfaceArea for Ellipse {
+  fn area(&self) -> f64 {
+    std::f64::consts::PI * self.radii.0 * self.radii.1
+  }
+}
+
+impl Transformable for Ellipse {
+  fn transform(&mut self, transform: &Matrix4) {
+    self.plane.transform(transform);
+  }
+}
+
+
+/// Elliptical arc between two parameters on an [Ellipse].
+///
+/// # Examples
+/// ```
+/// use shapex::*;
+///
+/// let arc = EllipticalArc::new(Point3::origin(), (2.0, 1.0), 0.0, 0.5);
+/// assert_eq!(arc.sample(0.0), Point3::new(2.0, 0.0, 0.0));
+/// ```
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EllipticalArc {
+  pub id: Uuid,
+  pub plane: Plane,
+  pub radii: (f64, f64),
+  pub bounds: (f64, f64),
+}
+
+impl EllipticalArc {
+  pub fn new(center: Point3, radii: (f64, f64), start: f64, end: f64) -> Self {
+    let plane = Plane::from_point(center);
+    Self::from_plane(plane, radii, start, end)
+  }
+
+  pub fn from_plane(plane: Plane, radii: (f64, f64), start: f64, end: f64) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      plane,
+      radii,
+      bounds: (start, end),
+    }
+  }
+
+  pub fn ellipse(&self) -> Ellipse {
+    Ellipse::from_plane(self.plane.clone(), self.radii)
+  }
+
+  pub fn range(&self) -> f64 {
+    self.bounds.1 - self.bounds.0
+  }
+
+  pub fn param_to_ellipse(&self, t: f64) -> f64 {
+    self.bounds.0 + t * self.range()
+  }
+
+  pub fn param_from_ellipse(&self, t: f64) -> f64 {
+    let mut range = self.range();
+    if range.almost(0.0) {
+      range = 1.0;
+    }
+    (t - self.bounds.0) / range
+  }
+
+  pub fn derivative(&self, t: f64) -> Vec3 {
+    self.ellipse().derivative(self.param_to_ellipse(t)) * self.range()
+  }
+
+  // Exact representation as a rational quadratic spline
+  pub fn to_spline(&self) -> Spline {
+    self.ellipse().spline_between(self.bounds.0, self.bounds.1)
+  }
+
+  pub fn invert(&mut self) {
+    self.bounds = (self.bounds.1, self.bounds.0);
+  }
+}
+
+impl Curve for EllipticalArc {
+  fn sample(&self, t: f64) -> Point3 {
+    self.ellipse().sample(self.param_to_ellipse(t))
+  }
+
+  fn unsample(&self, p: Point3) -> f64 {
+    let t = unwrap_param(self.ellipse().unsample(p), self.bounds);
+    self.param_from_ellipse(t)
+  }
+
+  fn tangent_at(&self, t: f64) -> Vec3 {
+    self.derivative(t).normalize()
+  }
+
+  fn curvature_at(&self, t: f64) -> f64 {
+    self.ellipse().curvature_at(self.param_to_ellipse(t))
+  }
+
+  fn tesselate(&self) -> Vec<Point3> {
+    self.tesselate_fixed((80.0 * self.range().abs()).ceil() as u32)
+  }
+
+  fn length_between(&self, start: f64, end: f64) -> f64 {
+    self.ellipse().length_between(self.param_to_ellipse(start), self.param_to_ellipse(end))
+  }
+}
+
+impl BasisCurve for EllipticalArc {
+  fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
+    match other {
+      CurveType::Line(line) => invert_intersections(intersection::line_elliptical_arc(line, self)),
+      _ => intersection::curve_curve(&self.clone().into_enum(), other),
+    }
+  }
+
+  fn into_enum(self) -> CurveType {
+    CurveType::EllipticalArc(self)
+  }
+}
+
+impl Splittable for EllipticalArc {
+  fn split_at(&self, t: f64) -> Option<(Self, Self)> {
+    if EPSILON < t && t < 1.0 - EPSILON {
+      let t = self.param_to_ellipse(t);
+      Some((
+        Self::from_plane(self.plane.clone(), self.radii, self.bounds.0, t),
+        Self::from_plane(self.plane.clone(), self.radii, t, self.bounds.1),
+      ))
+    } else {
+      None
+    }
+  }
+}
+
+impl Transformable for EllipticalArc {
+  fn transform(&mut self, transform: &Matrix4) {
+    self.plane.transform(transform);
+  }
+}
+
+
+/// Helical curve winding around an axis, as used for springs and threads.
+///
+/// The helix starts at `radius` from the origin of its plane, in direction of the plane's `u` axis,
+/// and rises along the plane's normal by `pitch` for every turn.
+/// Right-handed helices wind counter-clockwise around the normal, left-handed helices clockwise.
+/// Positive taper angles move the helix towards its axis as it rises.
+///
+/// # Examples
+/// ```
+/// use shapex::*;
+///
+/// let axis = Axis::new(Point3::origin(), Vec3::new(0.0, 0.0, 1.0));
+/// let helix = Helix::new(&axis, 1.0, 0.5, 4.0);
+/// assert_eq!(helix.sample(0.0), Point3::new(1.0, 0.0, 0.0));
+/// assert!(helix.sample(1.0).almost(Point3::new(1.0, 0.0, 2.0)));
+/// ```
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Helix {
+  pub id: Uuid,
+  pub plane: Plane,
+  pub radius: f64,
+  pub pitch: f64,
+  pub turns: f64,
+  pub right_handed: bool,
+  pub taper: Deg<f64>,
+}
+
+impl Helix {
+  pub fn new(axis: &Axis, radius: f64, pitch: f64, turns: f64) -> Self {
+    Self::from_plane(Plane::from_normal(axis.origin, axis.direction), radius, pitch, turns)
+  }
+
+  pub fn from_plane(plane: Plane, radius: f64, pitch: f64, turns: f64) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      plane,
+      radius,
+      pitch,
+      turns,
+      right_handed: true,
+      taper: Deg(0.0),
+    }
+  }
+
+  pub fn axis(&self) -> Axis {
+    Axis::from(&self.plane)
+  }
+
+  pub fn height(&self) -> f64 {
+    self.pitch * self.turns
+  }
+
+  pub fn height_at(&self, t: f64) -> f64 {
+    self.height() * t
+  }
+
+  pub fn radius_at(&self, t: f64) -> f64 {
+    self.radius + self.radius_slope() * self.height_at(t)
+  }
+
+  // Change of radius per unit of height
+  fn radius_slope(&self) -> f64 {
+    -Rad::from(self.taper).0.tan()
+  }
+
+  // Total angle swept, signed by handedness
+  fn angle(&self) -> f64 {
+    let angle = self.turns * std::f64::consts::PI * 2.0;
+    if self.right_handed { angle } else { -angle }
+  }
+
+  /// Point at the angle of parameter `t`, with the given distance from the axis and height along it.
+  pub fn sample_around(&self, t: f64, radius: f64, height: f64) -> Point3 {
+    let angle = self.angle() * t;
+    self.plane.sample(angle.cos() * radius, angle.sin() * radius) + self.plane.normal() * height
+  }
+
+  pub fn derivative(&self, t: f64) -> Vec3 {
+    self.derivatives(t)[0]
+  }
+
+  // First, second and third derivative
+  // With radial direction e and its perpendicular f, the curve is e * r(t) + n * h * t
+  fn derivatives(&self, t: f64) -> [Vec3; 3] {
+    let angle = self.angle();
+    let (sin, cos) = (angle * t).sin_cos();
+    let radial = self.plane.u * cos + self.plane.v * sin;
+    let perpendicular = self.plane.v * cos - self.plane.u * sin;
+    let radius = self.radius_at(t);
+    let rise = self.height();
+    let growth = self.radius_slope() * rise;
+    [
+      perpendicular * (angle * radius) + radial * growth + self.plane.normal() * rise,
+      perpendicular * (2.0 * angle * growth) - radial * (angle * angle * radius),
+      -perpendicular * (angle.powi(3) * radius) - radial * (3.0 * angle * angle * growth),
+    ]
+  }
+
+  // Rate at which the curve twists out of its osculating plane
+  pub fn torsion_at(&self, t: f64) -> f64 {
+    let derivatives = self.derivatives(t);
+    let binormal = derivatives[0].cross(derivatives[1]);
+    let denominator = binormal.magnitude2();
+    if denominator.almost(0.0) { return 0.0 }
+    binormal.dot(derivatives[2]) / denominator
+  }
+
+  // Antiderivative of the speed over the radius of tapered helices
+  fn length_integral(&self, radius: f64) -> f64 {
+    let angle = self.angle().abs();
+    let constant = self.height().powi(2) * (1.0 + self.radius_slope().powi(2));
+    let root = (angle * angle * radius * radius + constant).sqrt();
+    (radius * root + constant / angle * (angle * radius / constant.sqrt()).asinh()) / 2.0
+  }
+
+  // Quintic spline matching the position and first two derivatives of the helix at evenly spaced parameters
+  pub fn to_spline(&self) -> Spline {
+    let n = (HELIX_SPLINE_SEGMENTS as f64 * self.turns.abs()).ceil().max(1.0) as usize;
+    let scale = n as f64;
+    let mut controls = vec![self.sample(0.0)];
+    let mut knots = vec![0.0; 6];
+    for i in 0..n {
+      let (start, end) = (i as f64 / scale, (i + 1) as f64 / scale);
+      let (p0, p1) = (self.sample(start), self.sample(end));
+      let ([d0, dd0, _], [d1, dd1, _]) = (self.derivatives(start), self.derivatives(end));
+      controls.push(p0 + d0 / (5.0 * scale));
+      controls.push(p0 + d0 * 2.0 / (5.0 * scale) + dd0 / (20.0 * scale * scale));
+      controls.push(p1 - d1 * 2.0 / (5.0 * scale) + dd1 / (20.0 * scale * scale));
+      controls.push(p1 - d1 / (5.0 * scale));
+      controls.push(p1);
+      knots.append(&mut vec![(i + 1) as f64; 5]);
+    }
+    knots.push(scale);
+    Spline {
+      id: Uuid::new_v4(),
+      degree: 5,
+      weights: vec![1.0; controls.len()],
+      controls,
+      knots,
+    }
+  }
+
+  // Piece of the helix between two parameters
+  pub(crate) fn section(&self, start: f64, end: f64) -> Self {
+    let origin = self.sample_around(start, 0.0, self.height_at(start));
+    let u = (self.sample_around(start, 1.0, 0.0) - self.plane.origin).normalize();
+    let normal = self.plane.normal();
+    let mut section = self.clone();
+    section.id = Uuid::new_v4();
+    section.plane = Plane { origin, u, v: normal.cross(u) };
+    section.radius = self.radius_at(start);
+    section.turns = self.turns * (end - start);
+    section
+  }
+}
+
+impl Curve for Helix {
+  fn sample(&self, t: f64) -> Point3 {
+    self.sample_around(t, self.radius_at(t), self.height_at(t))
+  }
+
+  fn unsample(&self, p: Point3) -> f64 {
+    let angle = self.angle();
+    if angle.almost(0.0) { return 0.0 }
+    let Point2 { x, y } = self.plane.unsample(p);
+    let phase = y.atan2(x);
+    if self.height().almost(0.0) {
+      return (phase / angle).rem_euclid(1.0)
+    }
+    // Height determines the turn the point lies on
+    let t = (p - self.plane.origin).dot(self.plane.normal()) / self.height();
+    let turn = ((angle * t - phase) / (std::f64::consts::PI * 2.0)).round();
+    (phase + turn * std::f64::consts::PI * 2.0) / angle
+  }
+
+  fn tangent_at(&self, t: f64) -> Vec3 {
+    self.derivative(t).normalize()
+  }
+
+  fn curvature_at(&self, t: f64) -> f64 {
+    let derivatives = self.derivatives(t);
+    derivatives[0].cross(derivatives[1]).magnitude() / derivatives[0].magnitude().powi(3)
+  }
+
+  fn tesselate(&self) -> Vec<Point3> {
+    self.tesselate_fixed((80.0 * self.turns.abs()).ceil().max(1.0) as u32)
+  }
+
+  fn length_between(&self, start: f64, end: f64) -> f64 {
+    let growth = self.radius_slope() * self.height();
+    if growth.almost(0.0) || self.angle().almost(0.0) {
+      // Cylindrical helices run at constant speed
+      self.derivative(start).magnitude() * (end - start).abs()
+    } else {
+      ((self.length_integral(self.radius_at(end)) - self.length_integral(self.radius_at(start))) / growth).abs()
+    }
+  }
+
+  fn param_at_length(&self, length: f64) -> f64 {
+    let total = self.length();
+    if total.almost(0.0) { return 0.0 }
+    let tolerance = total * LENGTH_TOLERANCE;
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut t = (length / total).clamp(0.0, 1.0);
+    for _ in 0..LENGTH_ITERATIONS {
+      let error = self.length_between(0.0, t) - length;
+      if error.abs() <= tolerance { break }
+      if error > 0.0 { high = t } else { low = t }
+      let next = t - error / self.derivative(t).magnitude();
+      t = if next > low && next < high { next } else { (low + high) / 2.0 };
+    }
+    t
+  }
+}
+
+impl BasisCurve for Helix {
+  fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
+    intersection::curve_curve(&self.clone().into_enum(), other)
+  }
+
+  fn into_enum(self) -> CurveType {
+    CurveType::Helix(self)
+  }
+}
+
+impl Splittable for Helix {
+  fn split_at(&self, t: f64) -> Option<(Self, Self)> {
+    if EPSILON < t && t < 1.0 - EPSILON {
+      Some((self.section(0.0, t), self.section(t, 1.0)))
+    } else {
+      None
+    }
+  }
+}
+
+impl Transformable for Helix {
+  fn transform(&mut self, transform: &Matrix4) {
+    self.plane.transform(transform);
+  }
+}
+
+
 /// Non-uniform rational basis spline.
 ///
 /// The shape of the curve is determined by its degree and a set of weighted control vertices, as well as a knot vector,
@@ -863,8 +1485,7 @@ impl Spline {
     (0..num_knots).map(|i| i as f64 ).collect()
   }
 
-  #[allow(dead_code)]
-  fn clamped_knots(n: usize, degree: usize) -> Vec<f64> {
+  pub(crate) fn clamped_knots(n: usize, degree: usize) -> Vec<f64> {
     if degree >= n { return vec![] }
     let d = degree + 1;
     [
@@ -874,33 +1495,180 @@ impl Spline {
     ].concat()
   }
 
-  fn real_split(&self, t: f64, controls: &[Point3], left: &mut Vec<Point3>, right: &mut Vec<Point3>) -> Point3 {
-    if controls.len() == 1 {
-      let p = controls[0];
-      left.push(p);
-      right.push(p);
-      p
-    } else {
-      let len = controls.len() - 1;
-      let mut new_controls: Vec<Point3> = vec![];
-      for i in 0..len {
-        if i == 0 { left.push(controls[i]) }
-        if i == len - 1 { right.push(controls[i + 1]) }
-        new_controls.push(controls[i] * (1.0 - t) + (controls[i + 1] * t).to_vec());
+  // Insert a knot at the given actual parameter without changing the shape of the curve (Boehm)
+  pub(crate) fn insert_knot(&mut self, x: f64) {
+    let p = self.degree;
+    let n = self.controls.len();
+    let span = (p..n).find(|&i| x < self.knots[i + 1] ).unwrap_or(n - 1);
+    let homogeneous: Vec<Vec4> = self.controls.iter().zip(&self.weights).map(|(c, w)| (c.to_vec() * *w).extend(*w) ).collect();
+    let inserted: Vec<Vec4> = (0..=n).map(|i| {
+      if i + p <= span {
+        homogeneous[i]
+      } else if i > span {
+        homogeneous[i - 1]
+      } else {
+        let alpha = (x - self.knots[i]) / (self.knots[i + p] - self.knots[i]);
+        homogeneous[i] * alpha + homogeneous[i - 1] * (1.0 - alpha)
+      }
+    }).collect();
+    self.knots.insert(span + 1, x);
+    self.controls = inserted.iter().map(|h| Point3::from_vec(h.truncate() / h.w) ).collect();
+    self.weights = inserted.iter().map(|h| h.w ).collect();
+  }
+
+  // Same curve, traversed in the opposite direction
+  pub(crate) fn reverse(&mut self) {
+    let (first, last) = (self.knots[0], *self.knots.last().unwrap());
+    self.controls.reverse();
+    self.weights.reverse();
+    self.knots = self.knots.iter().rev().map(|knot| first + last - knot ).collect();
+  }
+
+  // Raise the degree by one without changing the shape of the curve
+  // The curve is decomposed into Bézier segments, which are elevated separately and stay joined at their ends
+  pub(crate) fn elevate_degree(&mut self) {
+    let p = self.degree;
+    let (start, end) = (self.knots[p], self.knots[self.controls.len()]);
+    let mut interior: Vec<f64> = self.knots.iter().copied().filter(|&knot| knot > start && knot < end ).collect();
+    interior.dedup();
+    for &x in &interior {
+      let multiplicity = self.knots.iter().filter(|&&knot| knot == x ).count();
+      for _ in multiplicity..p {
+        self.insert_knot(x);
+      }
+    }
+    let homogeneous: Vec<Vec4> = self.controls.iter().zip(&self.weights).map(|(c, w)| (c.to_vec() * *w).extend(*w) ).collect();
+    let mut elevated = vec![homogeneous[0]];
+    for bezier in homogeneous.windows(p + 1).step_by(p) {
+      for i in 1..=p {
+        let alpha = i as f64 / (p + 1) as f64;
+        elevated.push(bezier[i - 1] * alpha + bezier[i] * (1.0 - alpha));
+      }
+      elevated.push(bezier[p]);
+    }
+    self.degree = p + 1;
+    self.knots = [
+      vec![start; p + 2],
+      interior.iter().flat_map(|&x| vec![x; p + 1] ).collect(),
+      vec![end; p + 2],
+    ].concat();
+    self.controls = elevated.iter().map(|h| Point3::from_vec(h.truncate() / h.w) ).collect();
+    self.weights = elevated.iter().map(|h| h.w ).collect();
+  }
+
+  // Bring splines to a common degree and knot vector, without changing their shapes
+  pub(crate) fn make_compatible(splines: &mut [Self]) {
+    let degree = splines.iter().map(|spline| spline.degree ).max().unwrap();
+    for spline in splines.iter_mut() {
+      while spline.degree < degree {
+        spline.elevate_degree();
       }
-      self.real_split(t, &new_controls, left, right)
+      let (start, end) = (spline.knots[degree], spline.knots[spline.controls.len()]);
+      spline.knots = spline.knots.iter().map(|knot| (knot - start) / (end - start) ).collect();
+    }
+    // Union of all knot vectors, keeping the highest multiplicity of each knot
+    let multiplicity = |knots: &[f64], x: f64| knots.iter().filter(|knot| knot.almost(x) ).count();
+    let mut knots: Vec<f64> = vec![];
+    for spline in splines.iter() {
+      for &x in &spline.knots {
+        if multiplicity(&spline.knots, x) > multiplicity(&knots, x) {
+          knots.push(x);
+        }
+      }
+    }
+    knots.sort_by(|a, b| a.partial_cmp(b).unwrap() );
+    for spline in splines.iter_mut() {
+      let mut distinct = knots.clone();
+      distinct.dedup_by(|a, b| a.almost(*b) );
+      for x in distinct {
+        for _ in multiplicity(&spline.knots, x)..multiplicity(&knots, x) {
+          spline.insert_knot(x);
+        }
+      }
+      spline.knots = knots.clone();
+    }
+  }
+
+  // Spline made from a range of controls and knots, with knots shifted to start at zero
+  fn section(&self, controls: std::ops::Range<usize>, knots: Vec<f64>) -> Self {
+    let start = knots[0];
+    Self {
+      id: Uuid::new_v4(),
+      degree: self.degree,
+      controls: self.controls[controls.clone()].to_vec(),
+      knots: knots.iter().map(|knot| knot - start ).collect(),
+      weights: self.weights[controls].to_vec(),
     }
   }
 
+  // Derivatives of the curve up to the given order, starting with the position itself
+  // Derivatives of the homogeneous curve are divided by the weight using the quotient rule
+  pub fn derivatives(&self, t: f64, order: usize) -> Vec<Vec3> {
+    let basis = SplineSurface::get_basis_derivatives(self.degree, t, &self.knots, order);
+    let homogeneous: Vec<Vec4> = basis.iter().map(|row| {
+      row.iter().zip(&self.controls).zip(&self.weights).fold(Vec4::zero(), |acc, ((b, p), w)| acc + (p.to_vec() * *w).extend(*w) * *b )
+    }).collect();
+    let mut derivatives: Vec<Vec3> = vec![];
+    for k in 0..=order {
+      let correction = (1..=k).fold(Vec3::zero(), |acc, i| acc + derivatives[k - i] * (binomial(k, i) * homogeneous[i].w) );
+      derivatives.push((homogeneous[k].truncate() - correction) / homogeneous[0].w);
+    }
+    derivatives
+  }
+
   // https://stackoverflow.com/questions/25453159/getting-consistent-normals-from-a-3d-cubic-bezier-path
   pub fn normal(&self, t: f64) -> Vec3 {
-    let derivative = self.derive();
-    let tan = derivative.sample(t).to_vec().normalize();
-    let tan2 = (tan + derivative.derive().sample(t).to_vec()).normalize();
+    let derivatives = self.derivatives(t, 2);
+    let tan = derivatives[1].normalize();
+    let tan2 = (tan + derivatives[2]).normalize();
     let c = tan2.cross(tan);
     c.cross(tan).normalize()
   }
 
+  // Rate at which the curve twists out of its osculating plane
+  pub fn torsion_at(&self, t: f64) -> f64 {
+    let derivatives = self.derivatives(t, 3);
+    let binormal = derivatives[1].cross(derivatives[2]);
+    let denominator = binormal.magnitude2();
+    if denominator.almost(0.0) { return 0.0 }
+    binormal.dot(derivatives[3]) / denominator
+  }
+
+  // Normalized parameters of the knots that lie between start and end, including both
+  fn breakpoints(&self, start: f64, end: f64) -> Vec<f64> {
+    let n = self.controls.len();
+    let low = self.knots[self.degree];
+    let high = self.knots[n];
+    let mut params = vec![start];
+    for knot in &self.knots[self.degree + 1..n] {
+      let t = (knot - low) / (high - low);
+      if t > *params.last().unwrap() && t < end { params.push(t) }
+    }
+    params.push(end);
+    params
+  }
+
+  fn speed_integral(&self, start: f64, end: f64) -> f64 {
+    quadrature(start, end, 1).map(|(t, weight)| self.derivatives(t, 1)[1].magnitude() * weight ).sum()
+  }
+
+  // Adaptive Gauss-Legendre quadrature, halving intervals until both halves agree with their estimate
+  fn integrate_length(&self, start: f64, end: f64, estimate: f64, tolerance: f64, depth: usize) -> f64 {
+    let middle = (start + end) / 2.0;
+    let left = self.speed_integral(start, middle);
+    let right = self.speed_integral(middle, end);
+    if (left + right - estimate).abs() <= tolerance || depth == MAX_LENGTH_SUBDIVISIONS {
+      left + right
+    } else {
+      self.integrate_length(start, middle, left, tolerance / 2.0, depth + 1) +
+      self.integrate_length(middle, end, right, tolerance / 2.0, depth + 1)
+    }
+  }
+
+  fn control_polygon_length(&self) -> f64 {
+    self.controls.windows(2).map(|pair| pair[0].distance(pair[1]) ).sum()
+  }
+
   pub fn derive(&self) -> Self {
     let len = self.controls.len() - 1;
     let controls = (0..len).map(|i| (self.controls[i + 1] - self.controls[i].to_vec()) * len as f64 ).collect();
@@ -910,6 +1678,8 @@ impl Spline {
   fn unsample_recursive(&self, sample1: (f64, f64), sample2: (f64, f64), target: Point3) -> f64 {
     if sample1.0 == sample2.0 { return sample1.0 }
     let t_center = (sample1.0 + sample2.0) / 2.0;
+    // Neighboring floats can't be bisected any further
+    if t_center == sample1.0 || t_center == sample2.0 { return t_center }
     let p_center = self.sample(t_center);
     let dist_center = p_center.distance2(target);
     if p_center == target { return t_center }
@@ -953,24 +1723,45 @@ impl Curve for Spline {
   }
 
   fn tangent_at(&self, t: f64) -> Vec3 {
-    self.derive().sample(t).to_vec().normalize()
+    self.derivatives(t, 1)[1].normalize()
   }
 
-  fn curvature_at(&self, _t: f64) -> f64 {
-    todo!()
+  fn curvature_at(&self, t: f64) -> f64 {
+    let derivatives = self.derivatives(t, 2);
+    let speed = derivatives[1].magnitude();
+    if speed.almost(0.0) { return 0.0 }
+    derivatives[1].cross(derivatives[2]).magnitude() / speed.powi(3)
   }
 
   fn tesselate(&self) -> Vec<Point3> {
     self.tesselate_adaptive(0.025, Deg(20.0), (0.0, 1.0))
   }
 
-  fn length_between(&self, start: f64, end: f64) -> f64 { //XXX Replace with proper solution
-    let mut last_p = self.sample(start);
-    self.tesselate_adaptive(0.025, Deg(20.0), (start, end)).iter().fold(0.0, |acc, p| {
-      let dist = last_p.distance(*p);
-      last_p = *p;
-      acc + dist
-    })
+  // Integrated separately for each knot span, as the speed is only smooth within spans
+  fn length_between(&self, start: f64, end: f64) -> f64 {
+    let (start, end) = sort_tuple2(start, end);
+    let tolerance = self.control_polygon_length() * LENGTH_TOLERANCE;
+    self.breakpoints(start, end).windows(2).map(|span| {
+      let estimate = self.speed_integral(span[0], span[1]);
+      self.integrate_length(span[0], span[1], estimate, tolerance, 0)
+    }).sum()
+  }
+
+  // Newton iteration on the arc length function, falling back to bisection when leaving the bracket
+  fn param_at_length(&self, length: f64) -> f64 {
+    let total = self.length();
+    if total.almost(0.0) { return 0.0 }
+    let tolerance = self.control_polygon_length() * LENGTH_TOLERANCE;
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut t = (length / total).clamp(0.0, 1.0);
+    for _ in 0..LENGTH_ITERATIONS {
+      let error = self.length_between(0.0, t) - length;
+      if error.abs() <= tolerance { break }
+      if error > 0.0 { high = t } else { low = t }
+      let next = t - error / self.derivatives(t, 1)[1].magnitude();
+      t = if next > low && next < high { next } else { (low + high) / 2.0 };
+    }
+    t
   }
 
   fn endpoints(&self) -> (Point3, Point3) {
@@ -982,9 +1773,10 @@ impl BasisCurve for Spline {
   fn intersect(&self, other: &CurveType) -> Vec<CurveIntersectionType> {
     match other {
       CurveType::Line(line) => invert_intersections(intersection::line_spline(line, self)),
-      CurveType::Circle(_circle) => vec![],
-      CurveType::Arc(_arc) => vec![],
-      CurveType::Spline(_spline) => vec![],
+      CurveType::Circle(circle) => invert_intersections(intersection::circle_spline(circle, self)),
+      CurveType::Arc(arc) => invert_intersections(intersection::arc_spline(arc, self)),
+      CurveType::Spline(spline) => intersection::spline_spline(self, spline),
+      CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => intersection::curve_curve(&self.clone().into_enum(), other),
     }
   }
 
@@ -996,10 +1788,20 @@ impl BasisCurve for Spline {
 impl Splittable for Spline {
   fn split_at(&self, t: f64) -> Option<(Self, Self)> {
     if t.almost(0.0) || t.almost(1.0) { return None }
-    let mut left = vec![];
-    let mut right = vec![];
-    self.real_split(t, &self.controls, &mut left, &mut right);
-    Some((Self::new(left), Self::new(right)))
+    // Raise the multiplicity of the knot at t to the degree, which makes the curve pass through a control
+    let n = self.controls.len();
+    let x = self.knots[self.degree] + t * (self.knots[n] - self.knots[self.degree]);
+    let mut curve = self.clone();
+    let multiplicity = self.knots.iter().filter(|knot| **knot == x ).count();
+    for _ in multiplicity..self.degree {
+      curve.insert_knot(x);
+    }
+    let s = curve.knots.iter().position(|knot| *knot == x ).unwrap();
+    let p = self.degree;
+    Some((
+      curve.section(0..s, [&curve.knots[..s + p], &[x]].concat()),
+      curve.section(s - 1..curve.controls.len(), [&[x], &curve.knots[s..]].concat()),
+    ))
   }
 }
 
@@ -1157,4 +1959,300 @@ mod tests {
     almost_eq!(0.0, arc.unsample(arc.sample(0.0)));
     almost_eq!(1.0, arc.unsample(arc.sample(1.0)));
   }
+
+  fn quarter_circle() -> Spline {
+    let mut spline = Spline::new(vec![Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0)]);
+    spline.weights = vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0];
+    spline
+  }
+
+  #[test]
+  fn rational_spline() {
+    let spline = quarter_circle();
+    for i in 0..=10 {
+      almost_eq!(spline.sample(i as f64 / 10.0).to_vec().magnitude(), 1.0);
+    }
+    almost_eq!(spline.sample(0.5), Point3::new(std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2, 0.0));
+    almost_eq!(spline.tangent_at(0.0), Vec3::unit_y());
+    almost_eq!(spline.tangent_at(1.0), -Vec3::unit_x());
+    // Second derivative of the quotient matches finite differences of the first
+    let h = 1.0e-6;
+    let derivatives = spline.derivatives(0.3, 2);
+    assert!(derivatives[1].distance((spline.sample(0.3 + h) - spline.sample(0.3 - h)) / (2.0 * h)) < 1.0e-6);
+    assert!(derivatives[2].distance((spline.derivatives(0.3 + h, 1)[1] - spline.derivatives(0.3 - h, 1)[1]) / (2.0 * h)) < 1.0e-4);
+  }
+
+  #[test]
+  fn split_rational_spline() {
+    let spline = quarter_circle();
+    let (left, right) = spline.split_at(0.5).unwrap();
+    almost_eq!(left.sample(1.0), spline.sample(0.5));
+    almost_eq!(right.sample(0.0), spline.sample(0.5));
+    for i in 0..=10 {
+      let t = i as f64 / 10.0;
+      almost_eq!(left.sample(t).to_vec().magnitude(), 1.0);
+      almost_eq!(right.sample(t), spline.sample(0.5 + t / 2.0));
+    }
+  }
+
+  #[test]
+  fn split_multi_span_spline() {
+    let mut spline = Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(2.0, -1.0, 1.0),
+      Point3::new(3.0, 1.0, 0.0),
+      Point3::new(4.0, 0.0, 2.0),
+    ]);
+    spline.degree = 2;
+    spline.knots = Spline::clamped_knots(5, 2);
+    spline.weights = vec![1.0, 2.0, 0.5, 1.0, 1.0];
+    // Split both inside a span and at an existing knot
+    for t in [0.4, 1.0 / 3.0] {
+      let (left, right) = spline.split_at(t).unwrap();
+      assert_eq!(left.degree, 2);
+      assert_eq!(left.knots[0], 0.0);
+      assert_eq!(right.knots[0], 0.0);
+      for i in 0..=10 {
+        let s = i as f64 / 10.0;
+        almost_eq!(left.sample(s), spline.sample(s * t));
+        almost_eq!(right.sample(s), spline.sample(t + s * (1.0 - t)));
+      }
+    }
+  }
+
+  #[test]
+  fn elevate_spline_degree() {
+    let mut spline = Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(2.0, -1.0, 1.0),
+      Point3::new(3.0, 1.0, 0.0),
+      Point3::new(4.0, 0.0, 2.0),
+    ]);
+    spline.degree = 2;
+    spline.knots = Spline::clamped_knots(5, 2);
+    spline.weights = vec![1.0, 2.0, 0.5, 1.0, 1.0];
+    let mut elevated = spline.clone();
+    elevated.elevate_degree();
+    assert_eq!(elevated.degree, 3);
+    assert_eq!(elevated.knots.len(), elevated.controls.len() + 4);
+    for i in 0..=20 {
+      let t = i as f64 / 20.0;
+      almost_eq!(elevated.sample(t), spline.sample(t));
+    }
+  }
+
+  #[test]
+  fn compatible_splines() {
+    let arc = Arc::new(Point3::origin(), 1.0, 0.0, 0.6);
+    let line = Spline::new(vec![Point3::new(2.0, 0.0, 1.0), Point3::new(0.0, 2.0, 1.0)]);
+    let mut splines = [arc.to_spline(), line.clone()];
+    Spline::make_compatible(&mut splines);
+    assert_eq!(splines[0].degree, splines[1].degree);
+    assert_eq!(splines[0].knots, splines[1].knots);
+    assert_eq!(splines[0].controls.len(), splines[1].controls.len());
+    for i in 0..=20 {
+      let t = i as f64 / 20.0;
+      let p = splines[0].sample(t);
+      almost_eq!(p, arc.sample(arc.unsample(p)));
+      almost_eq!(splines[1].sample(t), line.sample(t));
+    }
+    almost_eq!(splines[0].sample(1.0), arc.sample(1.0));
+  }
+
+  #[test]
+  fn spline_curvature() {
+    let circle = quarter_circle();
+    for i in 0..=4 {
+      let t = i as f64 / 4.0;
+      almost_eq!(circle.curvature_at(t), 1.0);
+      almost_eq!(circle.torsion_at(t), 0.0);
+    }
+    // Twisted cubic (t, t², t³)
+    let cubic = Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0 / 3.0, 0.0, 0.0),
+      Point3::new(2.0 / 3.0, 1.0 / 3.0, 0.0),
+      Point3::new(1.0, 1.0, 1.0),
+    ]);
+    almost_eq!(cubic.curvature_at(0.0), 2.0);
+    almost_eq!(cubic.torsion_at(0.0), 3.0);
+    almost_eq!(cubic.torsion_at(0.5), 12.0 / 15.25);
+  }
+
+  #[test]
+  fn spline_length() {
+    let circle = quarter_circle();
+    almost_eq!(circle.length(), std::f64::consts::FRAC_PI_2);
+    almost_eq!(circle.length_between(0.5, 1.0), std::f64::consts::FRAC_PI_4);
+    almost_eq!(circle.param_at_length(std::f64::consts::FRAC_PI_4), 0.5);
+    let mut spline = Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(2.0, -1.0, 1.0),
+      Point3::new(3.0, 1.0, 0.0),
+      Point3::new(4.0, 0.0, 2.0),
+    ]);
+    spline.degree = 2;
+    spline.knots = Spline::clamped_knots(5, 2);
+    let polyline = spline.tesselate_fixed(20000);
+    let reference: f64 = polyline.windows(2).map(|pair| pair[0].distance(pair[1]) ).sum();
+    assert!((spline.length() - reference).abs() < 1.0e-6, "{} != {}", spline.length(), reference);
+    for length in [0.5, 2.0, 4.5] {
+      let t = spline.param_at_length(length);
+      almost_eq!(spline.length_between(0.0, t), length);
+    }
+  }
+
+  #[test]
+  fn unsample_ellipse() {
+    let ellipse = Ellipse::new(Point3::new(1.0, 1.0, 0.0), (2.0, 0.5));
+    almost_eq!(ellipse.sample(0.25), Point3::new(1.0, 1.5, 0.0));
+    for i in 0..10 {
+      let t = i as f64 / 10.0;
+      almost_eq!(ellipse.unsample(ellipse.sample(t)), t);
+    }
+    let arc = EllipticalArc::new(Point3::origin(), (2.0, 0.5), -0.2, 0.3);
+    almost_eq!(arc.unsample(arc.sample(0.1)), 0.1);
+    almost_eq!(arc.unsample(arc.sample(0.9)), 0.9);
+  }
+
+  #[test]
+  fn ellipse_curvature() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 0.5));
+    almost_eq!(ellipse.curvature_at(0.0), 2.0 / 0.25);
+    almost_eq!(ellipse.curvature_at(0.25), 0.5 / 4.0);
+    almost_eq!(ellipse.tangent_at(0.0), Vec3::unit_y());
+  }
+
+  #[test]
+  fn ellipse_length() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 0.5));
+    let polyline = ellipse.tesselate_fixed(20000);
+    let reference: f64 = polyline.windows(2).map(|pair| pair[0].distance(pair[1]) ).sum();
+    assert!((ellipse.length() - reference).abs() < 1.0e-6, "{} != {}", ellipse.length(), reference);
+    let arc = EllipticalArc::new(Point3::origin(), (2.0, 0.5), 0.0, 0.25);
+    almost_eq!(arc.length() * 4.0, ellipse.length());
+  }
+
+  #[test]
+  fn ellipse_to_spline() {
+    let ellipse = Ellipse::new(Point3::new(1.0, 0.0, 0.0), (2.0, 0.5));
+    let spline = ellipse.to_spline();
+    almost_eq!(spline.sample(0.0), ellipse.sample(0.0));
+    almost_eq!(spline.sample(1.0), ellipse.sample(0.0));
+    for i in 0..=20 {
+      let p = spline.sample(i as f64 / 20.0);
+      almost_eq!(p, ellipse.sample(ellipse.unsample(p)));
+    }
+    let arc = EllipticalArc::new(Point3::origin(), (2.0, 0.5), 0.1, 0.6);
+    let spline = arc.to_spline();
+    almost_eq!(spline.sample(0.0), arc.sample(0.0));
+    almost_eq!(spline.sample(1.0), arc.sample(1.0));
+    let p = spline.sample(0.3);
+    almost_eq!(p, arc.sample(arc.unsample(p)));
+  }
+
+  #[test]
+  fn split_ellipse() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 0.5));
+    let points = vec![ellipse.sample(0.1), ellipse.sample(0.6), ellipse.sample(0.3)];
+    let arcs = ellipse.split_at_points(&points).unwrap();
+    assert_eq!(arcs.len(), 3);
+    let length: f64 = arcs.iter().map(|arc| arc.as_curve().length() ).sum();
+    almost_eq!(length, ellipse.length());
+    let arc = EllipticalArc::new(Point3::origin(), (2.0, 0.5), 0.1, 0.6);
+    let (left, right) = arc.split_at(0.5).unwrap();
+    almost_eq!(left.sample(1.0), arc.sample(0.5));
+    almost_eq!(right.sample(1.0), arc.sample(1.0));
+  }
+
+  fn spring() -> Helix {
+    Helix::new(&Axis::new(Point3::new(0.0, 0.0, 1.0), Vec3::unit_z()), 2.0, 0.5, 3.0)
+  }
+
+  #[test]
+  fn unsample_helix() {
+    let mut helix = spring();
+    almost_eq!(helix.sample(0.0), Point3::new(2.0, 0.0, 1.0));
+    almost_eq!(helix.sample(1.0 / 12.0), Point3::new(0.0, 2.0, 1.125));
+    almost_eq!(helix.sample(1.0), Point3::new(2.0, 0.0, 2.5));
+    for i in 0..=10 {
+      let t = i as f64 / 10.0;
+      almost_eq!(helix.unsample(helix.sample(t)), t);
+    }
+    helix.right_handed = false;
+    helix.taper = Deg(10.0);
+    almost_eq!(helix.sample(1.0 / 12.0).y, -helix.radius_at(1.0 / 12.0));
+    for i in 0..=10 {
+      let t = i as f64 / 10.0;
+      almost_eq!(helix.unsample(helix.sample(t)), t);
+    }
+  }
+
+  #[test]
+  fn helix_curvature() {
+    let mut helix = spring();
+    // Rise per radian
+    let c = 0.5 / (2.0 * std::f64::consts::PI);
+    let denominator = 4.0 + c * c;
+    for t in [0.0, 0.3, 1.0] {
+      almost_eq!(helix.curvature_at(t), 2.0 / denominator);
+      almost_eq!(helix.torsion_at(t), c / denominator);
+    }
+    almost_eq!(helix.tangent_at(0.0), Vec3::new(0.0, 2.0, c).normalize());
+    helix.right_handed = false;
+    almost_eq!(helix.torsion_at(0.5), -c / denominator);
+    // Derivatives of tapered helices match finite differences
+    helix.taper = Deg(5.0);
+    let h = 1.0e-6;
+    let t = 0.4;
+    let derivatives = helix.derivatives(t);
+    assert!(derivatives[0].distance((helix.sample(t + h) - helix.sample(t - h)) / (2.0 * h)) < 1.0e-6);
+    assert!(derivatives[1].distance((helix.derivative(t + h) - helix.derivative(t - h)) / (2.0 * h)) < 1.0e-4);
+    assert!(derivatives[2].distance((helix.derivatives(t + h)[1] - helix.derivatives(t - h)[1]) / (2.0 * h)) < 1.0e-3);
+  }
+
+  #[test]
+  fn helix_length() {
+    let mut helix = spring();
+    let turn = (16.0 * std::f64::consts::PI.powi(2) + 0.25).sqrt();
+    almost_eq!(helix.length(), turn * 3.0);
+    almost_eq!(helix.param_at_length(turn), 1.0 / 3.0);
+    helix.taper = Deg(20.0);
+    let polyline = helix.tesselate_fixed(100000);
+    let reference: f64 = polyline.windows(2).map(|pair| pair[0].distance(pair[1]) ).sum();
+    assert!((helix.length() - reference).abs() < 1.0e-6, "{} != {}", helix.length(), reference);
+    for length in [1.0, 10.0, 25.0] {
+      let t = helix.param_at_length(length);
+      almost_eq!(helix.length_between(0.0, t), length);
+    }
+  }
+
+  #[test]
+  fn helix_to_spline() {
+    let mut helix = spring();
+    helix.taper = Deg(10.0);
+    let spline = helix.to_spline();
+    almost_eq!(spline.sample(0.0), helix.sample(0.0));
+    almost_eq!(spline.sample(1.0), helix.sample(1.0));
+    for i in 0..=100 {
+      let t = i as f64 / 100.0;
+      assert!(spline.sample(t).distance(helix.sample(t)) < 1.0e-6);
+    }
+  }
+
+  #[test]
+  fn split_helix() {
+    let mut helix = spring();
+    helix.taper = Deg(10.0);
+    let (left, right) = helix.split_at(0.25).unwrap();
+    for i in 0..=10 {
+      let t = i as f64 / 10.0;
+      almost_eq!(left.sample(t), helix.sample(t * 0.25));
+      almost_eq!(right.sample(t), helix.sample(0.25 + t * 0.75));
+    }
+    almost_eq!(left.length() + right.length(), helix.length());
+  }
 }
@@ -12,6 +12,8 @@ pub enum CurveIntersectionType {
   Pierce(CurveIntersection),
   /// Actual intersection
   Cross(CurveIntersection),
+  /// Curves meeting tangentially without crossing each other
+  Tangent(CurveIntersection),
   /// Intersection outside geometric bounds
   Extended(CurveIntersection),
   /// Overlap, Infinite intersections
@@ -19,12 +21,17 @@ pub enum CurveIntersectionType {
 }
 
 impl CurveIntersectionType {
-  pub fn new(mut isect: CurveIntersection) -> Self {
+  pub fn new(isect: CurveIntersection) -> Self {
+    Self::new_closed(isect, (false, false))
+  }
+
+  // Closed curves have no endpoints, so hits at their seam are not treated as touching
+  fn new_closed(mut isect: CurveIntersection, closed: (bool, bool)) -> Self {
     let first_hit = is_between(isect.t1, 0.0, 1.0);
     let second_hit = is_between(isect.t2, 0.0, 1.0);
     if first_hit && second_hit {
-      let first_touch = isect.t1.almost(0.0) || isect.t1.almost(1.0);
-      let second_touch = isect.t2.almost(0.0) || isect.t2.almost(1.0);
+      let first_touch = !closed.0 && (isect.t1.almost(0.0) || isect.t1.almost(1.0));
+      let second_touch = !closed.1 && (isect.t2.almost(0.0) || isect.t2.almost(1.0));
       if first_touch && second_touch {
         Self::Touch(isect)
       } else if first_touch || second_touch {
@@ -39,6 +46,16 @@ impl CurveIntersectionType {
     }
   }
 
+  // Hits in the interior of both curves don't cross them if the sine between their tangents is below `tolerance`
+  fn new_tangent(isect: CurveIntersection, closed: (bool, bool), tangents: (Vec3, Vec3), tolerance: f64) -> Self {
+    match Self::new_closed(isect, closed) {
+      Self::Cross(isect) if tangents.0.normalize().cross(tangents.1.normalize()).magnitude() < tolerance
+      => Self::Tangent(isect),
+
+      intersection => intersection,
+    }
+  }
+
   pub fn get_intersection(&self, include_extended: bool) -> Option<&CurveIntersection> {
     match self {
       Self::Contained
@@ -47,6 +64,7 @@ impl CurveIntersectionType {
       Self::Touch(isect)
       | Self::Pierce(isect)
       | Self::Cross(isect)
+      | Self::Tangent(isect)
       => Some(isect),
 
       Self::Extended(isect)
@@ -63,6 +81,7 @@ impl CurveIntersectionType {
     match self {
       Self::Contained
       | Self::Touch(_)
+      | Self::Tangent(_)
       | Self::Extended(_)
       => None,
 
@@ -87,6 +106,7 @@ impl CurveIntersectionType {
       Self::Touch(isect)
       | Self::Pierce(isect)
       | Self::Cross(isect)
+      | Self::Tangent(isect)
       | Self::Extended(isect)
       => isect.invert(),
       _ => {}
@@ -184,27 +204,21 @@ pub fn line_line(own: &Line, other: &Line) -> Option<CurveIntersectionType> {
 
 
 pub fn line_spline(line: &Line, spline: &Spline) -> Vec<CurveIntersectionType> {
-  let spline_end_points = spline.endpoints();
-  let mut intersections = vec![];
-  // Curves touch at endpoints
-  if line.points.0.almost(spline_end_points.0) {
-    intersections.push(CurveIntersectionType::Touch(CurveIntersection::new(line.points.0, 0.0, 0.0)))
-
-  } else if line.points.0.almost(spline_end_points.1) {
-    intersections.push(CurveIntersectionType::Touch(CurveIntersection::new(line.points.0, 0.0, 1.0)))
-
-  } else if line.points.1.almost(spline_end_points.0) {
-    intersections.push(CurveIntersectionType::Touch(CurveIntersection::new(line.points.1, 1.0, 0.0)))
-
-  } else if line.points.1.almost(spline_end_points.1) {
-    intersections.push(CurveIntersectionType::Touch(CurveIntersection::new(line.points.1, 1.0, 1.0)))
-  }
-  intersections
+  curve_curve(&line.clone().into_enum(), &spline.clone().into_enum())
 }
 
 
 pub fn line_circle(line: &Line, circle: &Circle) -> Vec<CurveIntersectionType> {
   let direction = line.points.1 - line.points.0;
+  let normal = circle.plane.normal();
+  let rise = direction.dot(normal);
+  if !(rise / direction.magnitude()).almost(0.0) {
+    // Lines crossing the plane of the circle can only meet it there
+    let t = (circle.plane.origin - line.points.0).dot(normal) / rise;
+    let p = line.sample(t);
+    if !(p - circle.plane.origin).magnitude().almost(circle.radius) { return vec![] }
+    return vec![CurveIntersectionType::new(CurveIntersection::new(p, t, circle.unsample(p)))]
+  }
   let f = line.points.0 - circle.plane.origin;
   let a = direction.dot(direction);
   let b = f.dot(direction) * 2.0;
@@ -237,6 +251,400 @@ pub fn line_arc(line: &Line, arc: &Arc) -> Vec<CurveIntersectionType> {
 }
 
 
+// Intersections are found on the unit circle, after scaling the ellipse's radii away
+pub fn line_ellipse(line: &Line, ellipse: &Ellipse) -> Vec<CurveIntersectionType> {
+  let direction = line.points.1 - line.points.0;
+  let normal = ellipse.plane.normal();
+  let rise = direction.dot(normal);
+  if !(rise / direction.magnitude()).almost(0.0) {
+    // Lines crossing the plane of the ellipse can only meet it there
+    let t = (ellipse.plane.origin - line.points.0).dot(normal) / rise;
+    let p = line.sample(t);
+    let u = ellipse.unsample(p);
+    if !ellipse.sample(u).almost(p) { return vec![] }
+    return vec![CurveIntersectionType::new_closed(CurveIntersection::new(p, t, u), (false, true))]
+  }
+  if !ellipse.plane.contains_point(line.points.0) { return vec![] }
+  let scale = |p: Point3| {
+    let p = ellipse.plane.unsample(p);
+    Point3::new(p.x / ellipse.radii.0, p.y / ellipse.radii.1, 0.0)
+  };
+  let unit_line = Line::new(scale(line.points.0), scale(line.points.1));
+  let unit_circle = Circle::new(Point3::origin(), 1.0);
+  line_circle(&unit_line, &unit_circle).into_iter().map(|intersection| {
+    let t1 = intersection.get_intersection(true).unwrap().t1;
+    let p = line.sample(t1);
+    let isect = CurveIntersection::new(p, t1, ellipse.unsample(p));
+    CurveIntersectionType::new_closed(isect, (false, true))
+  }).collect()
+}
+
+
+pub fn line_elliptical_arc(line: &Line, arc: &EllipticalArc) -> Vec<CurveIntersectionType> {
+  line_ellipse(line, &arc.ellipse()).into_iter().map(|intersection| {
+    let mut isect = intersection.get_intersection(true).unwrap().clone();
+    isect.t2 = arc.unsample(isect.point);
+    CurveIntersectionType::new(isect)
+  }).collect()
+}
+
+
+fn circle_points(own: &Circle, other: &Circle) -> Option<Vec<Point3>> {
+  let on_other = |p: &Point3| (*p - other.plane.origin).magnitude().almost(other.radius);
+  if !own.plane.normal().cross(other.plane.normal()).magnitude().almost(0.0) {
+    // Circles in different planes can only meet where one crosses the plane of the other
+    return Some(plane_points(own, &other.plane).into_iter().filter(on_other).collect())
+  }
+  if !other.plane.contains_point(own.plane.origin) {
+    return Some(vec![])
+  }
+  let offset = other.plane.origin - own.plane.origin;
+  let distance = offset.magnitude();
+  // Circles are concentric
+  if distance.almost(0.0) {
+    return if own.radius.almost(other.radius) { None } else { Some(vec![]) }
+  }
+  let direction = offset / distance;
+  let a = (distance.powf(2.0) + own.radius.powf(2.0) - other.radius.powf(2.0)) / (2.0 * distance);
+  let h_squared = own.radius.powf(2.0) - a.powf(2.0);
+  let center = own.plane.origin + direction * a;
+  let points = if h_squared.almost(0.0) {
+    // Circles touch tangentially
+    vec![center]
+  } else if h_squared < 0.0 {
+    vec![]
+  } else {
+    let perpendicular = own.plane.normal().cross(direction) * h_squared.sqrt();
+    vec![center + perpendicular, center - perpendicular]
+  };
+  Some(points.into_iter().filter(on_other).collect())
+}
+
+
+// Points where `circle` crosses or touches `plane`
+fn plane_points(circle: &Circle, plane: &Plane) -> Vec<Point3> {
+  // Solve a * cos(angle) + b * sin(angle) + c = 0 on the circle
+  let normal = plane.normal();
+  let a = circle.radius * circle.plane.u.dot(normal);
+  let b = circle.radius * circle.plane.v.dot(normal);
+  let c = (circle.plane.origin - plane.origin).dot(normal);
+  let amplitude = (a * a + b * b).sqrt();
+  if amplitude.almost(0.0) || c.abs() > amplitude && !c.abs().almost(amplitude) {
+    return vec![]
+  }
+  let phase = b.atan2(a);
+  let spread = (-c / amplitude).clamp(-1.0, 1.0).acos();
+  let point = |angle: f64| circle.plane.origin + (circle.plane.u * angle.cos() + circle.plane.v * angle.sin()) * circle.radius;
+  if spread.almost(0.0) || spread.almost(std::f64::consts::PI) {
+    vec![point(phase + spread)]
+  } else {
+    vec![point(phase + spread), point(phase - spread)]
+  }
+}
+
+
+pub fn circle_circle(own: &Circle, other: &Circle) -> Vec<CurveIntersectionType> {
+  match circle_points(own, other) {
+    None => vec![CurveIntersectionType::Contained],
+    Some(points) => points.into_iter().map(|p| {
+      let isect = CurveIntersection::new(p, own.unsample(p), other.unsample(p));
+      let tangents = (own.tangent_at(isect.t1), other.tangent_at(isect.t2));
+      CurveIntersectionType::new_tangent(isect, (true, true), tangents, EPSILON)
+    }).collect(),
+  }
+}
+
+
+pub fn circle_arc(circle: &Circle, arc: &Arc) -> Vec<CurveIntersectionType> {
+  let arc_circle = Circle::from_plane(arc.plane.clone(), arc.radius);
+  match circle_points(circle, &arc_circle) {
+    None => vec![CurveIntersectionType::Contained],
+    Some(points) => points.into_iter().map(|p| {
+      let isect = CurveIntersection::new(p, circle.unsample(p), arc.unsample(p));
+      let tangents = (circle.tangent_at(isect.t1), arc.tangent_at(isect.t2));
+      CurveIntersectionType::new_tangent(isect, (true, false), tangents, EPSILON)
+    }).collect(),
+  }
+}
+
+
+pub fn arc_arc(own: &Arc, other: &Arc) -> Vec<CurveIntersectionType> {
+  let own_circle = Circle::from_plane(own.plane.clone(), own.radius);
+  let other_circle = Circle::from_plane(other.plane.clone(), other.radius);
+  match circle_points(&own_circle, &other_circle) {
+    None => arc_overlap(own, other),
+    Some(points) => points.into_iter().map(|p| {
+      let isect = CurveIntersection::new(p, own.unsample(p), other.unsample(p));
+      let tangents = (own.tangent_at(isect.t1), other.tangent_at(isect.t2));
+      CurveIntersectionType::new_tangent(isect, (false, false), tangents, EPSILON)
+    }).collect(),
+  }
+}
+
+
+// Arcs on the same circle either overlap or touch at their endpoints
+fn arc_overlap(own: &Arc, other: &Arc) -> Vec<CurveIntersectionType> {
+  let inside = |arc: &Arc, p: Point3| {
+    let t = arc.unsample(p);
+    t > EPSILON && t < 1.0 - EPSILON
+  };
+  if inside(own, other.sample(0.0))
+  || inside(own, other.sample(1.0))
+  || inside(own, other.sample(0.5))
+  || inside(other, own.sample(0.5)) {
+    return vec![CurveIntersectionType::Contained]
+  }
+  let mut intersections = vec![];
+  for t1 in [0.0, 1.0] {
+    for t2 in [0.0, 1.0] {
+      let p = own.sample(t1);
+      if p.almost(other.sample(t2)) {
+        intersections.push(CurveIntersectionType::Touch(CurveIntersection::new(p, t1, t2)));
+      }
+    }
+  }
+  intersections
+}
+
+
+pub fn circle_spline(circle: &Circle, spline: &Spline) -> Vec<CurveIntersectionType> {
+  curve_curve(&circle.clone().into_enum(), &spline.clone().into_enum())
+}
+
+
+pub fn arc_spline(arc: &Arc, spline: &Spline) -> Vec<CurveIntersectionType> {
+  curve_curve(&arc.clone().into_enum(), &spline.clone().into_enum())
+}
+
+
+pub fn spline_spline(own: &Spline, other: &Spline) -> Vec<CurveIntersectionType> {
+  curve_curve(&own.clone().into_enum(), &other.clone().into_enum())
+}
+
+
+// Pieces are subdivided until their bounding boxes are smaller than this fraction of the curves' size
+const SUBDIVISION_TOLERANCE: f64 = 1.0e-2;
+
+// Refined intersections closer than this fraction of the curves' size are merged
+const MERGE_TOLERANCE: f64 = 1.0e-6;
+
+// Upper limit for the number of times pieces get subdivided
+const MAX_SUBDIVISIONS: usize = 48;
+
+// Maximum number of Newton iterations used to pull seeds onto both curves
+const REFINEMENT_ITERATIONS: usize = 64;
+
+// Maximum distance between both curves at intersection points
+const REFINEMENT_TOLERANCE: f64 = 1.0e-10;
+
+// Parameter steps below which the Newton iteration has converged
+const STEP_TOLERANCE: f64 = 1.0e-14;
+
+// Sine of the angle between both tangents below which refined intersections only touch
+const TANGENT_TOLERANCE: f64 = 1.0e-4;
+
+// Intersections of arbitrary curves
+// Pieces of both curves are subdivided while their control hulls overlap and the
+// remaining pairs are refined by Newton iteration. Curves that coincide between
+// two of their intersections are reported as overlapping.
+pub fn curve_curve(own: &CurveType, other: &CurveType) -> Vec<CurveIntersectionType> {
+  let hitter = CurveHitter::new(own, other);
+  let mut seeds = vec![];
+  for own_range in hitter.initial_ranges(0) {
+    for other_range in hitter.initial_ranges(1) {
+      hitter.subdivide([own_range, other_range], 0, &mut seeds);
+    }
+  }
+  let mut hits: Vec<[f64; 2]> = vec![];
+  for seed in seeds {
+    if let Some(x) = hitter.refine(seed, false) {
+      let p = hitter.sample(0, x[0]);
+      if !hits.iter().any(|hit| hitter.sample(0, hit[0]).distance(p) < hitter.size * MERGE_TOLERANCE ) {
+        hits.push(x);
+      }
+    }
+  }
+  hits.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap() );
+  let overlap = hits.windows(2).any(|pair| {
+    let middle = [(pair[0][0] + pair[1][0]) / 2.0, (pair[0][1] + pair[1][1]) / 2.0];
+    hitter.refine(middle, true).is_some()
+  });
+  if overlap { return vec![CurveIntersectionType::Contained] }
+  hits.into_iter().map(|x| {
+    let isect = CurveIntersection::new(hitter.sample(0, x[0]), x[0], x[1]);
+    let tangents = (hitter.derivative(0, x[0]), hitter.derivative(1, x[1]));
+    CurveIntersectionType::new_tangent(isect, (hitter.closed[0], hitter.closed[1]), tangents, TANGENT_TOLERANCE)
+  }).collect()
+}
+
+// Solves own(t1) = other(t2) for the parameters [t1, t2]
+struct CurveHitter<'a> {
+  curves: [&'a CurveType; 2],
+  closed: [bool; 2],
+  size: f64,
+}
+
+impl<'a> CurveHitter<'a> {
+  fn new(own: &'a CurveType, other: &'a CurveType) -> Self {
+    let curves = [own, other];
+    // Circles and ellipses wrap around, all other curves are clamped to their bounds
+    let closed = curves.map(|curve| matches!(curve, CurveType::Circle(_) | CurveType::Ellipse(_)) );
+    let size = curves.iter().map(|curve| {
+      let points: Vec<Point3> = initial_ranges(curve).into_iter().flat_map(|range| hull(curve, range) ).collect();
+      let (min, max) = bounding_box(&points);
+      min.distance(max)
+    }).fold(0.0, f64::max);
+    Self { curves, closed, size }
+  }
+
+  fn sample(&self, i: usize, t: f64) -> Point3 {
+    self.curves[i].as_curve().sample(t)
+  }
+
+  // Lines are extended to span the other curve, so that hits beyond their ends are found as well
+  fn initial_ranges(&self, i: usize) -> Vec<(f64, f64)> {
+    let CurveType::Line(line) = self.curves[i] else { return initial_ranges(self.curves[i]) };
+    let direction = line.points.1 - line.points.0;
+    let other = self.curves[1 - i];
+    initial_ranges(other).into_iter()
+    .flat_map(|range| hull(other, range) )
+    .map(|p| (p - line.points.0).dot(direction) / direction.magnitude2() )
+    .fold(vec![(0.0, 1.0)], |mut ranges, t| {
+      ranges[0] = (ranges[0].0.min(t), ranges[0].1.max(t));
+      ranges
+    })
+  }
+
+  // Exact derivatives keep tangent intersections from stalling early
+  fn derivative(&self, i: usize, t: f64) -> Vec3 {
+    self.curves[i].derivative(t)
+  }
+
+  fn subdivide(&self, ranges: [(f64, f64); 2], depth: usize, seeds: &mut Vec<[f64; 2]>) {
+    let boxes = [0, 1].map(|i| bounding_box(&hull(self.curves[i], ranges[i])) );
+    let disjoint = (0..3).any(|axis|
+      boxes[0].0[axis] > boxes[1].1[axis] + EPSILON || boxes[1].0[axis] > boxes[0].1[axis] + EPSILON
+    );
+    if disjoint { return }
+    let sizes = boxes.map(|(min, max)| min.distance(max) );
+    if sizes.iter().all(|size| *size < self.size * SUBDIVISION_TOLERANCE ) || depth == MAX_SUBDIVISIONS {
+      seeds.push(ranges.map(|range| (range.0 + range.1) / 2.0 ));
+      return
+    }
+    // Split the larger piece
+    let i = if sizes[0] >= sizes[1] { 0 } else { 1 };
+    let (start, end) = ranges[i];
+    let middle = (start + end) / 2.0;
+    for half in [(start, middle), (middle, end)] {
+      let mut ranges = ranges;
+      ranges[i] = half;
+      self.subdivide(ranges, depth + 1, seeds);
+    }
+  }
+
+  fn bound(&self, x: [f64; 2]) -> [f64; 2] {
+    [0, 1].map(|i| match self.curves[i] {
+      CurveType::Line(_) => x[i],
+      _ if self.closed[i] => x[i].rem_euclid(1.0),
+      _ => x[i].clamp(0.0, 1.0),
+    })
+  }
+
+  // Gauss-Newton iteration that keeps the parameter of the first curve fixed if asked to
+  fn refine(&self, mut x: [f64; 2], fix_own: bool) -> Option<[f64; 2]> {
+    for _ in 0..REFINEMENT_ITERATIONS {
+      x = self.bound(x);
+      let residual = self.sample(0, x[0]) - self.sample(1, x[1]);
+      let columns = [self.derivative(0, x[0]), -self.derivative(1, x[1])];
+      let rhs = [columns[0].dot(residual), columns[1].dot(residual)];
+      let a = columns[0].dot(columns[0]);
+      let b = columns[0].dot(columns[1]);
+      let c = columns[1].dot(columns[1]);
+      let det = a * c - b * b;
+      let step = if fix_own {
+        if c == 0.0 { return None }
+        [0.0, rhs[1] / c]
+      } else if det == 0.0 {
+        break
+      } else {
+        [(c * rhs[0] - b * rhs[1]) / det, (a * rhs[1] - b * rhs[0]) / det]
+      };
+      x[0] -= step[0];
+      x[1] -= step[1];
+      if step[0].abs() < STEP_TOLERANCE && step[1].abs() < STEP_TOLERANCE { break }
+    }
+    x = self.bound(x);
+    if self.sample(0, x[0]).distance(self.sample(1, x[1])) < REFINEMENT_TOLERANCE {
+      Some(x)
+    } else {
+      None
+    }
+  }
+}
+
+// Conic curves start out in pieces of less than a quarter turn, which their hulls rely on
+fn initial_ranges(curve: &CurveType) -> Vec<(f64, f64)> {
+  let n = match curve {
+    CurveType::Circle(_) | CurveType::Ellipse(_) => 4,
+    CurveType::Arc(arc) => (arc.range().abs() * 4.0).ceil().max(1.0) as usize,
+    CurveType::EllipticalArc(arc) => (arc.range().abs() * 4.0).ceil().max(1.0) as usize,
+    CurveType::Helix(helix) => (helix.turns.abs() * 4.0).ceil().max(1.0) as usize,
+    CurveType::Line(_) | CurveType::Spline(_) => 1,
+  };
+  (0..n).map(|i| (i as f64 / n as f64, (i + 1) as f64 / n as f64) ).collect()
+}
+
+// Control points of a piece of the curve, whose convex hull encloses the piece
+fn hull(curve: &CurveType, range: (f64, f64)) -> Vec<Point3> {
+  let start = curve.as_curve().sample(range.0);
+  let end = curve.as_curve().sample(range.1);
+  match curve {
+    CurveType::Line(_) => vec![start, end],
+    CurveType::Arc(Arc { plane, .. })
+    | CurveType::Circle(Circle { plane, .. })
+    | CurveType::Ellipse(Ellipse { plane, .. })
+    | CurveType::EllipticalArc(EllipticalArc { plane, .. }) => {
+      // Control points of the rational quadratic arc, whose middle lies on both tangents
+      let turns = (range.1 - range.0) * match curve {
+        CurveType::Arc(arc) => arc.range(),
+        CurveType::EllipticalArc(arc) => arc.range(),
+        _ => 1.0,
+      };
+      let middle = curve.as_curve().sample((range.0 + range.1) / 2.0);
+      let center = plane.origin;
+      let cos = (turns * std::f64::consts::PI).cos();
+      vec![start, center + (middle - center) / cos, end]
+    },
+    CurveType::Helix(helix) => {
+      // Outer arc hull and inner chord of the piece, spanning the heights of both ends
+      let turns = (range.1 - range.0) * helix.turns;
+      let middle = (range.0 + range.1) / 2.0;
+      let cos = (turns * std::f64::consts::PI).cos();
+      let (inner, outer) = sort_tuple2(helix.radius_at(range.0), helix.radius_at(range.1));
+      [helix.height_at(range.0), helix.height_at(range.1)].into_iter().flat_map(|height| [
+        helix.sample_around(range.0, inner, height),
+        helix.sample_around(range.1, inner, height),
+        helix.sample_around(range.0, outer, height),
+        helix.sample_around(middle, outer / cos, height),
+        helix.sample_around(range.1, outer, height),
+      ]).collect()
+    },
+    CurveType::Spline(spline) => {
+      let tail = spline.split_at(range.0).map_or_else(|| spline.clone(), |(_, tail)| tail );
+      let piece = tail.split_at((range.1 - range.0) / (1.0 - range.0)).map_or(tail, |(head, _)| head );
+      piece.controls
+    },
+  }
+}
+
+fn bounding_box(points: &[Point3]) -> (Point3, Point3) {
+  points.iter().fold((points[0], points[0]), |(min, max), p| (
+    Point3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+    Point3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+  ))
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -334,4 +742,329 @@ mod tests {
     let hit = line_circle(&line, &circle);
     assert_eq!(hit, vec![]);
   }
+
+  #[test]
+  fn circles_cross() {
+    let circle1 = Circle::new(Point3::origin(), 1.0);
+    let circle2 = Circle::new(Point3::new(1.0, 0.0, 0.0), 1.0);
+    let hits = circle_circle(&circle1, &circle2);
+    assert_eq!(hits.len(), 2);
+    let hit = match &hits[0] {
+      CurveIntersectionType::Cross(hit) => hit,
+      _ => panic!("Intersection type should be Cross instead of {:#?}", hits[0]),
+    };
+    assert!(hit.point.almost(Point3::new(0.5, 0.75_f64.sqrt(), 0.0)), "{:?}", hit.point);
+    assert!(hit.t1.almost(1.0 / 6.0));
+    assert!(hit.t2.almost(1.0 / 3.0));
+    let hit = hits[1].get_intersection(false).unwrap();
+    assert!(hit.point.almost(Point3::new(0.5, -(0.75_f64.sqrt()), 0.0)), "{:?}", hit.point);
+  }
+
+  #[test]
+  fn circles_touch() {
+    let circle1 = Circle::new(Point3::origin(), 1.0);
+    let circle2 = Circle::new(Point3::new(2.0, 0.0, 0.0), 1.0);
+    let hits = circle_circle(&circle1, &circle2);
+    assert_eq!(hits, vec![
+      CurveIntersectionType::Tangent(CurveIntersection::new(Point3::new(1.0, 0.0, 0.0), 0.0, 0.5)),
+    ]);
+    assert!(hits[0].get_splitting_intersection().is_none());
+    // Inner tangency
+    let circle3 = Circle::new(Point3::new(0.5, 0.0, 0.0), 0.5);
+    let hits = circle_circle(&circle1, &circle3);
+    assert!(matches!(hits[..], [CurveIntersectionType::Tangent(_)]));
+  }
+
+  #[test]
+  fn circles_in_different_planes() {
+    let circle1 = Circle::new(Point3::origin(), 1.0);
+    // Standing upright on the x axis, crossing the first circle at x = +-1
+    let plane = Plane { origin: Point3::origin(), u: Vec3::unit_x(), v: Vec3::unit_z() };
+    let circle2 = Circle::from_plane(plane.clone(), 1.0);
+    let hits = circle_circle(&circle1, &circle2);
+    assert_eq!(hits.len(), 2);
+    for hit in &hits {
+      let p = hit.get_intersection(false).unwrap().point;
+      assert!(p.y.almost(0.0) && p.z.almost(0.0) && p.x.abs().almost(1.0));
+    }
+    // Crossing the plane of the first circle outside of it
+    let circle3 = Circle::from_plane(Plane { origin: Point3::new(3.0, 0.0, 0.0), ..plane }, 1.0);
+    assert_eq!(circle_circle(&circle1, &circle3), vec![]);
+    // Parallel planes
+    let circle4 = Circle::new(Point3::new(0.0, 0.0, 1.0), 1.0);
+    assert_eq!(circle_circle(&circle1, &circle4), vec![]);
+  }
+
+  #[test]
+  fn circles_coincide() {
+    let circle1 = Circle::new(Point3::origin(), 1.0);
+    let circle2 = Circle::new(Point3::origin(), 1.0);
+    let circle3 = Circle::new(Point3::origin(), 2.0);
+    let circle4 = Circle::new(Point3::new(5.0, 0.0, 0.0), 1.0);
+    assert_eq!(circle_circle(&circle1, &circle2), vec![CurveIntersectionType::Contained]);
+    assert_eq!(circle_circle(&circle1, &circle3), vec![]);
+    assert_eq!(circle_circle(&circle1, &circle4), vec![]);
+  }
+
+  #[test]
+  fn circle_crosses_arc() {
+    let circle = Circle::new(Point3::origin(), 1.0);
+    let arc = Arc::new(Point3::new(1.0, 0.0, 0.0), 1.0, 0.25, 0.75);
+    let hits = circle_arc(&circle, &arc);
+    assert_eq!(hits.len(), 2);
+    let params: Vec<(f64, f64)> = hits.iter().map(|hit| match hit {
+      CurveIntersectionType::Cross(hit) => (hit.t1, hit.t2),
+      _ => panic!("Intersection type should be Cross instead of {:#?}", hit),
+    }).collect();
+    assert!(params[0].0.almost(1.0 / 6.0) && params[0].1.almost(1.0 / 6.0), "{:?}", params);
+    assert!(params[1].0.almost(5.0 / 6.0) && params[1].1.almost(5.0 / 6.0), "{:?}", params);
+    let inverted = arc.intersect(&circle.clone().into_enum());
+    assert!(inverted[0].get_intersection(false).unwrap().t1.almost(1.0 / 6.0));
+  }
+
+  #[test]
+  fn arc_pierces_arc() {
+    let arc1 = Arc::new(Point3::origin(), 1.0, 0.0, 0.5);
+    let arc2 = Arc::new(Point3::new(1.0, 1.0, 0.0), 1.0, 0.5, 0.75);
+    let hits = arc_arc(&arc1, &arc2);
+    assert_eq!(hits.len(), 2);
+    match &hits[0] {
+      CurveIntersectionType::Pierce(hit) => {
+        assert!(hit.point.almost(Point3::new(0.0, 1.0, 0.0)), "{:?}", hit.point);
+        assert!(hit.t1.almost(0.5));
+        assert!(hit.t2.almost(0.0));
+        assert_eq!(hit.direction, false, "Pierce orientation was wrong");
+      },
+      _ => panic!("Intersection type should be Pierce instead of {:#?}", hits[0]),
+    };
+    match &hits[1] {
+      CurveIntersectionType::Touch(hit) => assert!(hit.point.almost(Point3::new(1.0, 0.0, 0.0))),
+      _ => panic!("Intersection type should be Touch instead of {:#?}", hits[1]),
+    };
+  }
+
+  #[test]
+  fn arcs_on_same_circle() {
+    let arc1 = Arc::new(Point3::origin(), 1.0, 0.0, 0.5);
+    let arc2 = Arc::new(Point3::origin(), 1.0, 0.25, 0.75);
+    let arc3 = Arc::new(Point3::origin(), 1.0, 0.5, 1.0);
+    let arc4 = Arc::new(Point3::origin(), 1.0, -0.25, 0.1);
+    assert_eq!(arc_arc(&arc1, &arc2), vec![CurveIntersectionType::Contained]);
+    assert_eq!(arc_arc(&arc1, &arc4), vec![CurveIntersectionType::Contained]);
+    let hits = arc_arc(&arc1, &arc3);
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| matches!(hit, CurveIntersectionType::Touch(_)) ));
+  }
+
+  #[test]
+  fn split_overlapping_circles() {
+    let circle1 = Circle::new(Point3::origin(), 1.0);
+    let circle2 = Circle::new(Point3::new(1.0, 0.0, 0.0), 1.0);
+    let segments = circle1.split_with(&circle2.into_enum()).unwrap();
+    assert_eq!(segments.len(), 2, "{} segments found instead of 2", segments.len());
+    assert!(segments[0].as_curve().length().almost(circle1.circumfence() * 2.0 / 3.0));
+  }
+
+  fn bump() -> Spline {
+    Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(2.0, 0.0, 0.0),
+    ])
+  }
+
+  fn get_hits(intersections: &[CurveIntersectionType]) -> Vec<&CurveIntersection> {
+    intersections.iter().map(|isect| match isect {
+      CurveIntersectionType::Cross(hit) => hit,
+      _ => panic!("Intersection type should be Cross instead of {:#?}", isect),
+    }).collect()
+  }
+
+  #[test]
+  fn line_crosses_spline() {
+    let spline = bump();
+    let line = Line::new(Point3::new(-1.0, 0.75, 0.0), Point3::new(3.0, 0.75, 0.0));
+    let intersections = line_spline(&line, &spline);
+    let hits = get_hits(&intersections);
+    assert_eq!(hits.len(), 2, "{} intersections instead of 2", hits.len());
+    almost_eq!(hits[0].point, Point3::new(0.5, 0.75, 0.0));
+    almost_eq!(hits[0].t1, 0.375);
+    almost_eq!(hits[0].t2, 0.25);
+    almost_eq!(hits[1].point, Point3::new(1.5, 0.75, 0.0));
+    almost_eq!(hits[1].t2, 0.75);
+  }
+
+  #[test]
+  fn line_touches_spline() {
+    let spline = bump();
+    let tangent = Line::new(Point3::new(-1.0, 1.0, 0.0), Point3::new(3.0, 1.0, 0.0));
+    let intersections = line_spline(&tangent, &spline);
+    assert_eq!(intersections.len(), 1, "{} intersections instead of 1", intersections.len());
+    match &intersections[0] {
+      CurveIntersectionType::Tangent(hit) => assert!(hit.point.distance(Point3::new(1.0, 1.0, 0.0)) < 1.0e-6, "{:?}", hit.point),
+      _ => panic!("Intersection type should be Tangent instead of {:#?}", intersections[0]),
+    };
+    assert!(intersections[0].get_splitting_intersection().is_none());
+    // The extended line also reaches the start of the spline
+    let corner = Line::new(Point3::new(2.0, 0.0, 0.0), Point3::new(3.0, 0.0, 0.0));
+    let intersections = line_spline(&corner, &spline);
+    assert_eq!(intersections.len(), 2, "{} intersections instead of 2", intersections.len());
+    match &intersections[0] {
+      CurveIntersectionType::Extended(hit) => almost_eq!(hit.point, Point3::new(0.0, 0.0, 0.0)),
+      _ => panic!("Intersection type should be Extended instead of {:#?}", intersections[0]),
+    };
+    match &intersections[1] {
+      CurveIntersectionType::Touch(hit) => almost_eq!(hit.point, Point3::new(2.0, 0.0, 0.0)),
+      _ => panic!("Intersection type should be Touch instead of {:#?}", intersections[1]),
+    };
+  }
+
+  #[test]
+  fn line_extends_to_spline() {
+    let spline = bump();
+    let line = Line::new(Point3::new(-1.0, 0.75, 0.0), Point3::new(-0.5, 0.75, 0.0));
+    let intersections = line_spline(&line, &spline);
+    assert_eq!(intersections.len(), 2, "{} intersections instead of 2", intersections.len());
+    for intersection in &intersections {
+      let hit = intersection.get_intersection(true).unwrap();
+      assert!(matches!(intersection, CurveIntersectionType::Extended(_)));
+      assert!(hit.t1 > 1.0 && hit.point.y.almost(0.75));
+      assert!(intersection.get_intersection(false).is_none());
+    }
+  }
+
+  #[test]
+  fn spline_pierces_circle() {
+    let spline = bump();
+    let circle = Circle::new(Point3::new(1.0, 0.0, 0.0), 1.0);
+    let intersections = spline.intersect(&circle.into_enum());
+    assert_eq!(intersections.len(), 3, "{} intersections instead of 3", intersections.len());
+    // The spline touches the top of the circle tangentially and ends on it
+    let pierces: Vec<_> = intersections.iter().filter_map(|isect| match isect {
+      CurveIntersectionType::Pierce(hit) => Some(hit),
+      _ => None,
+    }).collect();
+    assert!(!pierces.is_empty(), "Spline didn't pierce the circle: {:?}", intersections);
+    for hit in pierces {
+      assert_eq!(hit.direction, true, "Pierce orientation was wrong");
+    }
+    assert!(matches!(intersections[1], CurveIntersectionType::Tangent(_)));
+  }
+
+  #[test]
+  fn arc_crosses_spline() {
+    let spline = bump();
+    let circle = Circle::new(Point3::new(1.0, 1.0, 0.0), 0.5);
+    let arc = Arc::new(Point3::new(1.0, 1.0, 0.0), 0.5, 0.5, 1.0);
+    let offset = (2.0_f64.sqrt() / 2.0 - 0.5).sqrt();
+    for intersections in [circle_spline(&circle, &spline), arc_spline(&arc, &spline)] {
+      let hits = get_hits(&intersections);
+      assert_eq!(hits.len(), 2, "{} intersections instead of 2", hits.len());
+      for hit in hits {
+        assert!(hit.point.distance(spline.sample(hit.t2)) < 1.0e-9);
+        assert!((hit.point.distance(Point3::new(1.0, 1.0, 0.0)) - 0.5).abs() < 1.0e-9);
+        assert!(((hit.point.x - 1.0).abs() - offset).abs() < 1.0e-9, "{:?}", hit.point);
+      }
+    }
+  }
+
+  #[test]
+  fn splines_cross() {
+    let spline = bump();
+    let other = Spline::new(vec![
+      Point3::new(0.0, 1.0, 0.0),
+      Point3::new(1.0, -1.0, 0.0),
+      Point3::new(2.0, 1.0, 0.0),
+    ]);
+    let intersections = spline_spline(&spline, &other);
+    let hits = get_hits(&intersections);
+    assert_eq!(hits.len(), 2, "{} intersections instead of 2", hits.len());
+    let t = (1.0 - 0.5_f64.sqrt()) / 2.0;
+    almost_eq!(hits[0].t1, t);
+    almost_eq!(hits[0].t2, t);
+    almost_eq!(hits[1].t1, 1.0 - t);
+    let segments = spline.split_with(&other.into_enum()).unwrap();
+    assert_eq!(segments.len(), 3, "{} segments found instead of 3", segments.len());
+  }
+
+  #[test]
+  fn splines_overlap() {
+    let spline = bump();
+    let (head, _) = spline.split_at(0.6).unwrap();
+    assert_eq!(spline_spline(&spline, &head), vec![CurveIntersectionType::Contained]);
+    assert_eq!(spline_spline(&spline, &spline), vec![CurveIntersectionType::Contained]);
+  }
+
+  #[test]
+  fn line_crosses_ellipse() {
+    let ellipse = Ellipse::new(Point3::new(1.0, 0.0, 0.0), (2.0, 1.0));
+    let line = Line::new(Point3::new(1.0, -2.0, 0.0), Point3::new(1.0, 2.0, 0.0));
+    let intersections = line_ellipse(&line, &ellipse);
+    let hits = get_hits(&intersections);
+    assert_eq!(hits.len(), 2);
+    almost_eq!(hits[0].point, Point3::new(1.0, -1.0, 0.0));
+    almost_eq!(hits[0].t1, 0.25);
+    almost_eq!(hits[0].t2, 0.75);
+    almost_eq!(hits[1].t2, 0.25);
+    let arc = EllipticalArc::new(Point3::new(1.0, 0.0, 0.0), (2.0, 1.0), 0.0, 0.5);
+    let intersections = line_elliptical_arc(&line, &arc);
+    assert!(matches!(intersections[0], CurveIntersectionType::Extended(_)));
+    assert!(matches!(&intersections[1], CurveIntersectionType::Cross(hit) if hit.t2.almost(0.5)));
+  }
+
+  #[test]
+  fn line_misses_ellipse() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 1.0));
+    let above = Line::new(Point3::new(-3.0, 0.0, 1.0), Point3::new(3.0, 0.0, 1.0));
+    assert!(get_hits(&line_ellipse(&above, &ellipse)).is_empty());
+    let through = Line::new(Point3::new(0.0, 0.0, -1.0), Point3::new(0.0, 0.0, 1.0));
+    assert!(get_hits(&line_ellipse(&through, &ellipse)).is_empty());
+    let piercing = Line::new(Point3::new(2.0, 0.0, -1.0), Point3::new(2.0, 0.0, 1.0));
+    let intersections = line_ellipse(&piercing, &ellipse);
+    let hits = get_hits(&intersections);
+    assert_eq!(hits.len(), 1);
+    almost_eq!(hits[0].point, Point3::new(2.0, 0.0, 0.0));
+    almost_eq!(hits[0].t1, 0.5);
+  }
+
+  #[test]
+  fn ellipse_crosses_circle() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 0.5));
+    let circle = Circle::new(Point3::origin(), 1.0);
+    let intersections = ellipse.intersect(&circle.clone().into_enum());
+    let hits = get_hits(&intersections);
+    assert_eq!(hits.len(), 4, "{} intersections instead of 4", hits.len());
+    // x² / 4 + y² / 0.25 = 1 and x² + y² = 1
+    let x = (0.75_f64 / (1.0 - 1.0 / 16.0)).sqrt();
+    for hit in hits {
+      almost_eq!(hit.point.x.abs(), x);
+      almost_eq!(circle.sample(hit.t2), hit.point);
+    }
+    let segments = ellipse.split_with(&circle.into_enum()).unwrap();
+    assert_eq!(segments.len(), 4, "{} segments found instead of 4", segments.len());
+  }
+
+  #[test]
+  fn ellipses_coincide() {
+    let ellipse = Ellipse::new(Point3::origin(), (2.0, 0.5));
+    let arc = EllipticalArc::new(Point3::origin(), (2.0, 0.5), 0.2, 0.4);
+    assert_eq!(ellipse.intersect(&arc.into_enum()), vec![CurveIntersectionType::Contained]);
+  }
+
+  #[test]
+  fn line_crosses_helix() {
+    let helix = Helix::new(&Axis::new(Point3::origin(), Vec3::unit_z()), 1.0, 1.0, 3.0);
+    let line = Line::new(Point3::new(1.0, 0.0, -0.5), Point3::new(1.0, 0.0, 3.5));
+    let intersections = line.intersect(&helix.into_enum());
+    assert_eq!(intersections.len(), 4, "{} intersections instead of 4", intersections.len());
+    for (i, intersection) in intersections.iter().enumerate() {
+      let hit = intersection.get_intersection(false).unwrap();
+      almost_eq!(hit.point, Point3::new(1.0, 0.0, i as f64));
+      almost_eq!(hit.t2, i as f64 / 3.0);
+    }
+    // Line passes through both ends of the helix
+    assert!(matches!(intersections[0], CurveIntersectionType::Pierce(_)));
+    assert!(matches!(intersections[1], CurveIntersectionType::Cross(_)));
+    assert!(matches!(intersections[3], CurveIntersectionType::Pierce(_)));
+  }
 }
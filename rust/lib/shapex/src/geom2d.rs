@@ -45,6 +45,20 @@ pub fn signed_polygon_area(closed_loop: &PolyLine) -> f64 {
   signed_area / 2.0
 }
 
+// Check if point lies inside closed loop, using the even-odd rule
+pub fn polygon_contains_point(closed_loop: &PolyLine, p: Point3) -> bool {
+  let mut is_inside = false;
+  let len = closed_loop.len();
+  for i in 0..len {
+    let a = closed_loop[i];
+    let b = closed_loop[(i + 1) % len];
+    if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+      is_inside = !is_inside;
+    }
+  }
+  is_inside
+}
+
 pub fn tesselate_polygon(vertices: PolyLine, holes: Vec<usize>) -> Mesh {
   // #[cfg(debug_assertions)]
   // assert!(!is_clockwise(&vertices));
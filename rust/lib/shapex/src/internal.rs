@@ -24,6 +24,28 @@ pub fn is_between(value: f64, start: f64, end: f64) -> bool {
   (start - EPSILON <= value && value <= end + EPSILON) || (end - EPSILON <= value && value <= start + EPSILON)
 }
 
+pub fn binomial(n: usize, k: usize) -> f64 {
+  (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64 )
+}
+
+// Nodes and weights of five point Gauss-Legendre quadrature on the interval -1..1
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+  (0.0, 0.5688888888888889),
+  (-0.5384693101056831, 0.47862867049936647),
+  (0.5384693101056831, 0.47862867049936647),
+  (-0.906179845938664, 0.23692688505618908),
+  (0.906179845938664, 0.23692688505618908),
+];
+
+// Sample positions and weights of composite Gauss-Legendre quadrature between start and end
+pub fn quadrature(start: f64, end: f64, segments: usize) -> impl Iterator<Item = (f64, f64)> {
+  let step = (end - start) / segments as f64;
+  (0..segments).flat_map(move |i| {
+    let center = start + step * (i as f64 + 0.5);
+    GAUSS_LEGENDRE.iter().map(move |(x, w)| (center + x * step / 2.0, w * step / 2.0) )
+  })
+}
+
 
 #[allow(unused_macros)]
 macro_rules! almost_eq {
@@ -23,6 +23,8 @@ pub mod features;
 pub use boolean::Boolean;
 pub use boolean::BooleanType;
 pub use volume::Volume;
+pub use volume::MassProperties;
+pub use volume::Containment;
 pub use repair::Repairable;
 pub use serialize::DeepClone;
 
@@ -128,10 +130,11 @@ impl Compound {
   }
 
   pub fn find_face_from_bounds(&self, ids: &HashSet<Uuid>) -> Option<&Ref<Face>> {
-    self.faces_iter().find(|face| {
-      let hashset = face.borrow().edge_ids();
-      hashset.intersection(&ids).count() >= 2
-    })
+    self.solids.iter().find_map(|solid| solid.find_face_from_bounds(ids) )
+  }
+
+  pub fn find_edge_from_bounds(&self, bounds: &(HashSet<Uuid>, HashSet<Uuid>)) -> Option<&Ref<Edge>> {
+    self.solids.iter().find_map(|solid| solid.find_edge_from_bounds(bounds) )
   }
 
   pub fn faces_iter(&self) -> impl Iterator<Item = &Ref<Face>> {
@@ -148,8 +151,15 @@ impl Solid {
     }
   }
 
-  pub fn lamina(wire: Wire, top_surface: SurfaceType) -> Self {
+  // Create a two-sided face from the outer ring, with the remaining rings cut out as holes
+  pub fn lamina(mut rings: Vec<Wire>, top_surface: SurfaceType) -> Self {
     println!("Creating Lamina:");
+    let normal = top_surface.as_surface().normal_at(0.0, 0.0);
+    // The outer ring runs counter-clockwise around the top face
+    let mut wire = rings.remove(0);
+    if signed_area(&wire, normal) < 0.0 {
+      wire.reverse();
+    }
     let mut bottom = top_surface.clone();
     bottom.as_surface_mut().flip();
     let mut this = Self::new();
@@ -169,7 +179,15 @@ impl Solid {
     // let he2 = shell.edges.last().unwrap().borrow().left_half.clone();
     let he1 = shell.vertices[0].borrow().half_edge();
     let he2 = shell.vertices.last().unwrap().borrow().half_edge();
-    shell.lmef(&he1, &he2, wire.last().unwrap().base.clone(), top_surface);
+    let (_, top_face) = shell.lmef(&he1, &he2, wire.last().unwrap().base.clone(), top_surface);
+    let bottom_face = shell.faces[0].clone();
+    for mut hole in rings {
+      // Holes are built counter-clockwise, which makes them run clockwise inside the top face
+      if signed_area(&hole, normal) < 0.0 {
+        hole.reverse();
+      }
+      shell.make_hole(&top_face, &bottom_face, hole);
+    }
     this
   }
 
@@ -192,10 +210,31 @@ impl Solid {
     None
   }
 
+  pub fn find_face_from_bounds(&self, ids: &HashSet<Uuid>) -> Option<&Ref<Face>> {
+    self.faces_iter().find(|face| {
+      let hashset = face.borrow().edge_ids();
+      hashset.intersection(ids).count() >= 2
+    })
+  }
+
+  // Find edge by the edge ids of the faces on both of its sides
+  pub fn find_edge_from_bounds(&self, bounds: &(HashSet<Uuid>, HashSet<Uuid>)) -> Option<&Ref<Edge>> {
+    let matches = |face: Ref<Face>, ids: &HashSet<Uuid>| face.borrow().edge_ids().intersection(ids).count() >= 2;
+    self.edges_iter().find(|edge| {
+      let edge = edge.borrow();
+      (matches(edge.left_face(), &bounds.0) && matches(edge.right_face(), &bounds.1)) ||
+      (matches(edge.left_face(), &bounds.1) && matches(edge.right_face(), &bounds.0))
+    })
+  }
+
   pub fn faces_iter(&self) -> impl Iterator<Item = &Ref<Face>> {
     self.shells.iter().flat_map(|shell| shell.faces.iter() )
   }
 
+  pub fn edges_iter(&self) -> impl Iterator<Item = &Ref<Edge>> {
+    self.shells.iter().flat_map(|shell| shell.edges.iter() )
+  }
+
   pub fn mvfs(&mut self, p: Point3, surface: SurfaceType) -> (Ref<Vertex>, Ref<Face>, &mut Shell) {
     let mut shell = Shell {
       faces: vec![],
@@ -292,13 +331,9 @@ impl Shell {
     });
     let mut he = he1.clone();
     while !Rc::ptr_eq(&he, he2) {
-      he = {
-        let mut heb = he.borrow_mut();
-        heb.origin = vertex.clone();
-        let mate = heb.mate();
-        let next = &mate.borrow().next;
-        next.upgrade().unwrap().clone()
-      }
+      he.borrow_mut().origin = vertex.clone();
+      let mate = he.borrow().mate();
+      he = mate.borrow().next();
     }
     let origin = he2.borrow().origin.clone();
     let right_half = if he1.borrow().edge.upgrade().is_some() {
@@ -387,7 +422,7 @@ impl Shell {
       let temp = nhe1b.previous.clone();
       nhe1b.previous = nhe2b.previous.clone();
       nhe2b.previous = temp;
-      // nhe1b.ring = Rc::downgrade(&ring);
+      nhe1b.ring = Rc::downgrade(&ring);
     }
     he2.borrow().ring.upgrade().unwrap().borrow_mut().half_edge = nhe2;
     self.edges.push(edge.clone());
@@ -395,56 +430,280 @@ impl Shell {
     (edge, face)
   }
 
+  // Kill edge and vertex
+  // Half edges emanating from vertex are moved to the other end of the edge
+  pub fn lkev(&mut self, edge: &Ref<Edge>, vertex: &Ref<Vertex>) {
+    let (he_out, he_in) = {
+      let edge = edge.borrow();
+      if Rc::ptr_eq(&edge.left_half.borrow().origin, vertex) {
+        (edge.left_half.clone(), edge.right_half.clone())
+      } else {
+        (edge.right_half.clone(), edge.left_half.clone())
+      }
+    };
+    let other_vertex = he_in.borrow().origin.clone();
+    let mut he = he_in.borrow().next();
+    while !Rc::ptr_eq(&he, &he_out) {
+      he.borrow_mut().origin = other_vertex.clone();
+      let mate = he.borrow().mate();
+      he = mate.borrow().next();
+    }
+    if Rc::ptr_eq(&other_vertex.borrow().half_edge(), &he_in) {
+      other_vertex.borrow_mut().half_edge = Rc::downgrade(&he_out.borrow().next());
+    }
+    for he in [&he_out, &he_in] {
+      HalfEdge::unlink(he);
+    }
+    self.edges.retain(|other| !Rc::ptr_eq(other, edge) );
+    self.vertices.retain(|other| !Rc::ptr_eq(other, vertex) );
+  }
+
+  // Kill edge and face
+  // The face on the right side of the edge is merged into the left face
+  pub fn lkef(&mut self, edge: &Ref<Edge>) {
+    let (he1, he2) = {
+      let edge = edge.borrow();
+      (edge.left_half.clone(), edge.right_half.clone())
+    };
+    let face = he1.borrow().face();
+    let ring = he1.borrow().ring.upgrade().unwrap();
+    let other_face = he2.borrow().face();
+    let other_ring = he2.borrow().ring.upgrade().unwrap();
+    HalfEdge::splice(&he1, &he2);
+    for he in ring.borrow().iter() {
+      he.borrow_mut().ring = Rc::downgrade(&ring);
+    }
+    // Move remaining rings over to merged face
+    for inner_ring in &other_face.borrow().rings {
+      if Rc::ptr_eq(inner_ring, &other_ring) { continue }
+      inner_ring.borrow_mut().face = Rc::downgrade(&face);
+      face.borrow_mut().rings.push(inner_ring.clone());
+    }
+    self.edges.retain(|other| !Rc::ptr_eq(other, edge) );
+    self.faces.retain(|other| !Rc::ptr_eq(other, &other_face) );
+  }
+
+  // Kill edge and make ring
+  // Both half edges of the edge need to be part of the same ring, which is split in two
+  pub fn lkemr(&mut self, edge: &Ref<Edge>) -> Ref<Ring> {
+    let (he1, he2) = {
+      let edge = edge.borrow();
+      (edge.left_half.clone(), edge.right_half.clone())
+    };
+    let face = he1.borrow().face();
+    let old_ring = he1.borrow().ring.upgrade().unwrap();
+    let he1_previous = he1.borrow().previous();
+    let he1_next = he1.borrow().next();
+    HalfEdge::splice(&he1, &he2);
+    old_ring.borrow_mut().half_edge = he1_previous;
+    let ring = rc(Ring {
+      half_edge: he1_next.clone(),
+      face: Rc::downgrade(&face),
+    });
+    for he in ring.borrow().iter() {
+      he.borrow_mut().ring = Rc::downgrade(&ring);
+    }
+    face.borrow_mut().rings.push(ring.clone());
+    self.edges.retain(|other| !Rc::ptr_eq(other, edge) );
+    ring
+  }
+
+  // Make edge and kill ring
+  // Connects the origins of two half edges in different rings of the same face, merging the second ring into the first
+  pub fn lmekr(&mut self, he1: &Ref<HalfEdge>, he2: &Ref<HalfEdge>, curve: CurveType) -> Ref<Edge> {
+    let ring = he1.borrow().ring.upgrade().unwrap();
+    let other_ring = he2.borrow().ring.upgrade().unwrap();
+    let face = ring.borrow().face.upgrade().unwrap();
+    for he in other_ring.borrow().iter() {
+      he.borrow_mut().ring = Rc::downgrade(&ring);
+    }
+    let he1_origin = he1.borrow().origin.clone();
+    let he2_origin = he2.borrow().origin.clone();
+    let nhe1 = HalfEdge::new_at(&he1_origin, he2);
+    let nhe2 = HalfEdge::new_at(&he2_origin, he1);
+    let edge = rc(Edge {
+      id: Uuid::new_v4(),
+      left_half: nhe1.clone(),
+      right_half: nhe2.clone(),
+      curve,
+    });
+    nhe1.borrow_mut().edge = Rc::downgrade(&edge);
+    nhe2.borrow_mut().edge = Rc::downgrade(&edge);
+    {
+      // Cross over both rings at the new edge
+      let previous = nhe1.borrow().previous();
+      previous.borrow_mut().next = Rc::downgrade(&nhe2);
+      let previous = nhe2.borrow().previous();
+      previous.borrow_mut().next = Rc::downgrade(&nhe1);
+      let mut nhe1b = nhe1.borrow_mut();
+      let mut nhe2b = nhe2.borrow_mut();
+      std::mem::swap(&mut nhe1b.previous, &mut nhe2b.previous);
+    }
+    let mut face = face.borrow_mut();
+    if Rc::ptr_eq(&face.outer_ring, &other_ring) {
+      face.outer_ring = ring.clone();
+    }
+    face.rings.retain(|other| !Rc::ptr_eq(other, &other_ring) );
+    self.edges.push(edge.clone());
+    edge
+  }
+
+  // Cut wire as an inner ring into both sides of a lamina
+  //
+  // The wire is connected to the outer ring of the top face by a bridge edge,
+  // which is removed again after closing the wire into a new face,
+  // which in turn becomes the corresponding inner ring of the bottom face.
+  fn make_hole(&mut self, top_face: &Ref<Face>, bottom_face: &Ref<Face>, wire: Wire) {
+    let he = top_face.borrow().outer_ring.borrow().half_edge.clone();
+    let start = wire[0].bounds.0;
+    let bridge_curve = Line::new(he.borrow().origin.borrow().point, start).into_enum();
+    let (bridge, _) = self.lmev(&he, &he, bridge_curve, start);
+    let mut tip = bridge.borrow().left_half.clone();
+    let mut first = tip.clone();
+    for (i, tcurve) in wire.iter().take(wire.len() - 1).enumerate() {
+      let (edge, _) = self.lmev(&tip, &tip, tcurve.base.clone(), tcurve.bounds.1);
+      if i == 0 {
+        first = edge.borrow().right_half.clone();
+      }
+      tip = edge.borrow().left_half.clone();
+    }
+    let surface = top_face.borrow().surface.clone();
+    let (_, hole_face) = self.lmef(&first, &tip, wire.last().unwrap().base.clone(), surface);
+    // Killing the bridge leaves the outer part of the ring in the new ring
+    let outer_ring = self.lkemr(&bridge);
+    top_face.borrow_mut().outer_ring = outer_ring;
+    self.lkfmrh(bottom_face, &hole_face);
+  }
+
+  // Kill face and make ring hole
+  // The rings of other_face become inner rings of face, whose surface they need to lie on
+  pub fn lkfmrh(&mut self, face: &Ref<Face>, other_face: &Ref<Face>) {
+    for ring in &other_face.borrow().rings {
+      ring.borrow_mut().face = Rc::downgrade(face);
+      face.borrow_mut().rings.push(ring.clone());
+    }
+    self.faces.retain(|other| !Rc::ptr_eq(other, other_face) );
+  }
+
+  // Turn shell inside out, by reversing all rings and flipping all surfaces
+  pub fn invert(&mut self) {
+    for face in &self.faces {
+      for ring in &face.borrow().rings {
+        let half_edges: Vec<Ref<HalfEdge>> = ring.borrow().iter().collect();
+        let ends: Vec<Ref<Vertex>> = half_edges.iter().map(|he| he.borrow().next().borrow().origin.clone() ).collect();
+        for (he, end) in half_edges.iter().zip(ends) {
+          end.borrow_mut().half_edge = Rc::downgrade(he);
+          let he = &mut *he.borrow_mut();
+          he.origin = end;
+          std::mem::swap(&mut he.next, &mut he.previous);
+        }
+      }
+      face.borrow_mut().surface.as_surface_mut().flip();
+    }
+  }
+
   pub fn sweep<C,S>(&mut self, face: &Ref<Face>, transform: &Matrix4, make_curve: C, make_surface: S)
   where
     C: Fn(Point3) -> CurveType,
     S: Fn(&TrimmedCurve) -> SurfaceType,
   {
-    for ring in &face.borrow().rings {
+    self.sweep_mapped(
+      face,
+      |point| transform.transform_point(point),
+      |tcurve| {
+        let mut curve = tcurve.base.clone();
+        curve.as_curve_mut().transform(transform);
+        curve
+      },
+      make_curve,
+      make_surface,
+    );
+    face.borrow_mut().surface.as_surface_mut().transform(transform);
+  }
+
+  // Sweep face, moving each vertex and edge of its rings individually
+  // The surface of the face itself is left untouched
+  pub fn sweep_mapped<P,M,C,S>(&mut self, face: &Ref<Face>, map_point: P, map_curve: M, make_curve: C, make_surface: S)
+  where
+    P: Fn(Point3) -> Point3,
+    M: Fn(&TrimmedCurve) -> CurveType,
+    C: Fn(Point3) -> CurveType,
+    S: Fn(&TrimmedCurve) -> SurfaceType,
+  {
+    let rings = face.borrow().rings.clone();
+    for ring in &rings {
       let first = ring.borrow().half_edge.clone();
       let mut scan = first.borrow().next();
-      self.sweep_mev(&scan, transform, &make_curve);
+      self.sweep_mev(&scan, &map_point, &make_curve);
       while !Rc::ptr_eq(&scan, &first) {
         scan = {
           let scan_next = scan.borrow().next();
-          self.sweep_mev(&scan_next, transform, &make_curve);
-          self.sweep_mef(&scan, transform, &make_surface);
+          self.sweep_mev(&scan_next, &map_point, &make_curve);
+          self.sweep_mef(&scan, &map_curve, &make_surface);
           let scanb = scan.borrow();
           scanb.next().borrow().mate().borrow().next()
         }
       }
-      self.sweep_mef(&scan, transform, &make_surface);
+      self.sweep_mef(&scan, &map_curve, &make_surface);
     }
-    face.borrow_mut().surface.as_surface_mut().transform(transform);
   }
 
-  fn sweep_mev<C: Fn(Point3) -> CurveType>(&mut self, scan: &Ref<HalfEdge>, transform: &Matrix4, make_curve: C) {
+  fn sweep_mev<P: Fn(Point3) -> Point3, C: Fn(Point3) -> CurveType>(&mut self, scan: &Ref<HalfEdge>, map_point: P, make_curve: C) {
     let point = scan.borrow().origin.borrow().point;
     let curve = make_curve(point);
-    self.lmev(scan, scan, curve, transform.transform_point(point));
+    self.lmev(scan, scan, curve, map_point(point));
   }
 
-  fn sweep_mef<S: Fn(&TrimmedCurve) -> SurfaceType>(&mut self, scan: &Ref<HalfEdge>, transform: &Matrix4, make_surface: S) {
+  fn sweep_mef<M, S>(&mut self, scan: &Ref<HalfEdge>, map_curve: M, make_surface: S)
+  where
+    M: Fn(&TrimmedCurve) -> CurveType,
+    S: Fn(&TrimmedCurve) -> SurfaceType,
+  {
     let scan_previous = scan.borrow().previous();
     let next = scan.borrow().next();
     let next_next = next.borrow().next();
-    let mut curve = scan.borrow().edge().borrow().curve.clone();
-    curve.as_curve_mut().transform(transform);
+    let tcurve = scan.borrow().make_curve();
+    let mut curve = map_curve(&tcurve);
     // Create new stable id for cloned curve
-    let curve_id = curve.id();
+    let curve_id = tcurve.base.id();
     let fields = curve_id.as_fields();
     curve.set_id(Uuid::from_fields(fields.0, fields.1 + 1, fields.2, fields.3));
     // Sweep actual surface
-    let surface = make_surface(&scan.borrow().make_curve());
+    let surface = make_surface(&tcurve);
     // let p1 = scan_previous.borrow().origin.borrow().point;
     // let p2 = next_next.borrow().origin.borrow().point;
-    self.lmef(
+    let (_, new_face) = self.lmef(
       // New edge is oriented from..
       &scan_previous, // ..this half edge's vertex..
       &next_next, // ..to this half edge's vertex
       curve,
       surface,
     );
+    // Rings consisting of a single edge get split the other way around,
+    // leaving the new face with nothing but the swept copy of the edge
+    if Rc::ptr_eq(&scan_previous, &next_next) {
+      let old_ring = scan.borrow().ring.upgrade().unwrap();
+      let new_ring = new_face.borrow().outer_ring.clone();
+      let old_face = old_ring.borrow().face.upgrade().unwrap();
+      {
+        let mut old_face = old_face.borrow_mut();
+        for ring in old_face.rings.iter_mut().filter(|ring| Rc::ptr_eq(ring, &old_ring) ) {
+          *ring = new_ring.clone();
+        }
+        if Rc::ptr_eq(&old_face.outer_ring, &old_ring) {
+          old_face.outer_ring = new_ring.clone();
+        } else {
+          // Closed curves run the same way in inner and outer rings,
+          // so surfaces swept from holes have to face the other way
+          new_face.borrow_mut().surface.as_surface_mut().flip();
+        }
+      }
+      let mut new_face_mut = new_face.borrow_mut();
+      new_face_mut.outer_ring = old_ring.clone();
+      new_face_mut.rings = vec![old_ring.clone()];
+      old_ring.borrow_mut().face = Rc::downgrade(&new_face);
+      new_ring.borrow_mut().face = Rc::downgrade(&old_face);
+    }
   }
 
   pub fn print(&self) {
@@ -464,7 +723,11 @@ impl Shell {
 impl Face {
   pub fn make_surface(&self) -> TrimmedSurface {
     let wire = self.outer_ring.borrow().make_wire();
-    TrimmedSurface::new(self.surface.clone(), wire)
+    let mut surface = TrimmedSurface::new(self.surface.clone(), wire);
+    for ring in self.rings.iter().filter(|ring| !Rc::ptr_eq(ring, &self.outer_ring) ) {
+      surface.profile.push(ring.borrow().make_wire());
+    }
+    surface
   }
 
   pub fn edge_ids(&self) -> HashSet<Uuid> {
@@ -559,6 +822,45 @@ impl HalfEdge {
     }
   }
 
+  // Remove half edge from its ring
+  fn unlink(this: &Ref<Self>) {
+    let previous = this.borrow().previous();
+    let next = this.borrow().next();
+    previous.borrow_mut().next = Rc::downgrade(&next);
+    next.borrow_mut().previous = Rc::downgrade(&previous);
+    let ring = this.borrow().ring.upgrade().unwrap();
+    if Rc::ptr_eq(&ring.borrow().half_edge, this) {
+      ring.borrow_mut().half_edge = next;
+    }
+  }
+
+  // Remove two opposing half edges, connecting the remaining half edges across them.
+  // Rings on both sides are merged if they differ, or split otherwise.
+  fn splice(he1: &Ref<Self>, he2: &Ref<Self>) {
+    let he1_previous = he1.borrow().previous();
+    let he1_next = he1.borrow().next();
+    let he2_previous = he2.borrow().previous();
+    let he2_next = he2.borrow().next();
+    he1_previous.borrow_mut().next = Rc::downgrade(&he2_next);
+    he2_next.borrow_mut().previous = Rc::downgrade(&he1_previous);
+    he2_previous.borrow_mut().next = Rc::downgrade(&he1_next);
+    he1_next.borrow_mut().previous = Rc::downgrade(&he2_previous);
+    let he1_origin = he1.borrow().origin.clone();
+    if Rc::ptr_eq(&he1_origin.borrow().half_edge(), he1) {
+      he1_origin.borrow_mut().half_edge = Rc::downgrade(&he2_next);
+    }
+    let he2_origin = he2.borrow().origin.clone();
+    if Rc::ptr_eq(&he2_origin.borrow().half_edge(), he2) {
+      he2_origin.borrow_mut().half_edge = Rc::downgrade(&he1_next);
+    }
+    for ring in [he1.borrow().ring.upgrade().unwrap(), he2.borrow().ring.upgrade().unwrap()] {
+      let ring_he = ring.borrow().half_edge.clone();
+      if Rc::ptr_eq(&ring_he, he1) || Rc::ptr_eq(&ring_he, he2) {
+        ring.borrow_mut().half_edge = he1_previous.clone();
+      }
+    }
+  }
+
   pub fn mate(&self) -> Ref<Self> {
     if let Some(edge) = self.edge.upgrade() {
       let edge = edge.borrow();
@@ -702,6 +1004,16 @@ impl Iterator for VertexEdgesIterator {
 }
 
 
+// Area enclosed by the wire, positive if it runs counter-clockwise around normal
+fn signed_area(wire: &Wire, normal: Vec3) -> f64 {
+  let points: Vec<Point3> = wire.iter().flat_map(|tcurve| tcurve.tesselate() ).collect();
+  let origin = points[0];
+  points.iter().zip(points.iter().cycle().skip(1)).fold(0.0, |acc, (p, q)|
+    acc + (p - origin).cross(q - origin).dot(normal) / 2.0
+  )
+}
+
+
 #[cfg(test)]
 mod tests {
   // use super::*;
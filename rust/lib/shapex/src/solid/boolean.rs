@@ -1,13 +1,22 @@
+use std::rc::{Rc, Weak};
+
+use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 
 use crate::solid::*;
+use crate::geom2d;
+use crate::transform::*;
+use crate::surface::intersection;
+use super::volume;
 
 
 /// Type of a boolean set operation.
 ///
 /// The [Create](Self::Create) variant is used to add [Solid]s to a [Compound] without actually performing any set operation.
+/// [Difference](Self::Difference) yields the symmetric difference, meaning the regions covered by exactly one of the operands.
+/// It is assembled from both operands cut by each other, so the two parts come out as separate [Solid]s.
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BooleanType {
   Create,
   Join,
@@ -20,33 +29,1581 @@ pub enum BooleanType {
 /// All types that can be combined using boolean set operations.
 
 pub trait Boolean {
-  fn create(&mut self, tool: Self);
-  fn join(&mut self, tool: Self);
-  fn cut(&mut self, tool: Self);
-  fn intersect(&mut self, tool: Self);
-  fn difference(&mut self, tool: Self);
-  fn boolean(&mut self, tool: Self, op: BooleanType);
+  fn create(&mut self, tool: Self) -> Result<(), String>;
+  fn join(&mut self, tool: Self) -> Result<(), String>;
+  fn cut(&mut self, tool: Self) -> Result<(), String>;
+  fn intersect(&mut self, tool: Self) -> Result<(), String>;
+  fn difference(&mut self, tool: Self) -> Result<(), String>;
+  fn boolean(&mut self, tool: Self, op: BooleanType) -> Result<(), String>;
 }
 
 
 impl Boolean for Compound {
-  fn create(&mut self, mut tool: Self) { self.solids.append(&mut tool.solids) }
-  fn join(&mut self, mut tool: Self) { self.solids.append(&mut tool.solids) }
-  fn cut(&mut self, _tool: Self) { todo!() }
-  fn intersect(&mut self, _tool: Self) { todo!() }
-  fn difference(&mut self, _tool: Self) { todo!() }
+  fn create(&mut self, mut tool: Self) -> Result<(), String> {
+    self.solids.append(&mut tool.solids);
+    Ok(())
+  }
+
+  fn join(&mut self, tool: Self) -> Result<(), String> {
+    for tool_solid in tool.solids {
+      let mut joined = tool_solid;
+      let mut i = 0;
+      while i < self.solids.len() {
+        if bounds_overlap(solid_bounds(&self.solids[i]), solid_bounds(&joined)) {
+          // Bodies whose union falls apart into several solids are disjoint and kept separate
+          let mut union = boolean_solids(&self.solids[i], &joined, BooleanType::Join)?;
+          if union.len() == 1 {
+            joined = union.remove(0);
+            self.solids.remove(i);
+            i = 0;
+            continue;
+          }
+        }
+        i += 1;
+      }
+      self.solids.push(joined);
+    }
+    Ok(())
+  }
 
-  fn boolean(&mut self, tool: Self, op: BooleanType) {
+  fn cut(&mut self, tool: Self) -> Result<(), String> {
+    for tool_solid in &tool.solids {
+      let mut solids = vec![];
+      for solid in &self.solids {
+        solids.append(&mut boolean_solids(solid, tool_solid, BooleanType::Cut)?);
+      }
+      self.solids = solids;
+    }
+    Ok(())
+  }
+
+  fn intersect(&mut self, tool: Self) -> Result<(), String> {
+    let mut solids = vec![];
+    for solid in &self.solids {
+      for tool_solid in &tool.solids {
+        solids.append(&mut boolean_solids(solid, tool_solid, BooleanType::Intersection)?);
+      }
+    }
+    self.solids = solids;
+    Ok(())
+  }
+
+  fn difference(&mut self, tool: Self) -> Result<(), String> {
+    let mut remainder = tool.clone();
+    remainder.cut(self.clone())?;
+    self.cut(tool)?;
+    self.solids.append(&mut remainder.solids);
+    Ok(())
+  }
+
+  fn boolean(&mut self, tool: Self, op: BooleanType) -> Result<(), String> {
     match op {
       BooleanType::Create => self.create(tool),
       BooleanType::Join => self.join(tool),
-      _ => todo!()
+      BooleanType::Cut => self.cut(tool),
+      BooleanType::Intersection => self.intersect(tool),
+      BooleanType::Difference => self.difference(tool),
+    }
+  }
+}
+
+
+// Parameter offset used to measure the direction in which curves leave a vertex
+const DEPARTURE_OFFSET: f64 = 0.001;
+
+// Distance from the boundary of a solid at which fragments touching it are probed from both sides
+const PROBE_OFFSET: f64 = 1.0e-5;
+
+// Number of samples per curved edge, when mapping curves into the parameter space of curved faces
+const PARAMETRIC_SEGMENTS: usize = 16;
+
+// Distance from curved faces, within which intersection curves approximated by splines still lie on them
+const APPROXIMATION_TOLERANCE: f64 = 1.0e-5;
+
+// Parametric distance from the seam of a closed face, at which pieces of a temporary seam are checked against its domain
+const SEAM_PROBE_OFFSET: f64 = 1.0e-3;
+
+// Number of bisection steps used to locate the points where curves cross the seam of a closed face
+const SEAM_BISECTIONS: usize = 48;
+
+
+// Combine two solids, yielding any number of new solids.
+// Faces of both operands are split along their mutual intersections.
+// The resulting fragments are classified against the opposite solid,
+// selected according to the operation and stitched back together.
+fn boolean_solids(solid: &Solid, tool: &Solid, op: BooleanType) -> Result<Vec<Solid>, String> {
+  let own = Operand::new(solid);
+  let other = Operand::new(tool);
+  let mut fragments = vec![];
+  for (faces, others, is_tool) in [(&own, &other, false), (&other, &own, true)] {
+    for face in &faces.faces {
+      for (mut fragment, location) in split_face(&face.borrow(), others, is_tool)? {
+        if !is_selected(location, op, is_tool) { continue }
+        if is_tool && op == BooleanType::Cut {
+          fragment.flip();
+        }
+        fragments.push(fragment);
+      }
+    }
+  }
+  stitch(fragments, solid.id)
+}
+
+fn is_selected(location: Location, op: BooleanType, is_tool: bool) -> bool {
+  match (op, is_tool) {
+    (BooleanType::Join, false) => matches!(location, Location::Outside | Location::OnSame),
+    (BooleanType::Join, true) => location == Location::Outside,
+    (BooleanType::Intersection, false) => matches!(location, Location::Inside | Location::OnSame),
+    (BooleanType::Intersection, true) => location == Location::Inside,
+    (BooleanType::Cut, false) => matches!(location, Location::Outside | Location::OnOpposite),
+    (BooleanType::Cut, true) => location == Location::Inside,
+    // Differences are assembled from two cuts
+    _ => false,
+  }
+}
+
+
+/// Position of a face fragment relative to another solid.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Location {
+  Inside,
+  Outside,
+  OnSame, // Coincides with a boundary face of equal orientation
+  OnOpposite, // Coincides with a boundary face of opposite orientation
+}
+
+
+// Curve running along a ring. The flag is false if the curve is traversed backwards.
+type Segment = (CurveType, bool);
+
+// Rings bounding a region of a split face, along with a point in its interior
+type SplitRegion = (Vec<Vec<Segment>>, Point3);
+
+
+/// Section of a face, that lies completely inside, outside or on the boundary of another solid.
+
+struct Fragment {
+  id: Option<Uuid>,
+  surface: SurfaceType,
+  rings: Vec<Vec<Segment>>,
+  point: Point3, // Arbitrary point in the interior of the fragment
+}
+
+impl Fragment {
+  fn from_face(face: &Face, point: Point3) -> Self {
+    Self {
+      id: Some(face.id),
+      surface: face.surface.clone(),
+      rings: face_segments(face),
+      point,
+    }
+  }
+
+  fn flip(&mut self) {
+    self.surface.as_surface_mut().flip();
+    for ring in &mut self.rings {
+      ring.reverse();
+      for segment in ring.iter_mut() {
+        segment.1 = !segment.1;
+      }
+    }
+  }
+}
+
+
+/// Faces of a solid, along with the regions used to split faces of the other operand.
+
+struct Operand<'a> {
+  solid: &'a Solid,
+  faces: Vec<Ref<Face>>,
+  regions: Vec<Region>,
+}
+
+impl<'a> Operand<'a> {
+  fn new(solid: &'a Solid) -> Self {
+    let faces: Vec<Ref<Face>> = solid.faces_iter().cloned().collect();
+    let regions = faces.iter().map(|face| Region::new(&face.borrow()) ).collect();
+    Self { solid, faces, regions }
+  }
+
+  // Points on the boundary of the solid are probed from both sides of the fragment they belong to
+  fn locate(&self, point: Point3, normal: Vec3, coplanar: &[(&PlanarRegion, bool)]) -> Location {
+    for (region, is_same) in coplanar {
+      if region.contains(region.to_local.transform_point(point)) {
+        return if *is_same { Location::OnSame } else { Location::OnOpposite }
+      }
+    }
+    match self.solid.classify_point(point, EPSILON) {
+      Containment::Inside => Location::Inside,
+      Containment::Outside => Location::Outside,
+      Containment::OnBoundary => {
+        let front = self.solid.classify_point(point + normal * PROBE_OFFSET, EPSILON);
+        let back = self.solid.classify_point(point - normal * PROBE_OFFSET, EPSILON);
+        match (back, front) {
+          (Containment::Inside, Containment::Outside) => Location::OnSame,
+          (Containment::Outside, Containment::Inside) => Location::OnOpposite,
+          (Containment::Inside, _) => Location::Inside,
+          _ => Location::Outside,
+        }
+      },
+    }
+  }
+}
+
+
+/// Face of an operand, prepared for splitting and clipping.
+
+enum Region {
+  Planar(PlanarRegion),
+  Curved(CurvedRegion),
+}
+
+impl Region {
+  fn new(face: &Face) -> Self {
+    PlanarRegion::new(face).map_or_else(|| Self::Curved(CurvedRegion::new(face)), Self::Planar )
+  }
+
+  fn clip(&self, curve: &CurveType) -> Vec<CurveType> {
+    match self {
+      Self::Planar(region) => region.clip(curve),
+      Self::Curved(region) => region.clip(curve),
+    }
+  }
+
+  fn split(&self, cutters: Vec<CurveType>) -> Result<Vec<SplitRegion>, String> {
+    match self {
+      Self::Planar(region) => Ok(region.split(cutters)),
+      Self::Curved(region) => region.split(cutters),
+    }
+  }
+
+  fn interior_point(&self) -> Point3 {
+    match self {
+      Self::Planar(region) => region.interior_point().unwrap_or(region.plane.origin),
+      Self::Curved(region) => region.interior_point(),
+    }
+  }
+
+  fn normal_at(&self, p: Point3) -> Vec3 {
+    match self {
+      Self::Planar(region) => region.plane.normal(),
+      Self::Curved(region) => region.normal_at(p),
+    }
+  }
+}
+
+
+/// Planar face, transformed into the coordinate system of its plane.
+
+struct PlanarRegion {
+  plane: Plane,
+  to_local: Matrix4,
+  curves: Vec<CurveType>, // Boundary curves in local space
+  polygons: Vec<PolyLine>, // Tesselated rings in local space, starting with the outer ring
+  bounds: Vec<Point3>, // Tesselated rings in world space
+}
+
+impl PlanarRegion {
+  fn new(face: &Face) -> Option<Self> {
+    if let SurfaceType::Planar(surface) = &face.surface {
+      let plane = surface.plane.clone();
+      let to_local = plane.as_transform().invert().unwrap();
+      let rings: Vec<Vec<Segment>> = face_segments(face).into_iter().map(|ring|
+        ring.into_iter().map(|(mut curve, is_forward)| {
+          curve.as_curve_mut().transform(&to_local);
+          (curve, is_forward)
+        }).collect()
+      ).collect();
+      let polygons: Vec<PolyLine> = rings.iter().map(|ring| ring_polygon(ring.iter().map(|(curve, is_forward)| (curve, *is_forward) )) ).collect();
+      let to_world = plane.as_transform();
+      let bounds = polygons.iter().flatten().map(|p| to_world.transform_point(*p) ).collect();
+      let curves = rings.into_iter().flatten().map(|(curve, _)| curve ).collect();
+      Some(Self { plane, to_local, curves, polygons, bounds })
+    } else {
+      None
+    }
+  }
+
+  // Check if local point lies inside the region, excluding its boundary
+  fn contains(&self, p: Point3) -> bool {
+    !self.touches(p) &&
+    geom2d::polygon_contains_point(&self.polygons[0], p) &&
+    !self.polygons.iter().skip(1).any(|polygon| geom2d::polygon_contains_point(polygon, p) )
+  }
+
+  // Check if local point lies on the boundary of the region
+  fn touches(&self, p: Point3) -> bool {
+    self.curves.iter().any(|curve| curve.as_curve().contains_point(p) )
+  }
+
+  fn world_curves(&self) -> Vec<CurveType> {
+    let to_world = self.plane.as_transform();
+    self.curves.iter().map(|curve| {
+      let mut curve = curve.clone();
+      curve.as_curve_mut().transform(&to_world);
+      curve
+    }).collect()
+  }
+
+  // Split curve at the boundary of the region and return all pieces inside or on the region
+  fn clip(&self, curve: &CurveType) -> Vec<CurveType> {
+    let mut curve = curve.clone();
+    curve.as_curve_mut().transform(&self.to_local);
+    let to_world = self.plane.as_transform();
+    split_curve(&curve, &self.curves).into_iter().filter(|piece| {
+      let midpoint = piece.as_curve().sample(0.5);
+      self.contains(midpoint) || self.touches(midpoint)
+    }).map(|mut piece| {
+      piece.as_curve_mut().transform(&to_world);
+      piece
+    }).collect()
+  }
+
+  // Split region along the given world space curves, yielding the rings and an interior point of each fragment
+  fn split(&self, cutters: Vec<CurveType>) -> Vec<SplitRegion> {
+    let mut curves = self.curves.clone();
+    curves.extend(cutters.into_iter().map(|mut curve| {
+      curve.as_curve_mut().transform(&self.to_local);
+      curve
+    }));
+    let mut graph = Graph::default();
+    for curve in &curves {
+      for piece in split_curve(curve, &curves) {
+        graph.add_curve(piece);
+      }
+    }
+    graph.prune();
+    let to_world = self.plane.as_transform();
+    graph.regions().into_iter()
+    .filter(|(_, point)| self.contains(*point) )
+    .map(|(rings, point)| {
+      let rings = rings.into_iter().map(|ring|
+        ring.into_iter().map(|(mut curve, is_forward)| {
+          curve.as_curve_mut().transform(&to_world);
+          (curve, is_forward)
+        }).collect()
+      ).collect();
+      (rings, to_world.transform_point(point))
+    }).collect()
+  }
+
+  fn interior_point(&self) -> Option<Point3> {
+    interior_point(&self.polygons).map(|p| self.plane.as_transform().transform_point(p) )
+  }
+}
+
+
+/// Curved face, along with the domain it covers in the parameter space of its surface.
+
+struct CurvedRegion {
+  surface: TrimmedSurface,
+  curves: Vec<CurveType>, // Boundary curves in world space
+  polygons: Vec<PolyLine>, // Parametric domain, starting with the polygons that add to it
+  bounds: [(f64, f64); 2], // Parametric range of the domain along u and v
+  closed: [bool; 2],
+}
+
+impl CurvedRegion {
+  fn new(face: &Face) -> Self {
+    let surface = face.make_surface();
+    let mut domain = surface.parametric_domain();
+    domain.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap() );
+    let mut bounds = [(f64::INFINITY, f64::NEG_INFINITY); 2];
+    for p in domain.iter().flat_map(|(polygon, _)| polygon.iter() ) {
+      for (axis, range) in bounds.iter_mut().enumerate() {
+        *range = (range.0.min(p[axis]), range.1.max(p[axis]));
+      }
+    }
+    let closed = [surface.base.is_closed_u(), surface.base.is_closed_v()];
+    let curves = face_segments(face).into_iter().flatten().map(|(curve, _)| curve ).collect();
+    let polygons = domain.into_iter().map(|(polygon, _)| polygon ).collect();
+    Self { surface, curves, polygons, bounds, closed }
+  }
+
+  fn params(&self, p: Point3) -> Point3 {
+    let (u, v) = self.surface.base.as_surface().unsample(p);
+    Point3::new(u, v, 0.0)
+  }
+
+  // Check if the domain wraps all the way around a closed direction of the surface
+  fn spans(&self, axis: usize) -> bool {
+    self.closed[axis] && self.bounds[axis].1 - self.bounds[axis].0 > 1.0 - EPSILON
+  }
+
+  // Check if curve runs along the seam, where the domain wraps around the given axis
+  fn on_seam(&self, curve: &CurveType, axis: usize) -> bool {
+    self.spans(axis) && [0.0, 0.5, 1.0].iter().all(|&t| {
+      let offset = (self.params(curve.as_curve().sample(t))[axis] - self.bounds[axis].0).rem_euclid(1.0);
+      offset.almost(0.0) || offset.almost(1.0)
+    })
+  }
+
+  // Check if world point lies on the boundary of the region
+  fn touches(&self, p: Point3) -> bool {
+    self.curves.iter().any(|curve| curve.as_curve().contains_point(p) )
+  }
+
+  // Split curve at the boundary of the region and return all pieces inside or on the region
+  fn clip(&self, curve: &CurveType) -> Vec<CurveType> {
+    split_curve(curve, &self.curves).into_iter().filter(|piece| {
+      let midpoint = piece.as_curve().sample(0.5);
+      self.touches(midpoint) || self.contains(midpoint)
+    }).collect()
+  }
+
+  // Check if world point lies on the region, within the accuracy of approximated intersection curves
+  fn contains(&self, p: Point3) -> bool {
+    let params = self.params(p);
+    self.surface.base.as_surface().sample(params.x, params.y).distance(p) < APPROXIMATION_TOLERANCE &&
+    self.surface.on_surface(params.x, params.y)
+  }
+
+  // Split region along the given world space curves, which are mapped into parameter space to find the regions they enclose.
+  // Surfaces face along the cross product of their derivatives, so outer rings run counter-clockwise in parameter space.
+  fn split(&self, cutters: Vec<CurveType>) -> Result<Vec<SplitRegion>, String> {
+    let mut curves = self.curves.clone();
+    // Faces wrapping around closed surfaces without a seam edge are cut open along a temporary seam
+    for axis in (0..2).filter(|&axis| self.spans(axis) && !self.curves.iter().any(|curve| self.on_seam(curve, axis) ) ) {
+      let mut seam = self.seam(axis);
+      if seam.is_empty() {
+        return Err("Faces wrapping around closed surfaces can't be cut open".into())
+      }
+      curves.append(&mut seam);
+    }
+    curves.extend(cutters);
+    let curves: Vec<CurveType> = curves.iter().flat_map(|curve| split_curve(curve, &curves) ).collect();
+    // Pieces still crossing the seam are split where they wrap around in parameter space.
+    // Approximated cutters pass slightly off the seam, so the seam is split and snapped onto their crossings.
+    let is_seam = |curve: &CurveType| (0..2).any(|axis| self.on_seam(curve, axis) );
+    let crossings: Vec<Vec<Point3>> = curves.iter().map(|curve|
+      if is_seam(curve) { vec![] } else { self.seam_crossings(curve) }
+    ).collect();
+    let points: Vec<Point3> = crossings.iter().flatten().cloned().collect();
+    let curves: Vec<CurveType> = curves.into_iter().zip(crossings).flat_map(|(curve, crossings)| {
+      if !crossings.is_empty() { return split_curve_at(&curve, crossings) }
+      if points.is_empty() || !is_seam(&curve) { return vec![curve] }
+      let base = curve.as_curve();
+      let hits = points.iter().filter(|p| base.sample(base.unsample(**p)).distance(**p) < APPROXIMATION_TOLERANCE ).cloned().collect();
+      split_curve_at(&curve, hits).into_iter().map(|piece| snap_ends(piece, &points) ).collect()
+    }).collect();
+    let mut graph = Graph::default();
+    let mut pieces: Vec<(Uuid, CurveType)> = vec![];
+    for piece in curves {
+      for parametric_curve in self.parametric_curves(&piece) {
+        pieces.push((parametric_curve.id(), piece.clone()));
+        graph.add_curve(parametric_curve);
+      }
+    }
+    graph.prune();
+    let world_curve = |curve: &CurveType| pieces.iter().find(|(id, _)| *id == curve.id() ).unwrap().1.clone();
+    Ok(graph.regions().into_iter()
+    .filter(|(_, point)| self.surface.on_surface(point.x, point.y) )
+    .map(|(rings, point)| {
+      let rings = rings.into_iter().map(|ring|
+        ring.into_iter().map(|(curve, is_forward)| (world_curve(&curve), is_forward) ).collect()
+      ).collect();
+      (rings, self.surface.base.as_surface().sample(point.x, point.y))
+    }).collect())
+  }
+
+  // Pieces of the isoline along the seam of a closed direction, that lie inside the region
+  fn seam(&self, axis: usize) -> Vec<CurveType> {
+    let other = 1 - axis;
+    let params = |t: f64, offset: f64| {
+      let mut params = [0.0; 2];
+      params[axis] = self.bounds[axis].0 + offset;
+      params[other] = t;
+      params
+    };
+    let surface = self.surface.base.as_surface();
+    let points: Vec<Point3> = (0..=PARAMETRIC_SEGMENTS).map(|i| {
+      let [u, v] = params(i as f64 / PARAMETRIC_SEGMENTS as f64, 0.0);
+      surface.sample(u, v)
+    }).collect();
+    let line = Line::new(points[0], *points.last().unwrap());
+    let isoline = if points.iter().all(|p| line.contains_point(*p) ) {
+      line.into_enum()
+    } else {
+      features::interpolate(&points).into_enum()
+    };
+    // Pieces are probed on both sides of the seam, as the domain ends right at it
+    split_curve(&isoline, &self.curves).into_iter().filter(|piece| {
+      let t = self.params(piece.as_curve().sample(0.5))[other];
+      [-SEAM_PROBE_OFFSET, SEAM_PROBE_OFFSET].iter().any(|&offset| {
+        let [u, v] = params(t, offset);
+        self.surface.on_surface(u, v)
+      })
+    }).collect()
+  }
+
+  // Points where curve crosses the seam of a closed direction, found by bisecting samples on either side of it
+  fn seam_crossings(&self, curve: &CurveType) -> Vec<Point3> {
+    let base = curve.as_curve();
+    let mut crossings = vec![];
+    for axis in (0..2).filter(|&axis| self.spans(axis) ) {
+      let offset = |t: f64| (self.params(base.sample(t))[axis] - self.bounds[axis].0).rem_euclid(1.0);
+      let on_seam = |offset: f64| offset.almost(0.0) || offset.almost(1.0);
+      let params: Vec<f64> = (0..=PARAMETRIC_SEGMENTS).map(|i| i as f64 / PARAMETRIC_SEGMENTS as f64 ).collect();
+      let offsets: Vec<f64> = params.iter().map(|&t| offset(t) ).collect();
+      for i in 0..PARAMETRIC_SEGMENTS {
+        // Samples right on the seam cross it, if their neighbors lie on opposite sides
+        if i > 0 && on_seam(offsets[i]) {
+          if !on_seam(offsets[i - 1]) && !on_seam(offsets[i + 1]) && (offsets[i + 1] - offsets[i - 1]).abs() >= 0.5 {
+            crossings.push(base.sample(params[i]));
+          }
+          continue
+        }
+        if on_seam(offsets[i]) || on_seam(offsets[i + 1]) || (offsets[i + 1] - offsets[i]).abs() < 0.5 { continue }
+        let mut range = (params[i], params[i + 1]);
+        for _ in 0..SEAM_BISECTIONS {
+          let middle = (range.0 + range.1) / 2.0;
+          if (offset(middle) - offsets[i]).abs() < 0.5 {
+            range.0 = middle;
+          } else {
+            range.1 = middle;
+          }
+        }
+        crossings.push(base.sample((range.0 + range.1) / 2.0));
+      }
+    }
+    crossings
+  }
+
+  // Map world space curve into the parameter space of the domain.
+  // Curves running along a seam are found on both sides of the domain.
+  //XXX Curves through poles of the surface are not supported
+  fn parametric_curves(&self, curve: &CurveType) -> Vec<CurveType> {
+    let base = curve.as_curve();
+    let mut reference = self.params(base.sample(0.5));
+    let mut seam = None;
+    for axis in (0..2).filter(|&axis| self.closed[axis] ) {
+      let (min, max) = self.bounds[axis];
+      if self.spans(axis) {
+        let offset = (reference[axis] - min).rem_euclid(1.0);
+        if offset.almost(0.0) || offset.almost(1.0) {
+          seam = Some(axis);
+          reference[axis] = min;
+        } else {
+          reference[axis] = min + offset;
+        }
+      } else {
+        reference[axis] += ((min + max) / 2.0 - reference[axis]).round();
+      }
+    }
+    // Unwrap samples outwards from the midpoint of the curve
+    let segments = if let CurveType::Line(_) = curve { 1 } else { PARAMETRIC_SEGMENTS };
+    let unwrap = |i: usize, previous: Point3| {
+      let mut p = self.params(base.sample(i as f64 / segments as f64));
+      for axis in (0..2).filter(|&axis| self.closed[axis] ) {
+        p[axis] += (previous[axis] - p[axis]).round();
+      }
+      p
+    };
+    let mut points = vec![reference; segments + 1];
+    let mut previous = reference;
+    for i in (0..=segments).filter(|i| i * 2 >= segments ) {
+      points[i] = unwrap(i, previous);
+      previous = points[i];
+    }
+    previous = reference;
+    for i in (0..=segments).rev().filter(|i| i * 2 < segments ) {
+      points[i] = unwrap(i, previous);
+      previous = points[i];
+    }
+    let mut curves = vec![polyline_curve(points.clone())];
+    if let Some(axis) = seam {
+      curves.push(polyline_curve(points.into_iter().map(|mut p| {
+        p[axis] += 1.0;
+        p
+      }).collect()));
+    }
+    curves
+  }
+
+  fn interior_point(&self) -> Point3 {
+    let p = interior_point(&self.polygons).unwrap_or_else(|| Point3::new(
+      (self.bounds[0].0 + self.bounds[0].1) / 2.0,
+      (self.bounds[1].0 + self.bounds[1].1) / 2.0,
+      0.0,
+    ));
+    self.surface.base.as_surface().sample(p.x, p.y)
+  }
+
+  fn normal_at(&self, p: Point3) -> Vec3 {
+    let params = self.params(p);
+    let (du, dv) = self.surface.base.derivatives(params.x, params.y);
+    du.cross(dv).normalize()
+  }
+}
+
+// Move the ends of a curve onto nearby points
+fn snap_ends(curve: CurveType, points: &[Point3]) -> CurveType {
+  let snap = |p: Point3| points.iter().find(|other| other.distance(p) < APPROXIMATION_TOLERANCE ).cloned().unwrap_or(p);
+  match curve {
+    CurveType::Line(line) => Line::new(snap(line.points.0), snap(line.points.1)).into_enum(),
+    CurveType::Spline(mut spline) => {
+      let last = spline.controls.len() - 1;
+      spline.controls[0] = snap(spline.controls[0]);
+      spline.controls[last] = snap(spline.controls[last]);
+      spline.into_enum()
+    },
+    _ => curve,
+  }
+}
+
+// Curve through the given points, used to represent curves in parameter space
+fn polyline_curve(points: PolyLine) -> CurveType {
+  let n = points.len();
+  let mut spline = Spline::new(points);
+  spline.degree = 1;
+  spline.knots = Spline::clamped_knots(n, 1);
+  spline.into_enum()
+}
+
+
+/// Planar graph of curves, used to find the regions they enclose.
+
+#[derive(Default)]
+struct Graph {
+  points: Vec<Point3>,
+  edges: Vec<(CurveType, usize, usize)>,
+}
+
+impl Graph {
+  fn add_curve(&mut self, curve: CurveType) {
+    let (start, end) = curve.as_curve().endpoints();
+    let start = self.add_point(start);
+    let end = self.add_point(end);
+    if start == end && !curve.as_curve().is_closed() { return }
+    let midpoint = curve.as_curve().sample(0.5);
+    let is_duplicate = self.edges.iter().any(|(other, other_start, other_end)|
+      ((*other_start == start && *other_end == end) || (*other_start == end && *other_end == start)) &&
+      other.as_curve().sample(0.5).almost(midpoint)
+    );
+    if !is_duplicate {
+      self.edges.push((curve, start, end));
+    }
+  }
+
+  fn add_point(&mut self, p: Point3) -> usize {
+    self.points.iter().position(|other| other.almost(p) ).unwrap_or_else(|| {
+      self.points.push(p);
+      self.points.len() - 1
+    })
+  }
+
+  // Remove dangling edges
+  fn prune(&mut self) {
+    loop {
+      let mut degrees = vec![0; self.points.len()];
+      for (_, start, end) in &self.edges {
+        degrees[*start] += 1;
+        degrees[*end] += 1;
+      }
+      let len = self.edges.len();
+      self.edges.retain(|(_, start, end)| degrees[*start] > 1 && degrees[*end] > 1 );
+      if self.edges.len() == len { break }
+    }
+  }
+
+  // Half edges are indexed as 2 * edge for forward and 2 * edge + 1 for backward traversal
+  fn origin(&self, half_edge: usize) -> usize {
+    let (_, start, end) = &self.edges[half_edge / 2];
+    if half_edge % 2 == 0 { *start } else { *end }
+  }
+
+  fn segment(&self, half_edge: usize) -> Segment {
+    (self.edges[half_edge / 2].0.clone(), half_edge % 2 == 0)
+  }
+
+  fn polygon(&self, half_edges: &[usize]) -> PolyLine {
+    ring_polygon(half_edges.iter().map(|&he| {
+      let (curve, _, _) = &self.edges[he / 2];
+      (curve, he % 2 == 0)
+    }))
+  }
+
+  // Find all bounded regions, along with an interior point of each
+  fn regions(&self) -> Vec<SplitRegion> {
+    let num_half_edges = self.edges.len() * 2;
+    // Sort outgoing half edges counter-clockwise around their origin
+    let mut outgoing: Vec<Vec<(usize, f64)>> = vec![vec![]; self.points.len()];
+    for he in 0..num_half_edges {
+      let curve = self.edges[he / 2].0.as_curve();
+      let (t0, t1) = if he % 2 == 0 { (0.0, DEPARTURE_OFFSET) } else { (1.0, 1.0 - DEPARTURE_OFFSET) };
+      let direction = curve.sample(t1) - curve.sample(t0);
+      outgoing[self.origin(he)].push((he, direction.y.atan2(direction.x)));
+    }
+    for half_edges in &mut outgoing {
+      half_edges.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap() );
+    }
+    // Continue with the outgoing half edge that lies clockwise next to the incoming one
+    let next = |he: usize| -> usize {
+      let twin = he ^ 1;
+      let half_edges = &outgoing[self.origin(twin)];
+      let i = half_edges.iter().position(|(other, _)| *other == twin ).unwrap();
+      half_edges[(i + half_edges.len() - 1) % half_edges.len()].0
+    };
+    // Trace loops
+    let mut visited = vec![false; num_half_edges];
+    let mut loops: Vec<Vec<usize>> = vec![];
+    for start in 0..num_half_edges {
+      let mut half_edges = vec![];
+      let mut he = start;
+      while !visited[he] {
+        visited[he] = true;
+        half_edges.push(he);
+        he = next(he);
+      }
+      if !half_edges.is_empty() {
+        loops.push(half_edges);
+      }
+    }
+    // Find connected components
+    let mut parents: Vec<usize> = (0..self.points.len()).collect();
+    for (_, start, end) in &self.edges {
+      let root = find_root(&mut parents, *start);
+      let other_root = find_root(&mut parents, *end);
+      parents[root] = other_root;
+    }
+    let components: Vec<usize> = loops.iter().map(|half_edges| find_root(&mut parents, self.origin(half_edges[0])) ).collect();
+    // Counter-clockwise loops bound regions, clockwise ones form holes in other components
+    let polygons: Vec<PolyLine> = loops.iter().map(|half_edges| self.polygon(half_edges) ).collect();
+    let areas: Vec<f64> = polygons.iter().map(geom2d::signed_polygon_area).collect();
+    let outers: Vec<usize> = (0..loops.len()).filter(|&i| areas[i] > 0.0 ).collect();
+    let mut holes: Vec<Vec<usize>> = vec![vec![]; loops.len()];
+    for i in (0..loops.len()).filter(|&i| areas[i] <= 0.0 ) {
+      let p = self.points[self.origin(loops[i][0])];
+      let container = outers.iter()
+      .filter(|&&j| components[j] != components[i] && geom2d::polygon_contains_point(&polygons[j], p) )
+      .min_by(|&&a, &&b| areas[a].partial_cmp(&areas[b]).unwrap() );
+      if let Some(&j) = container {
+        holes[j].push(i);
+      }
+    }
+    outers.iter().filter_map(|&i| {
+      let mut rings = vec![loops[i].iter().map(|&he| self.segment(he) ).collect()];
+      let mut ring_polygons = vec![polygons[i].clone()];
+      for &j in &holes[i] {
+        rings.push(loops[j].iter().map(|&he| self.segment(he) ).collect());
+        ring_polygons.push(polygons[j].clone());
+      }
+      interior_point(&ring_polygons).map(|p| (rings, p) )
+    }).collect()
+  }
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+  while parents[i] != i {
+    parents[i] = parents[parents[i]];
+    i = parents[i];
+  }
+  i
+}
+
+
+fn split_face(face: &Face, other: &Operand, is_tool: bool) -> Result<Vec<(Fragment, Location)>, String> {
+  let region = Region::new(face);
+  // Collect curves along which the face needs to be split
+  let mut cutters = vec![];
+  let mut coplanar = vec![];
+  for (other_face, other_region) in other.faces.iter().zip(&other.regions) {
+    if let (Region::Planar(region), Region::Planar(other_region)) = (&region, other_region) {
+      match intersection::plane_plane(&region.plane, &other_region.plane) {
+        Some(SurfaceIntersectionType::Contained) => {
+          for curve in other_region.world_curves() {
+            cutters.append(&mut region.clip(&curve));
+          }
+          let is_same = region.plane.normal().dot(other_region.plane.normal()) > 0.0;
+          coplanar.push((other_region, is_same));
+        },
+        Some(isect) => if let Some(line) = isect.get_line() {
+          let line = extend_line(line, &[&region.bounds, &other_region.bounds]);
+          for piece in other_region.clip(&line.into_enum()) {
+            cutters.append(&mut region.clip(&piece));
+          }
+        },
+        None => {},
+      }
+    } else {
+      // Surfaces are intersected in the same order for both operands, so they agree on the resulting curves
+      let other_surface = &other_face.borrow().surface;
+      let intersections = if is_tool {
+        other_surface.intersect(&face.surface)
+      } else {
+        face.surface.intersect(other_surface)
+      };
+      for isect in intersections {
+        match isect {
+          SurfaceIntersectionType::Cross(curve) | SurfaceIntersectionType::Touch(curve) => {
+            for piece in other_region.clip(&curve) {
+              cutters.append(&mut region.clip(&piece));
+            }
+          },
+          // Coincident faces are split along each other's boundary, apart from seams, and located by probing both sides
+          SurfaceIntersectionType::Contained => if let Region::Curved(other_region) = other_region {
+            for curve in other_region.curves.iter().filter(|curve| !(0..2).any(|axis| other_region.on_seam(curve, axis) ) ) {
+              cutters.append(&mut region.clip(curve));
+            }
+          },
+          SurfaceIntersectionType::Extended(_) => {},
+        }
+      }
+    }
+  }
+  let regions = if cutters.is_empty() { vec![] } else { region.split(cutters)? };
+  if regions.is_empty() {
+    let point = region.interior_point();
+    return Ok(vec![(Fragment::from_face(face, point), other.locate(point, region.normal_at(point), &coplanar))])
+  }
+  Ok(regions.into_iter().enumerate().map(|(i, (rings, point))| {
+    let fragment = Fragment {
+      // First fragment inherits the identity of the original face
+      id: if i == 0 { Some(face.id) } else { None },
+      surface: face.surface.clone(),
+      rings,
+      point,
+    };
+    let location = other.locate(point, region.normal_at(point), &coplanar);
+    (fragment, location)
+  }).collect())
+}
+
+
+// Split curve at its intersections with the given curves, as well as their endpoints
+fn split_curve(curve: &CurveType, cutters: &[CurveType]) -> Vec<CurveType> {
+  let base = curve.as_curve();
+  let (start, end) = base.endpoints();
+  let mut points: Vec<Point3> = vec![];
+  for cutter in cutters {
+    if std::ptr::eq(cutter, curve) { continue }
+    let mut hits: Vec<Point3> = base.intersect(cutter).iter().filter_map(|isect|
+      isect.get_splitting_intersection().map(|hit| hit.point )
+    ).collect();
+    let (cutter_start, cutter_end) = cutter.as_curve().endpoints();
+    hits.extend([cutter_start, cutter_end].into_iter().filter(|p| base.contains_point(*p) ));
+    for p in hits {
+      if !p.almost(start) && !p.almost(end) && !points.iter().any(|other| other.almost(p) ) {
+        points.push(p);
+      }
+    }
+  }
+  split_curve_at(curve, points)
+}
+
+// Split curve at the given points. Closed curves are opened at a single point.
+fn split_curve_at(curve: &CurveType, points: Vec<Point3>) -> Vec<CurveType> {
+  let base = curve.as_curve();
+  // Drop points too close to each other in parameter space to be split at
+  let mut params: Vec<f64> = points.iter().map(|p| base.unsample(*p) ).collect();
+  params.sort_by(|a, b| a.partial_cmp(b).unwrap() );
+  let mut last = 0.0;
+  let params: Vec<f64> = params.into_iter().filter(|&t| {
+    let is_valid = t > last + EPSILON && t < 1.0 - EPSILON;
+    if is_valid { last = t }
+    is_valid
+  }).collect();
+  let pieces = match (curve, &params[..]) {
+    (CurveType::Circle(circle), [t]) => vec![Arc::from_plane(circle.plane.clone(), circle.radius, *t, t + 1.0).into_enum()],
+    (CurveType::Ellipse(ellipse), [t]) => vec![EllipticalArc::from_plane(ellipse.plane.clone(), ellipse.radii, *t, t + 1.0).into_enum()],
+    _ => {
+      let points = params.into_iter().map(|t| base.sample(t) ).collect();
+      curve.split_at_points(&points).unwrap_or_else(|| vec![curve.clone()] )
+    },
+  };
+  pieces.into_iter().filter(|piece| piece.as_curve().length() > EPSILON ).collect()
+}
+
+
+fn extend_line(line: &Line, bounds: &[&Vec<Point3>]) -> Line {
+  let origin = line.points.0;
+  let direction = line.tangent();
+  let params: Vec<f64> = bounds.iter().flat_map(|points| points.iter() ).map(|p| (p - origin).dot(direction) ).collect();
+  let min = params.iter().cloned().fold(f64::INFINITY, f64::min);
+  let max = params.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+  Line::new(origin + direction * (min - 1.0), origin + direction * (max + 1.0))
+}
+
+
+fn face_segments(face: &Face) -> Vec<Vec<Segment>> {
+  face.rings.iter().map(|ring|
+    ring.borrow().iter().map(|he| half_edge_segment(&he.borrow()) ).collect()
+  ).collect()
+}
+
+fn half_edge_segment(half_edge: &HalfEdge) -> Segment {
+  let curve = half_edge.edge().borrow().curve.clone();
+  let start = half_edge.origin.borrow().point;
+  let end = half_edge.end_vertex().borrow().point;
+  let (curve_start, curve_end) = curve.as_curve().endpoints();
+  if curve_start.almost(start) && curve_end.almost(end) {
+    (curve, true)
+  } else if curve_start.almost(end) && curve_end.almost(start) {
+    (curve, false)
+  } else {
+    // Edge only covers a section of its curve
+    let trims = half_edge.make_curve().trims;
+    match &curve {
+      CurveType::Line(_) => (Line::new(start, end).into_enum(), true),
+      CurveType::Arc(arc) => (Arc::from_plane(arc.plane.clone(), arc.radius, arc.param_to_circle(trims.0), arc.param_to_circle(trims.1)).into_enum(), true),
+      CurveType::Circle(circle) => (Arc::from_plane(circle.plane.clone(), circle.radius, trims.0, trims.1).into_enum(), true),
+      CurveType::EllipticalArc(arc) => (EllipticalArc::from_plane(arc.plane.clone(), arc.radii, arc.param_to_ellipse(trims.0), arc.param_to_ellipse(trims.1)).into_enum(), true),
+      CurveType::Ellipse(ellipse) => (EllipticalArc::from_plane(ellipse.plane.clone(), ellipse.radii, trims.0, trims.1).into_enum(), true),
+      CurveType::Spline(_) | CurveType::Helix(_) => curve.split_at_points(&vec![start, end]).unwrap_or_default().into_iter().find_map(|piece| {
+        let (piece_start, piece_end) = piece.as_curve().endpoints();
+        if piece_start.almost(start) && piece_end.almost(end) {
+          Some((piece, true))
+        } else if piece_start.almost(end) && piece_end.almost(start) {
+          Some((piece, false))
+        } else { None }
+      }).unwrap_or((Line::new(start, end).into_enum(), true)),
+    }
+  }
+}
+
+fn segment_polyline(curve: &CurveType, is_forward: bool) -> PolyLine {
+  let mut polyline = curve.as_curve().tesselate();
+  if polyline.len() < 2 {
+    polyline = tuple2_to_vec(curve.as_curve().endpoints());
+  }
+  if !is_forward {
+    polyline.reverse();
+  }
+  polyline
+}
+
+fn ring_polygon<'a>(segments: impl Iterator<Item = (&'a CurveType, bool)>) -> PolyLine {
+  segments.flat_map(|(curve, is_forward)| {
+    let mut polyline = segment_polyline(curve, is_forward);
+    polyline.pop();
+    polyline
+  }).collect()
+}
+
+// Find a point well inside the area bounded by the given polygons, using the centroid of the largest triangle
+fn interior_point(polygons: &[PolyLine]) -> Option<Point3> {
+  let mut vertices = vec![];
+  let mut holes = vec![];
+  for polygon in polygons {
+    if !vertices.is_empty() {
+      holes.push(vertices.len());
+    }
+    vertices.extend(polygon.iter().cloned());
+  }
+  let mesh = geom2d::tesselate_polygon(vertices, holes);
+  mesh.faces.chunks(3).map(|triangle| {
+    let a = mesh.vertices[triangle[0]];
+    let b = mesh.vertices[triangle[1]];
+    let c = mesh.vertices[triangle[2]];
+    let area = geom2d::cross_2d(b - a, c - a).abs();
+    (area, Point3::from_vec((a.to_vec() + b.to_vec() + c.to_vec()) / 3.0))
+  }).max_by(|a, b| a.0.partial_cmp(&b.0).unwrap() ).map(|(_, p)| p )
+}
+
+
+struct StitchedHalfEdge {
+  half_edge: Ref<HalfEdge>,
+  start: usize,
+  end: usize,
+  midpoint: Point3,
+  curve: CurveType,
+  face: usize,
+}
+
+// Build topology from fragments, merging coincident vertices and edges
+fn stitch(mut fragments: Vec<Fragment>, id: Uuid) -> Result<Vec<Solid>, String> {
+  // Split edges at the vertices of neighboring fragments, where faces were cut at different points,
+  // like curved faces along their seams. Vertices of closed edges are arbitrary and thus ignored.
+  let corners: Vec<Point3> = fragments.iter()
+  .flat_map(|fragment| fragment.rings.iter().flatten() )
+  .filter(|(curve, _)| !curve.as_curve().is_closed() )
+  .flat_map(|(curve, _)| tuple2_to_vec(curve.as_curve().endpoints()) )
+  .collect();
+  for ring in fragments.iter_mut().flat_map(|fragment| fragment.rings.iter_mut() ) {
+    *ring = ring.iter().flat_map(|(curve, is_forward)| {
+      let (start, end) = curve.as_curve().endpoints();
+      let points: Vec<Point3> = corners.iter().filter(|p|
+        !p.almost(start) && !p.almost(end) && curve.as_curve().contains_point(**p)
+      ).cloned().collect();
+      let mut pieces = if points.is_empty() { vec![curve.clone()] } else { split_curve_at(curve, points) };
+      if !is_forward {
+        pieces.reverse();
+      }
+      pieces.into_iter().map(|piece| (piece, *is_forward) ).collect::<Vec<_>>()
+    }).collect();
+  }
+  let mut vertices: Vec<Ref<Vertex>> = vec![];
+  let mut half_edges: Vec<StitchedHalfEdge> = vec![];
+  let mut faces = vec![];
+  let mut points = vec![];
+  for (face_index, fragment) in fragments.into_iter().enumerate() {
+    let mut rings = vec![];
+    for ring in &fragment.rings {
+      // Half Edges
+      let mut ring_half_edges = vec![];
+      for (curve, is_forward) in ring {
+        let (mut start, mut end) = curve.as_curve().endpoints();
+        if !is_forward {
+          std::mem::swap(&mut start, &mut end);
+        }
+        let start = add_vertex(&mut vertices, start);
+        let end = add_vertex(&mut vertices, end);
+        let vertex = &vertices[start];
+        let half_edge = rc(HalfEdge {
+          id: Uuid::new_v4(),
+          next: Weak::new(),
+          previous: Weak::new(),
+          origin: vertex.clone(),
+          edge: Weak::new(),
+          ring: Weak::new(),
+        });
+        if vertex.borrow().half_edge.upgrade().is_none() {
+          vertex.borrow_mut().half_edge = Rc::downgrade(&half_edge);
+        }
+        half_edges.push(StitchedHalfEdge {
+          half_edge: half_edge.clone(),
+          start,
+          end,
+          midpoint: curve.as_curve().sample(0.5),
+          curve: curve.clone(),
+          face: face_index,
+        });
+        ring_half_edges.push(half_edge);
+      }
+
+      // Connect Half Edges in a loop
+      let len = ring_half_edges.len();
+      for i in 0..len {
+        let mut he = ring_half_edges[i].borrow_mut();
+        he.next = Rc::downgrade(&ring_half_edges[(i + 1) % len]);
+        he.previous = Rc::downgrade(&ring_half_edges[(len + i - 1) % len]);
+      }
+      let out_ring = rc(Ring {
+        half_edge: ring_half_edges[0].clone(),
+        face: Weak::new(),
+      });
+
+      // Connect Half Edges to Rings
+      for he in ring_half_edges {
+        he.borrow_mut().ring = Rc::downgrade(&out_ring);
+      }
+      rings.push(out_ring);
+    }
+
+    let out_face = rc(Face {
+      id: fragment.id.unwrap_or_else(Uuid::new_v4),
+      outer_ring: rings[0].clone(),
+      rings: rings.clone(),
+      surface: fragment.surface,
+      flip_normal: false,
+    });
+
+    // Connect rings to face
+    for ring in rings {
+      ring.borrow_mut().face = Rc::downgrade(&out_face);
+    }
+    faces.push(out_face);
+    points.push(fragment.point);
+  }
+
+  // Pair opposing Half Edges into Edges
+  let mut is_paired = vec![false; half_edges.len()];
+  let mut edges = vec![];
+  let mut parents: Vec<usize> = (0..faces.len()).collect();
+  for i in 0..half_edges.len() {
+    if is_paired[i] { continue }
+    let this = &half_edges[i];
+    let j = (i + 1..half_edges.len()).find(|&j| {
+      let other = &half_edges[j];
+      !is_paired[j] && other.start == this.end && other.end == this.start && other.midpoint.almost(this.midpoint)
+    }).ok_or("Boolean operation produced an open shell")?;
+    is_paired[i] = true;
+    is_paired[j] = true;
+    let other = &half_edges[j];
+    let edge = rc(Edge {
+      id: Uuid::new_v4(),
+      left_half: this.half_edge.clone(),
+      right_half: other.half_edge.clone(),
+      curve: this.curve.clone(),
+    });
+    this.half_edge.borrow_mut().edge = Rc::downgrade(&edge);
+    other.half_edge.borrow_mut().edge = Rc::downgrade(&edge);
+    let root = find_root(&mut parents, this.face);
+    let other_root = find_root(&mut parents, other.face);
+    parents[root] = other_root;
+    edges.push((edge, this.face));
+  }
+
+  // Form shells from connected faces
+  let roots: Vec<usize> = (0..faces.len()).map(|i| find_root(&mut parents, i) ).collect();
+  let mut shells: Vec<(Shell, Point3)> = vec![];
+  let mut shell_roots = vec![];
+  for (i, face) in faces.iter().enumerate() {
+    let shell_index = shell_roots.iter().position(|&root| root == roots[i] ).unwrap_or_else(|| {
+      shell_roots.push(roots[i]);
+      shells.push((Shell { faces: vec![], edges: vec![], vertices: vec![] }, points[i]));
+      shells.len() - 1
+    });
+    shells[shell_index].0.faces.push(face.clone());
+  }
+  for (edge, face) in edges {
+    let shell_index = shell_roots.iter().position(|&root| root == roots[face] ).unwrap();
+    shells[shell_index].0.edges.push(edge);
+  }
+  for he in &half_edges {
+    let shell_index = shell_roots.iter().position(|&root| root == roots[he.face] ).unwrap();
+    let shell = &mut shells[shell_index].0;
+    if !shell.vertices.iter().any(|vertex| Rc::ptr_eq(vertex, &he.half_edge.borrow().origin) ) {
+      shell.vertices.push(he.half_edge.borrow().origin.clone());
+    }
+  }
+
+  for (shell, _) in &mut shells {
+    merge_faces(shell);
+  }
+
+  // Shells contained in an odd number of other shells form cavities
+  let containers: Vec<Vec<usize>> = shells.iter().enumerate().map(|(i, (_, point))|
+    (0..shells.len()).filter(|&j| j != i && volume::encloses(&shells[j].0, *point) ).collect()
+  ).collect();
+  let mut solids: Vec<Solid> = vec![];
+  let mut solid_indices = vec![None; shells.len()];
+  for i in (0..shells.len()).filter(|&i| containers[i].len() % 2 == 0 ) {
+    solid_indices[i] = Some(solids.len());
+    solids.push(Solid {
+      id: if solids.is_empty() { id } else { Uuid::new_v4() },
+      shells: vec![shells[i].0.clone()],
+    });
+  }
+  for i in (0..shells.len()).filter(|&i| containers[i].len() % 2 == 1 ) {
+    let parent = containers[i].iter()
+    .filter(|&&j| solid_indices[j].is_some() )
+    .max_by_key(|&&j| containers[j].len() )
+    .ok_or("Boolean operation produced an unbounded cavity")?;
+    solids[solid_indices[*parent].unwrap()].shells.push(shells[i].0.clone());
+  }
+  for solid in &solids {
+    solid.validate()?;
+  }
+  Ok(solids)
+}
+
+// Merge adjacent faces sharing the same plane and remove the vertices between collinear edges
+fn merge_faces(shell: &mut Shell) {
+  while let Some(edge) = shell.edges.iter().find(|edge| {
+    let edge = edge.borrow();
+    let left = edge.left_face();
+    let right = edge.right_face();
+    !Rc::ptr_eq(&left, &right) && is_coplanar(&left.borrow().surface, &right.borrow().surface)
+  }).cloned() {
+    shell.lkef(&edge);
+  }
+  // Clean up edges left inside of merged faces
+  while let Some(edge) = shell.edges.iter().find(|edge| {
+    let edge = edge.borrow();
+    let face = edge.left_face();
+    let is_planar = matches!(face.borrow().surface, SurfaceType::Planar(_));
+    is_planar && Rc::ptr_eq(&face, &edge.right_face()) &&
+    Rc::ptr_eq(&edge.left_half.borrow().ring.upgrade().unwrap(), &edge.right_half.borrow().ring.upgrade().unwrap())
+  }).cloned() {
+    let (he1, he2) = {
+      let edge = edge.borrow();
+      (edge.left_half.clone(), edge.right_half.clone())
+    };
+    if Rc::ptr_eq(&he1.borrow().next(), &he2) {
+      let vertex = he2.borrow().origin.clone();
+      shell.lkev(&edge, &vertex);
+    } else if Rc::ptr_eq(&he2.borrow().next(), &he1) {
+      let vertex = he1.borrow().origin.clone();
+      shell.lkev(&edge, &vertex);
+    } else {
+      let face = he1.borrow().face();
+      let ring = shell.lkemr(&edge);
+      if ring_area(&ring.borrow()) > 0.0 {
+        face.borrow_mut().outer_ring = ring;
+      }
+    }
+  }
+  // Merge collinear lines
+  while let Some(vertex) = shell.vertices.iter().find(|vertex| collinear_edges(&vertex.borrow()).is_some() ).cloned() {
+    let (he, other_he) = collinear_edges(&vertex.borrow()).unwrap();
+    let end = other_he.borrow().end_vertex().borrow().point;
+    let other_end = he.borrow().end_vertex().borrow().point;
+    let edge = he.borrow().edge();
+    let other_edge = other_he.borrow().edge();
+    shell.lkev(&edge, &vertex);
+    let mut line = Line::new(end, other_end);
+    line.id = other_edge.borrow().curve.id();
+    other_edge.borrow_mut().curve = line.into_enum();
+  }
+}
+
+fn is_coplanar(surface: &SurfaceType, other: &SurfaceType) -> bool {
+  if let (SurfaceType::Planar(surface), SurfaceType::Planar(other)) = (surface, other) {
+    surface.plane.normal().almost(other.plane.normal()) && surface.plane.contains_point(other.plane.origin)
+  } else {
+    false
+  }
+}
+
+// Signed area of a planar ring, as seen against the normal of its face
+fn ring_area(ring: &Ring) -> f64 {
+  let face = ring.face.upgrade().unwrap();
+  let face = face.borrow();
+  if let SurfaceType::Planar(surface) = &face.surface {
+    let to_local = surface.plane.as_transform().invert().unwrap();
+    let segments: Vec<Segment> = ring.iter().map(|he| {
+      let (mut curve, is_forward) = half_edge_segment(&he.borrow());
+      curve.as_curve_mut().transform(&to_local);
+      (curve, is_forward)
+    }).collect();
+    geom2d::signed_polygon_area(&ring_polygon(segments.iter().map(|(curve, is_forward)| (curve, *is_forward) )))
+  } else {
+    0.0
+  }
+}
+
+// Find both half edges emanating from a vertex that joins exactly two collinear lines
+fn collinear_edges(vertex: &Vertex) -> Option<(Ref<HalfEdge>, Ref<HalfEdge>)> {
+  let half_edges: Vec<Ref<HalfEdge>> = vertex.edges_iter().take(3).collect();
+  if half_edges.len() != 2 { return None }
+  let directions: Vec<Vec3> = half_edges.iter().filter_map(|he| {
+    let he = he.borrow();
+    if let CurveType::Line(_) = he.edge().borrow().curve {
+      Some((he.end_vertex().borrow().point - vertex.point).normalize())
+    } else { None }
+  }).collect();
+  if directions.len() == 2 && directions[0].almost(-directions[1]) {
+    Some((half_edges[0].clone(), half_edges[1].clone()))
+  } else {
+    None
+  }
+}
+
+fn solid_bounds(solid: &Solid) -> (Point3, Point3) {
+  let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+  let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+  for edge in &solid.shells[0].edges {
+    for p in edge.borrow().curve.as_curve().tesselate() {
+      min = Point3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+      max = Point3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
     }
   }
+  (min, max)
+}
+
+fn bounds_overlap(bounds: (Point3, Point3), other: (Point3, Point3)) -> bool {
+  bounds.0.x <= other.1.x + EPSILON && other.0.x <= bounds.1.x + EPSILON &&
+  bounds.0.y <= other.1.y + EPSILON && other.0.y <= bounds.1.y + EPSILON &&
+  bounds.0.z <= other.1.z + EPSILON && other.0.z <= bounds.1.z + EPSILON
+}
+
+fn add_vertex(vertices: &mut Vec<Ref<Vertex>>, p: Point3) -> usize {
+  vertices.iter().position(|vertex| vertex.borrow().point.almost(p) ).unwrap_or_else(|| {
+    vertices.push(rc(Vertex {
+      point: p,
+      half_edge: Weak::new(),
+    }));
+    vertices.len() - 1
+  })
 }
 
 
 #[cfg(test)]
 mod tests {
+  use super::*;
+
+  fn make_box(origin: Point3, size: Vec3) -> Solid {
+    let points = vec![
+      origin,
+      origin + Vec3::new(size.x, 0.0, 0.0),
+      origin + Vec3::new(size.x, size.y, 0.0),
+      origin + Vec3::new(0.0, size.y, 0.0),
+    ];
+    let wire = (0..4).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    ).collect();
+    features::extrude(&Profile::new(Plane::from_point(origin), vec![Wire::new(wire)]), size.z).unwrap()
+  }
+
+  fn make_cylinder(center: Point3, radius: f64, height: f64) -> Solid {
+    let wire = Wire::new(vec![TrimmedCurve::new(Circle::new(center, radius).into_enum())]);
+    features::extrude(&Profile::new(Plane::from_point(center), vec![wire]), height).unwrap()
+  }
+
+  // Cylinder of radius 0.5 along the y axis, crossing the unit cylinder at half the height of 4
+  fn make_cross_cylinder() -> Solid {
+    let plane = Plane { origin: Point3::new(0.0, -3.0, 2.0), u: Vec3::unit_z(), v: Vec3::unit_x() };
+    let wire = Wire::new(vec![TrimmedCurve::new(Circle::from_plane(plane.clone(), 0.5).into_enum())]);
+    features::extrude(&Profile::new(plane, vec![wire]), 6.0).unwrap()
+  }
+
+  fn count(solid: &Solid) -> (usize, usize, usize) {
+    solid.shells.iter().fold((0, 0, 0), |acc, shell|
+      (acc.0 + shell.vertices.len(), acc.1 + shell.edges.len(), acc.2 + shell.faces.len())
+    )
+  }
+
+  #[test]
+  fn cut_corner() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    compound.cut(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(count(solid), (14, 21, 9));
+  }
+
+  #[test]
+  fn cut_through() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(3.0, 3.0, 1.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, -1.0), Vec3::new(1.0, 1.0, 3.0)).into_compound();
+    compound.cut(tool).unwrap();
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(count(solid), (16, 24, 10));
+    assert_eq!(solid.shells[0].genus(), 1);
+    assert_eq!(solid.faces_iter().filter(|face| face.borrow().rings.len() == 2 ).count(), 2);
+  }
+
+  #[test]
+  fn cut_pocket() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(3.0, 3.0, 2.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.cut(tool).unwrap();
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(count(solid), (16, 24, 11));
+    assert_eq!(solid.shells[0].genus(), 0);
+  }
 
+  #[test]
+  fn cut_cavity() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(3.0, 3.0, 3.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.cut(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    assert_eq!(compound.solids[0].shells.len(), 2);
+    compound.solids[0].validate().unwrap();
+    assert!((compound.volume() - 26.0).abs() < 1.0e-9);
+    assert_eq!(compound.classify_point(Point3::new(1.5, 1.5, 1.5), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(0.5, 1.5, 1.5), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.5, 1.5), EPSILON), Containment::OnBoundary);
+  }
+
+  #[test]
+  fn cut_disjoint() {
+    let solid = make_box(Point3::origin(), Vec3::new(1.0, 1.0, 1.0));
+    let id = solid.id;
+    let mut compound = solid.into_compound();
+    let tool = make_box(Point3::new(5.0, 5.0, 5.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.cut(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    assert_eq!(compound.solids[0].id, id);
+    assert_eq!(count(&compound.solids[0]), (8, 12, 6));
+  }
+
+  #[test]
+  fn cut_drilled_hole() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 1.0)).into_compound();
+    let tool = make_cylinder(Point3::new(1.0, 1.0, -1.0), 0.5, 3.0).into_compound();
+    compound.cut(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    assert_eq!(solid.faces_iter().filter(|face| face.borrow().rings.len() == 2 ).count(), 2);
+    assert!((compound.volume() - (4.0 - std::f64::consts::PI * 0.25)).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.0, 0.5), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.6, 0.5), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.5, 0.5), EPSILON), Containment::OnBoundary);
+  }
+
+  #[test]
+  fn cut_blind_hole() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 1.0)).into_compound();
+    let tool = make_cylinder(Point3::new(1.0, 1.0, 0.5), 0.5, 1.0).into_compound();
+    compound.cut(tool).unwrap();
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 0);
+    assert!((compound.volume() - (4.0 - std::f64::consts::PI * 0.125)).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.0, 0.75), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.0, 0.25), EPSILON), Containment::Inside);
+  }
+
+  #[test]
+  fn cut_cross_hole_cylinder() {
+    let mut compound = make_cylinder(Point3::origin(), 1.0, 4.0).into_compound();
+    compound.cut(make_cross_cylinder().into_compound()).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    // 4π minus the integral of 4 * sqrt(1 - x²) * sqrt(0.25 - x²) over [-0.5, 0.5].
+    // Faces bounded by intersection curves are integrated over polygonal domains.
+    assert!((compound.volume() - 11.046331).abs() < 1.0e-2);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 0.0, 2.0), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 0.9, 2.0), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 0.0, 3.0), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(0.75, 0.0, 2.0), EPSILON), Containment::Inside);
+  }
+
+  #[test]
+  fn cut_cross_hole_box() {
+    let mut compound = make_box(Point3::new(-1.0, -1.0, 0.0), Vec3::new(2.0, 2.0, 4.0)).into_compound();
+    compound.cut(make_cross_cylinder().into_compound()).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    let solid = &compound.solids[0];
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    assert_eq!(solid.faces_iter().filter(|face| face.borrow().rings.len() == 2 ).count(), 2);
+    assert!((compound.volume() - (16.0 - std::f64::consts::PI * 0.5)).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 0.0, 2.0), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 0.0, 3.0), EPSILON), Containment::Inside);
+  }
+
+  #[test]
+  fn coaxial_cylinders() {
+    // Stacked cylinders share their wall between heights 1 and 2
+    let cylinder = make_cylinder(Point3::origin(), 1.0, 2.0);
+    let stacked = make_cylinder(Point3::new(0.0, 0.0, 1.0), 1.0, 2.0);
+    let pi = std::f64::consts::PI;
+    for (op, volume) in [(BooleanType::Join, 3.0 * pi), (BooleanType::Cut, pi), (BooleanType::Intersection, pi)] {
+      let mut compound = cylinder.clone().into_compound();
+      compound.boolean(stacked.clone().into_compound(), op).unwrap();
+      assert_eq!(compound.solids.len(), 1);
+      compound.solids[0].validate().unwrap();
+      assert!((compound.volume() - volume).abs() < 1.0e-6);
+    }
+    // Cylinder wall coincides with the whole side of the inner one
+    let mut compound = cylinder.clone().into_compound();
+    compound.cut(make_cylinder(Point3::new(0.0, 0.0, 0.5), 1.0, 1.0).into_compound()).unwrap();
+    assert_eq!(compound.solids.len(), 2);
+    for solid in &compound.solids {
+      solid.validate().unwrap();
+    }
+    assert!((compound.volume() - pi).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn join_cylinder() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 1.0)).into_compound();
+    let tool = make_cylinder(Point3::new(1.0, 1.0, 0.5), 0.5, 1.0).into_compound();
+    compound.join(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert!((compound.volume() - (4.0 + std::f64::consts::PI * 0.125)).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.0, 1.25), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(1.0, 1.6, 1.25), EPSILON), Containment::Outside);
+  }
+
+  #[test]
+  fn intersect_cylinder() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 1.0)).into_compound();
+    let tool = make_cylinder(Point3::new(1.0, 1.0, 0.5), 0.5, 1.0).into_compound();
+    compound.intersect(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert!((compound.volume() - std::f64::consts::PI * 0.125).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn intersect_boxes() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    compound.intersect(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert_eq!(count(&compound.solids[0]), (8, 12, 6));
+  }
+
+  #[test]
+  fn join_stacked() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    let tool = make_box(Point3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.join(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert_eq!(count(&compound.solids[0]), (8, 12, 6));
+  }
+
+  #[test]
+  fn join_overlapping() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    compound.join(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert_eq!(count(&compound.solids[0]), (20, 30, 12));
+  }
+
+  #[test]
+  fn join_touching() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 1.0)).into_compound();
+    let tool = make_box(Point3::new(0.5, 0.5, 1.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.join(tool).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert_eq!(count(&compound.solids[0]), (16, 24, 11));
+  }
+
+  #[test]
+  fn join_disjoint() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    let tool = make_box(Point3::new(0.0, 0.0, 2.0), Vec3::new(1.0, 1.0, 1.0)).into_compound();
+    compound.join(tool).unwrap();
+    assert_eq!(compound.solids.len(), 2);
+  }
+
+  #[test]
+  fn join_unfusable() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    let mut tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 2.0, 2.0));
+    tool.shells[0].faces.pop();
+    assert!(compound.join(tool.into_compound()).is_err());
+  }
+
+  #[test]
+  fn join_cross_cylinder() {
+    let mut compound = make_box(Point3::new(-1.0, -1.0, 0.0), Vec3::new(2.0, 2.0, 4.0)).into_compound();
+    compound.join(make_cross_cylinder().into_compound()).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    assert!((compound.volume() - (16.0 + std::f64::consts::PI)).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 2.0, 2.0), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(0.0, 2.0, 3.0), EPSILON), Containment::Outside);
+  }
+
+  #[test]
+  fn join_overlapping_cylinders() {
+    let mut compound = make_cylinder(Point3::origin(), 1.0, 1.0).into_compound();
+    compound.join(make_cylinder(Point3::new(1.0, 0.0, 0.0), 1.0, 1.0).into_compound()).unwrap();
+    assert_eq!(compound.solids.len(), 1);
+    compound.solids[0].validate().unwrap();
+    // Two unit disks minus their lens shaped overlap of 2π/3 - √3/2
+    let volume = 2.0 * std::f64::consts::PI - (2.0 * std::f64::consts::PI / 3.0 - 3.0f64.sqrt() / 2.0);
+    assert!((compound.volume() - volume).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(0.5, 0.0, 0.5), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(0.5, 0.9, 0.5), EPSILON), Containment::Outside);
+  }
+
+  #[test]
+  fn difference_boxes() {
+    let mut compound = make_box(Point3::origin(), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    let tool = make_box(Point3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 2.0, 2.0)).into_compound();
+    compound.difference(tool).unwrap();
+    assert_eq!(compound.solids.len(), 2);
+    for solid in &compound.solids {
+      solid.validate().unwrap();
+      assert_eq!(count(solid), (14, 21, 9));
+    }
+    // Both boxes lose their shared unit cube
+    assert!((compound.volume() - 14.0).abs() < 1.0e-6);
+    assert_eq!(compound.classify_point(Point3::new(1.5, 1.5, 1.5), EPSILON), Containment::Outside);
+    assert_eq!(compound.classify_point(Point3::new(0.5, 0.5, 0.5), EPSILON), Containment::Inside);
+    assert_eq!(compound.classify_point(Point3::new(2.5, 2.5, 2.5), EPSILON), Containment::Inside);
+  }
 }
@@ -1,15 +1,58 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
 use crate::transform::*;
 use crate::solid::*;
 use crate::surface::intersection;
 use crate::surface::SurfaceType;
+use crate::geom2d;
+use super::volume::{ray_face_intersections, ray_surface_intersections};
+
+
+// Maximum deviation of the dot product between tangents, for curves to count as tangent continuous
+const TANGENT_TOLERANCE: f64 = 1.0e-6;
+
+// Maximum distance between guide rails and the profile vertices they pass through
+const RAIL_TOLERANCE: f64 = 1.0e-3;
+
+// Maximum distance of blend geometry from the faces it is supposed to lie on
+const BLEND_TOLERANCE: f64 = 1.0e-6;
+
+// Number of cross sections used to approximate blends that can't be represented exactly
+const BLEND_SECTIONS: usize = 9;
+
+// Number of iterations used to place the rolling ball of a fillet onto curved faces
+const BLEND_ITERATIONS: usize = 10;
+
+// Number of samples used to approximate offset curves and surfaces
+const OFFSET_SAMPLES: usize = 9;
+
+// Weight that keeps offset vertices close to their originals, when fewer than three faces fix their location
+const OFFSET_REGULARIZATION: f64 = 1.0e-9;
+
+// Relative distance by which through-all extrusions overshoot the bodies they pass through
+const THROUGH_ALL_CLEARANCE: f64 = 0.01;
+
+// Number of grid steps across the bounding box of a profile, when sampling its interior
+const PROFILE_SAMPLES: usize = 16;
+
+// Number of samples used to rebuild closed edges of end caps that follow curved or inclined faces
+const CAP_SAMPLES: usize = 33;
 
 
 /// Create a new [Solid] by extruding `profile` orthogonally to its plane by the given `distance`.
+///
+/// Inner rings of the profile turn into holes through the solid.
 
 pub fn extrude(profile: &Profile, distance: f64) -> Result<Solid, String> {
-  let wire = profile.rings[0].clone();
+  if distance.almost(0.0) {
+    return Err("Extrusion distance must not be zero".into())
+  }
   let vec = profile.plane.normal() * distance;
-  let mut solid = Solid::lamina(wire, PlanarSurface::new(profile.plane.clone()).into_enum());
+  let mut solid = Solid::lamina(profile.rings.clone(), PlanarSurface::new(profile.plane.clone()).into_enum());
   let shell = &mut solid.shells[0];
   let is_forward = distance >= 0.0;
   let face = if is_forward {
@@ -21,124 +64,2119 @@ pub fn extrude(profile: &Profile, distance: f64) -> Result<Solid, String> {
   shell.sweep(
     &face,
     &transform,
-    |point| {
-      Line::new(point + vec, point).into_enum()
+    |point| extrusion_curve(point, vec),
+    |tcurve| extrusion_surface(tcurve, vec, is_forward),
+  );
+  Ok(solid)
+}
+
+/// Create a new [Solid] by extruding `profile` orthogonally to its plane by the given `distance`,
+/// inclining its side faces inwards by the `taper` angle.
+///
+/// Negative angles widen the profile along the extrusion.
+
+pub fn extrude_tapered(profile: &Profile, distance: f64, taper: Deg<f64>) -> Result<Solid, String> {
+  if taper.0.almost(0.0) {
+    return extrude(profile, distance)
+  }
+  if distance.almost(0.0) {
+    return Err("Extrusion distance must not be zero".into())
+  }
+  extrude_tapered_between(profile, distance.min(0.0), distance.max(0.0), taper)
+}
+
+/// Create a new [Solid] by extruding `profile` from the `start` to the `end` offset along its normal,
+/// inclining its side faces inwards by the `taper` angle on both sides of the profile.
+///
+/// Tapered extrusions must start at the profile or cross it, as the taper is measured from there.
+
+pub fn extrude_tapered_between(profile: &Profile, start: f64, end: f64, taper: Deg<f64>) -> Result<Solid, String> {
+  if taper.0.almost(0.0) {
+    return extrude_between(profile, start, end)
+  }
+  if taper.0.abs() >= 90.0 {
+    return Err("Taper angle must be smaller than 90°".into())
+  }
+  let (back, front) = if start < end { (start, end) } else { (end, start) };
+  if front.almost(back) {
+    return Err("Extrusion distance must not be zero".into())
+  }
+  if back > 0.0 && !back.almost(0.0) || front < 0.0 && !front.almost(0.0) {
+    return Err("Tapered extrusions must start at the profile or cross it".into())
+  }
+  let mut solid = Solid::lamina(profile.rings.clone(), PlanarSurface::new(profile.plane.clone()).into_enum());
+  let shell = &mut solid.shells[0];
+  let front_face = shell.faces.last().unwrap().clone();
+  let back_face = shell.faces.first().unwrap().clone();
+  for (face, distance) in [(front_face, front), (back_face, back)] {
+    if distance.almost(0.0) { continue }
+    let vec = profile.plane.normal() * distance;
+    let inset = distance.abs() * Rad::from(taper).0.tan();
+    let top = TaperedProfile::new(profile, inset, vec)?;
+    let is_forward = distance >= 0.0;
+    // Sweeping can't fail midway, so vertices without a tapered counterpart are reported afterwards
+    let error = RefCell::new(None);
+    let map_point = |point: Point3| top.point(point).unwrap_or_else(|err| {
+      error.borrow_mut().get_or_insert(err);
+      point
+    });
+    shell.sweep_mapped(
+      &face,
+      map_point,
+      |tcurve| top.curve(tcurve),
+      |point| Line::new(map_point(point), point).into_enum(),
+      |tcurve| tapered_surface(tcurve, &top, vec, is_forward, map_point),
+    );
+    if let Some(err) = error.into_inner() {
+      return Err(err)
+    }
+    face.borrow_mut().surface.as_surface_mut().transform(&Matrix4::from_translation(vec));
+  }
+  Ok(solid)
+}
+
+// Side faces of tapered extrusions, oriented like those of straight ones
+fn tapered_surface(tcurve: &TrimmedCurve, top: &TaperedProfile, vec: Vec3, is_forward: bool, map_point: impl Fn(Point3) -> Point3) -> SurfaceType {
+  match (&tcurve.base, top.curve(tcurve)) {
+    (CurveType::Circle(circle), CurveType::Circle(top_circle))
+    => RevolutionSurface::cone(Axis::new(circle.plane.origin, vec), circle.radius, top_circle.radius, vec.magnitude()).into_enum(),
+
+    (CurveType::Arc(arc), CurveType::Arc(top_arc))
+    => {
+      let mut surface = if is_forward {
+        RevolutionSurface::cone(Axis::new(arc.plane.origin, vec), arc.radius, top_arc.radius, vec.magnitude())
+      } else {
+        RevolutionSurface::cone(Axis::new(arc.plane.origin + vec, -vec), top_arc.radius, arc.radius, vec.magnitude())
+      };
+      surface.u_bounds = arc.bounds;
+      if tcurve.is_forward() != is_forward {
+        surface.flip();
+      }
+      surface.into_enum()
     },
-    |tcurve| {
-      match &tcurve.base {
-        CurveType::Line(_)
-        => PlanarSurface::new(Plane::from_triangle(
-          tcurve.bounds.0,
-          tcurve.bounds.0 + vec,
-          tcurve.bounds.1,
-        )).into_enum(),
-
-        CurveType::Circle(circle)
-        => RevolutionSurface::cylinder(Axis::new(circle.plane.origin, vec), circle.radius, vec.magnitude()).into_enum(),
-
-        CurveType::Arc(arc)
-        => {
-          let axis = if is_forward {
-            Axis::new(arc.plane.origin, vec)
-          } else {
-            Axis::new(arc.plane.origin + vec, -vec)
-          };
-          let mut surface = RevolutionSurface::cylinder(axis, arc.radius, vec.magnitude());
-          surface.u_bounds = arc.bounds;
-          if tcurve.is_forward() != is_forward {
-            surface.flip();
-          }
-          surface.into_enum()
-        },
 
-        CurveType::Spline(spline)
-        => {
-          let mut surface = SplineSurface::tabulated(spline, vec);
-          if tcurve.is_forward() {
-            surface.flip();
+    (base, CurveType::Spline(top_spline))
+    => {
+      let mut surface = SplineSurface::ruled(&spline_form(base), &top_spline);
+      if tcurve.is_forward() {
+        surface.flip();
+      }
+      surface.into_enum()
+    },
+
+    _ => PlanarSurface::new(Plane::from_triangle(
+      tcurve.bounds.0,
+      map_point(tcurve.bounds.0),
+      tcurve.bounds.1,
+    )).into_enum(),
+  }
+}
+
+
+// Top cap of a tapered extrusion
+//
+// Maps the vertices and curves of the profile to their inset copies, translated to the top.
+struct TaperedProfile {
+  vertices: Vec<(Point3, Point3)>,
+  curves: HashMap<Uuid, CurveType>,
+}
+
+impl TaperedProfile {
+  fn new(profile: &Profile, inset: f64, vec: Vec3) -> Result<Self, String> {
+    let normal = profile.plane.normal();
+    let mut this = Self { vertices: vec![], curves: HashMap::new() };
+    for (i, wire) in profile.rings.iter().enumerate() {
+      // Material lies to the left of counter-clockwise outer rings and clockwise inner rings
+      let side = if (i == 0) == (signed_area(wire, normal) > 0.0) { 1.0 } else { -1.0 };
+      let offsets = wire.iter()
+        .map(|tcurve| InsetCurve::new(tcurve, normal, inset * side) )
+        .collect::<Result<Vec<InsetCurve>, String>>()?;
+      for (j, tcurve) in wire.iter().enumerate() {
+        let next = (j + 1) % wire.len();
+        let p = tcurve.bounds.1;
+        // Miter point of the neighbouring offset curves approximates their intersection
+        let n1 = normal.cross(curve_tangent(tcurve, 1.0));
+        let n2 = normal.cross(curve_tangent(&wire[next], 0.0));
+        let denominator = 1.0 + n1.dot(n2);
+        if denominator.almost(0.0) {
+          return Err("Profiles with cusps can't be tapered".into())
+        }
+        let estimate = p + (n1 + n2) * (inset * side / denominator);
+        this.vertices.push((p, offsets[j].meet(&offsets[next], normal, estimate) + vec));
+      }
+      // Inset lines collapse or turn around, once the taper consumes them
+      for tcurve in wire.iter().filter(|tcurve| matches!(tcurve.base, CurveType::Line(_)) ) {
+        let inset = this.point(tcurve.bounds.1)? - this.point(tcurve.bounds.0)?;
+        if inset.dot((tcurve.bounds.1 - tcurve.bounds.0).normalize()) <= EPSILON {
+          return Err("Taper angle is too steep for the profile".into())
+        }
+      }
+      for (tcurve, offset) in wire.iter().zip(&offsets) {
+        let curve = this.inset_curve(tcurve, offset, normal, vec)?;
+        this.curves.insert(tcurve.base.id(), curve);
+      }
+    }
+    Ok(this)
+  }
+
+  fn point(&self, p: Point3) -> Result<Point3, String> {
+    self.vertices.iter().find(|(q, _)| q.almost(p) ).map(|(_, top)| *top ).ok_or_else(||
+      "Profile vertex has no tapered counterpart".to_string()
+    )
+  }
+
+  fn curve(&self, tcurve: &TrimmedCurve) -> CurveType {
+    self.curves[&tcurve.base.id()].clone()
+  }
+
+  // Inset copy of the base curve, keeping its direction
+  fn inset_curve(&self, tcurve: &TrimmedCurve, offset: &InsetCurve, normal: Vec3, vec: Vec3) -> Result<CurveType, String> {
+    let plane_offset = |plane: &Plane| {
+      let mut plane = plane.clone();
+      plane.origin += vec;
+      plane
+    };
+    Ok(match (&tcurve.base, offset) {
+      (CurveType::Line(line), _) => Line::new(self.point(line.points.0)?, self.point(line.points.1)?).into_enum(),
+      (CurveType::Circle(circle), &InsetCurve::Circle(_, radius)) => Circle::from_plane(plane_offset(&circle.plane), radius).into_enum(),
+      (CurveType::Arc(arc), &InsetCurve::Circle(_, radius)) => {
+        let (start, end) = arc.endpoints();
+        let mut top = Arc::from_plane(plane_offset(&arc.plane), radius, 0.0, 1.0);
+        let t0 = top.unsample(self.point(start)?);
+        let t1 = top.unsample(self.point(end)?);
+        // Keep the winding of the original arc
+        let span = arc.bounds.1 - arc.bounds.0;
+        top.bounds = (t0, t1 + (t0 + span - t1).round());
+        top.into_enum()
+      },
+      (base, &InsetCurve::Other(inset)) => {
+        //XXX Moving control points only approximates the offset curve
+        let inset = if tcurve.is_forward() { inset } else { -inset };
+        let spline = spline_form(base);
+        let mut top = spline.clone();
+        let n = top.controls.len();
+        for (i, p) in top.controls.iter_mut().enumerate() {
+          let tangent = spline.tangent_at(i as f64 / (n - 1) as f64).normalize();
+          *p += normal.cross(tangent) * inset + vec;
+        }
+        let (start, end) = spline.endpoints();
+        top.controls[0] = self.point(start)?;
+        top.controls[n - 1] = self.point(end)?;
+        top.into_enum()
+      },
+      _ => unreachable!(),
+    })
+  }
+}
+
+
+// Curve in the profile plane, moved inwards by a fixed distance
+enum InsetCurve {
+  // Point on the line and direction of the profile
+  Line(Point3, Vec3),
+  // Center and radius
+  Circle(Point3, f64),
+  // Free-form curves are approximated
+  Other(f64),
+}
+
+impl InsetCurve {
+  fn new(tcurve: &TrimmedCurve, normal: Vec3, inset: f64) -> Result<Self, String> {
+    let circle = |plane: &Plane, radius: f64| {
+      // Counter-clockwise circles run around their center to the left
+      let tangent = curve_tangent(tcurve, 0.0);
+      let winding = (tcurve.bounds.0 - plane.origin).cross(tangent).dot(normal).signum();
+      let radius = radius - inset * winding;
+      if radius <= EPSILON {
+        Err("Taper angle is too steep for the profile".to_string())
+      } else {
+        Ok(Self::Circle(plane.origin, radius))
+      }
+    };
+    match &tcurve.base {
+      CurveType::Line(_) => {
+        let direction = (tcurve.bounds.1 - tcurve.bounds.0).normalize();
+        Ok(Self::Line(tcurve.bounds.0 + normal.cross(direction) * inset, direction))
+      },
+      CurveType::Arc(arc) => circle(&arc.plane, arc.radius),
+      CurveType::Circle(circle_curve) => circle(&circle_curve.plane, circle_curve.radius),
+      CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => Ok(Self::Other(inset)),
+    }
+  }
+
+  // Intersection with the following curve, closest to the estimate
+  fn meet(&self, other: &Self, normal: Vec3, estimate: Point3) -> Point3 {
+    let closest = |candidates: Vec<Point3>| candidates.into_iter()
+      .min_by(|a, b| a.distance2(estimate).partial_cmp(&b.distance2(estimate)).unwrap() )
+      .unwrap_or(estimate);
+    match (self, other) {
+      (&Self::Line(p1, d1), &Self::Line(p2, d2)) => {
+        let denominator = d1.cross(d2).dot(normal);
+        if denominator.abs() < EPSILON {
+          estimate
+        } else {
+          p1 + d1 * ((p2 - p1).cross(d2).dot(normal) / denominator)
+        }
+      },
+      (&Self::Line(p, d), &Self::Circle(center, radius)) |
+      (&Self::Circle(center, radius), &Self::Line(p, d)) => {
+        let offset = p - center;
+        let b = d.dot(offset);
+        let discriminant = b * b - offset.magnitude2() + radius * radius;
+        if discriminant < 0.0 {
+          estimate
+        } else {
+          closest(vec![p + d * (-b + discriminant.sqrt()), p + d * (-b - discriminant.sqrt())])
+        }
+      },
+      (&Self::Circle(c1, r1), &Self::Circle(c2, r2)) => {
+        let axis = c2 - c1;
+        let distance = axis.magnitude();
+        if distance < EPSILON {
+          return estimate
+        }
+        let axis = axis / distance;
+        let a = (r1 * r1 - r2 * r2 + distance * distance) / (2.0 * distance);
+        let h2 = r1 * r1 - a * a;
+        if h2 < 0.0 {
+          estimate
+        } else {
+          let base = c1 + axis * a;
+          let across = normal.cross(axis) * h2.sqrt();
+          closest(vec![base + across, base - across])
+        }
+      },
+      _ => estimate,
+    }
+  }
+}
+
+/// Create a new [Solid] by extruding `profile` orthogonally to its plane,
+/// spanning from the offset `start` to the offset `end` along its normal.
+
+pub fn extrude_between(profile: &Profile, start: f64, end: f64) -> Result<Solid, String> {
+  let mut profile = profile.clone();
+  let offset = Matrix4::from_translation(profile.plane.normal() * start);
+  profile.transform(&offset);
+  profile.plane.transform(&offset);
+  extrude(&profile, end - start)
+}
+
+/// Signed distance along the normal of `profile` up to the surface of `face`.
+///
+/// The surface is extended beyond the boundary of the face.
+/// Negative distances lie behind the profile.
+
+pub fn distance_to_face(profile: &Profile, face: &Face) -> Result<f64, String> {
+  let normal = profile.plane.normal();
+  // Closest hit for each ray, as the surface may be hit repeatedly
+  let hits: Vec<f64> = profile_samples(profile).into_iter().filter_map(|p|
+    ray_surface_intersections(&face.surface, p, normal).into_iter()
+      .min_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap() )
+  ).collect();
+  if hits.is_empty() {
+    return Err("Face can't be reached from the profile".into())
+  }
+  if hits.iter().any(|hit| hit.signum() != hits[0].signum() ) {
+    return Err("Face crosses the profile plane".into())
+  }
+  Ok(hits.into_iter().fold(0.0, |acc, hit| if hit.abs() > acc.abs() { hit } else { acc } ))
+}
+
+/// Create a new [Solid] by extruding `profile` along its normal up to the surface of `face`,
+/// inclining its side faces inwards by the `taper` angle.
+///
+/// The end cap follows the surface, which is extended beyond the boundary of the face.
+
+pub fn extrude_to_face(profile: &Profile, face: &Face, taper: Deg<f64>) -> Result<Solid, String> {
+  let distance = distance_to_face(profile, face)?;
+  let solid = extrude_tapered(profile, distance, taper)?;
+  let outward = profile.plane.normal() * distance.signum();
+  let end = profile.plane.origin + profile.plane.normal() * distance;
+  let cap = solid.faces_iter().find(|cap| match &cap.borrow().surface {
+    SurfaceType::Planar(surface) => surface.plane.normal().dot(outward).almost(1.0) && surface.plane.contains_point(end),
+    _ => false,
+  }).unwrap().clone();
+  trim_cap(&cap, &face.surface, outward)?;
+  Ok(solid)
+}
+
+// Move an end cap onto `surface`, by sliding its vertices along the side edges and re-trimming its edges
+fn trim_cap(cap: &Ref<Face>, surface: &SurfaceType, outward: Vec3) -> Result<(), String> {
+  let half_edges: Vec<Ref<HalfEdge>> = cap.borrow().rings.iter()
+    .flat_map(|ring| ring.borrow().iter().collect::<Vec<_>>() )
+    .collect();
+  // Closed edges are rebuilt from samples that slide along the side face, to keep starting at their vertex
+  let mut closed_curves = vec![];
+  for he in &half_edges {
+    if !Rc::ptr_eq(&he.borrow().origin, &he.borrow().end_vertex()) { continue }
+    let edge = he.borrow().edge();
+    let side_face = he.borrow().mate().borrow().face();
+    let side = side_face.borrow().surface.clone();
+    let tcurve = edge.borrow().left_half.borrow().make_curve();
+    let points = (0..CAP_SAMPLES).map(|i| {
+      let p = tcurve.sample(i as f64 / (CAP_SAMPLES - 1) as f64);
+      let (u, v) = side.as_surface().unsample(p);
+      let ends = [side.as_surface().sample(u, 0.0), side.as_surface().sample(u, 1.0)];
+      let base = if ends[0].distance(p) > ends[1].distance(p) { ends[0] } else { ends[1] };
+      slide_onto(surface, base, side.as_surface().sample(u, v)).ok_or("Side faces don't reach the face")
+    }).collect::<Result<Vec<Point3>, &str>>()?;
+    closed_curves.push((edge, interpolate(&points).into_enum()));
+  }
+  for he in &half_edges {
+    // Side edges arrive at the cap vertices from the other end of the solid
+    let side = he.borrow().previous().borrow().mate().borrow().previous();
+    let vertex = he.borrow().origin.clone();
+    let base = side.borrow().origin.borrow().point;
+    let point = slide_onto(surface, base, vertex.borrow().point).ok_or("Side edges don't reach the face")?;
+    vertex.borrow_mut().point = point;
+    set_edge_curve(&side.borrow().edge(), Line::new(point, base).into_enum());
+  }
+  {
+    let mut cap = cap.borrow_mut();
+    cap.surface = surface.clone();
+    let (u, v) = cap.surface.as_surface().unsample(half_edges[0].borrow().origin.borrow().point);
+    let (du, dv) = cap.surface.derivatives(u, v);
+    if du.cross(dv).dot(outward) < 0.0 {
+      cap.surface.as_surface_mut().flip();
+    }
+  }
+  // Other cap edges follow the intersections of the side faces with the new surface
+  for he in &half_edges {
+    let edge = he.borrow().edge();
+    if let Some((_, curve)) = closed_curves.iter().find(|(other, _)| Rc::ptr_eq(other, &edge) ) {
+      set_edge_curve(&edge, curve.clone());
+      continue
+    }
+    let bounds = (he.borrow().origin.borrow().point, he.borrow().end_vertex().borrow().point);
+    let side_face = he.borrow().mate().borrow().face();
+    let curves: Vec<CurveType> = side_face.borrow().surface.intersect(surface).into_iter()
+      .filter_map(|isect| match isect {
+        SurfaceIntersectionType::Touch(curve)
+        | SurfaceIntersectionType::Cross(curve)
+        | SurfaceIntersectionType::Extended(curve)
+        => Some(curve),
+        SurfaceIntersectionType::Contained => None,
+      }).collect();
+    let deviation = |curve: &CurveType| {
+      let samples = curve.as_curve().tesselate();
+      [bounds.0, bounds.1].iter()
+        .map(|bound| samples.iter().map(|p| p.distance(*bound) ).fold(f64::MAX, f64::min) )
+        .sum::<f64>()
+    };
+    let curve = curves.into_iter()
+      .min_by(|a, b| deviation(a).partial_cmp(&deviation(b)).unwrap() )
+      .ok_or("Side faces don't intersect the face")?;
+    set_edge_curve(&edge, curve);
+  }
+  Ok(())
+}
+
+// First intersection with `surface` on the way from `base` through p
+fn slide_onto(surface: &SurfaceType, base: Point3, p: Point3) -> Option<Point3> {
+  let direction = (p - base).normalize();
+  Line::new(base, p).into_enum().intersect_surface(surface).iter()
+    .filter_map(|isect| isect.get_point(true) )
+    .filter(|q| (q - base).dot(direction) > EPSILON )
+    .min_by(|a, b| (a - base).dot(direction).partial_cmp(&(b - base).dot(direction)).unwrap() )
+}
+
+// Replace the curve of an edge, keeping its id
+fn set_edge_curve(edge: &Ref<Edge>, mut curve: CurveType) {
+  let mut edge = edge.borrow_mut();
+  curve.set_id(edge.curve.id());
+  edge.curve = curve;
+}
+
+
+/// Distance along the normal of `profile`, or against it, up to the first faces of `body`.
+///
+/// The distance is chosen such that the extrusion reaches `body` along its whole boundary.
+
+pub fn distance_to_next(profile: &Profile, body: &Compound, forward: bool) -> Result<f64, String> {
+  let direction = if forward { profile.plane.normal() } else { -profile.plane.normal() };
+  let distance = profile_samples(profile).into_iter().filter_map(|p|
+    body.faces_iter()
+      .flat_map(|face| ray_face_intersections(&face.borrow(), p, direction) )
+      .filter(|&t| t > EPSILON )
+      .min_by(|a, b| a.partial_cmp(b).unwrap() )
+  ).fold(0.0, f64::max);
+  if distance == 0.0 {
+    return Err("No faces found in extrusion direction".into())
+  }
+  Ok(if forward { distance } else { -distance })
+}
+
+/// Distance along the normal of `profile`, or against it, that clears `body` entirely.
+
+pub fn distance_through_all(profile: &Profile, body: &Compound, forward: bool) -> Result<f64, String> {
+  let direction = if forward { profile.plane.normal() } else { -profile.plane.normal() };
+  let origin = profile.plane.origin;
+  let distance = body.solids.iter()
+    .flat_map(|solid| solid.edges_iter() )
+    .flat_map(|edge| edge.borrow().curve.as_curve().tesselate() )
+    .fold(0.0, |acc: f64, p| acc.max((p - origin).dot(direction)) );
+  if distance <= EPSILON {
+    return Err("No bodies found in extrusion direction".into())
+  }
+  // Stick out a little, to avoid coinciding faces
+  let distance = distance * (1.0 + THROUGH_ALL_CLEARANCE);
+  Ok(if forward { distance } else { -distance })
+}
+
+// Origins of the rays used to measure extrusion distances, along the boundary and on a grid across the interior
+fn profile_samples(profile: &Profile) -> Vec<Point3> {
+  let plane = &profile.plane;
+  let boundary: Vec<Point3> = profile.rings.iter().flat_map(|wire| wire.tesselate() ).collect();
+  let rings: Vec<PolyLine> = profile.rings.iter().map(|wire|
+    wire.tesselate().into_iter().map(|p| {
+      let local = plane.unsample(p);
+      Point3::new(local.x, local.y, 0.0)
+    }).collect()
+  ).collect();
+  let (min, max) = rings[0].iter().fold((Point3::new(f64::MAX, f64::MAX, 0.0), Point3::new(f64::MIN, f64::MIN, 0.0)), |(min, max), p|
+    (Point3::new(min.x.min(p.x), min.y.min(p.y), 0.0), Point3::new(max.x.max(p.x), max.y.max(p.y), 0.0))
+  );
+  let interior = (1..PROFILE_SAMPLES).flat_map(|j| (1..PROFILE_SAMPLES).map(move |i| {
+    let (s, t) = (i as f64 / PROFILE_SAMPLES as f64, j as f64 / PROFILE_SAMPLES as f64);
+    Point3::new(min.x + (max.x - min.x) * s, min.y + (max.y - min.y) * t, 0.0)
+  })).filter(|p|
+    geom2d::polygon_contains_point(&rings[0], *p) && !rings[1..].iter().any(|ring| geom2d::polygon_contains_point(ring, *p) )
+  ).map(|p| plane.sample(p.x, p.y) );
+  boundary.into_iter().chain(interior).collect()
+}
+
+fn extrusion_curve(point: Point3, vec: Vec3) -> CurveType {
+  Line::new(point + vec, point).into_enum()
+}
+
+fn extrusion_surface(tcurve: &TrimmedCurve, vec: Vec3, is_forward: bool) -> SurfaceType {
+  match &tcurve.base {
+    CurveType::Line(_)
+    => PlanarSurface::new(Plane::from_triangle(
+      tcurve.bounds.0,
+      tcurve.bounds.0 + vec,
+      tcurve.bounds.1,
+    )).into_enum(),
+
+    CurveType::Circle(circle)
+    => RevolutionSurface::cylinder(Axis::new(circle.plane.origin, vec), circle.radius, vec.magnitude()).into_enum(),
+
+    CurveType::Arc(arc)
+    => {
+      let axis = if is_forward {
+        Axis::new(arc.plane.origin, vec)
+      } else {
+        Axis::new(arc.plane.origin + vec, -vec)
+      };
+      let mut surface = RevolutionSurface::cylinder(axis, arc.radius, vec.magnitude());
+      surface.u_bounds = arc.bounds;
+      if tcurve.is_forward() != is_forward {
+        surface.flip();
+      }
+      surface.into_enum()
+    },
+
+    CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_)
+    => {
+      let mut surface = SplineSurface::tabulated(&spline_form(&tcurve.base), vec);
+      // Closed curves have forward trims either way, but only run forwards in the face swept along the profile normal
+      let is_closed = tcurve.bounds.0.almost(tcurve.bounds.1);
+      if (is_closed && is_forward) || (!is_closed && tcurve.is_forward()) {
+        surface.flip();
+      }
+      surface.into_enum()
+    },
+  }
+}
+
+// Rational spline form of free-form, elliptical and helical curves
+fn spline_form(curve: &CurveType) -> Spline {
+  match curve {
+    CurveType::Spline(spline) => spline.clone(),
+    CurveType::Ellipse(ellipse) => ellipse.to_spline(),
+    CurveType::EllipticalArc(arc) => arc.to_spline(),
+    //XXX Helices have no exact spline form and are approximated by quintic pieces
+    CurveType::Helix(helix) => helix.to_spline(),
+    _ => unreachable!(),
+  }
+}
+
+
+/// Create a new [Solid] by sweeping `profile` around the given `axis`.
+///
+/// Inner rings of the profile turn into tunnels through the solid,
+/// or into cavities when the profile is revolved all the way around.
+
+pub fn revolve(profile: &Profile, mut axis: Axis, angle: Deg<f64>) -> Result<Solid, String> {
+  if axis.direction.dot(profile.plane.u).signum() < 0.0 {
+    axis.flip();
+  }
+  //XXX Start and end faces of full revolutions stay in place, coinciding with each other
+  let is_full = angle.0.abs() >= 360.0 - EPSILON;
+  let rings = if is_full {
+    vec![profile.rings[0].clone()]
+  } else {
+    profile.rings.clone()
+  };
+  let mut solid = Solid::lamina(rings, PlanarSurface::new(profile.plane.clone()).into_enum());
+  let shell = &mut solid.shells[0];
+  // Sweep the top face when the profile rotates along its normal
+  let normal = profile.plane.normal();
+  let motion = profile.rings[0].cage().iter().fold(0.0, |acc, p|
+    acc + axis.direction.cross(p - axis.origin).dot(normal)
+  );
+  let is_forward = (angle >= Deg(0.0)) == (motion >= 0.0);
+  let face = if is_forward {
+    shell.faces.last()
+  } else {
+    shell.faces.first()
+  }.unwrap().clone();
+  let transform = axis.rotation(angle);
+  shell.sweep(
+    &face,
+    &transform,
+    |point| revolution_curve(point, &axis, angle),
+    |tcurve| revolution_surface(tcurve, &axis, angle, is_forward),
+  );
+  if is_full {
+    for ring in profile.rings.iter().skip(1) {
+      let mut cavity = revolve(&Profile::new(profile.plane.clone(), vec![ring.clone()]), axis.clone(), angle)?;
+      let mut inner = cavity.shells.remove(0);
+      inner.invert();
+      solid.shells.push(inner);
+    }
+  }
+  Ok(solid)
+}
+
+fn revolution_curve(point: Point3, axis: &Axis, angle: Deg<f64>) -> CurveType {
+  let p_axis = axis.closest_point(point);
+  let radius = p_axis.distance(point);
+  let mut plane: Plane = axis.into();
+  plane.origin = p_axis;
+  let mut arc = Arc::from_plane(plane, radius, 0.0, 1.0);
+  let t = arc.unsample(point);
+  arc.bounds.0 = t;
+  arc.bounds.1 = t + (angle / Deg(360.0));
+  arc.into_enum()
+}
+
+fn revolution_surface(tcurve: &TrimmedCurve, axis: &Axis, angle: Deg<f64>, is_forward: bool) -> SurfaceType {
+  // Closed curves run the same way in top and bottom faces
+  let is_closed = matches!(tcurve.base, CurveType::Circle(_) | CurveType::Ellipse(_));
+  let mut tcurve = tcurve.clone();
+  if is_forward {
+    tcurve.flip();
+  }
+  let mut surface = RevolutionSurface::with_bounds(axis.clone(), tcurve, (0.0, angle / Deg(360.0)));
+  if !is_forward && !is_closed {
+    surface.flip();
+  }
+  surface.into_enum()
+}
+
+
+/// Orientation of the profile while it travels along the path of a [sweep].
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SweepOrientation {
+  /// Profile turns along with the path, following its Frenet frame
+  Frenet,
+  /// Profile keeps its initial orientation
+  Fixed,
+}
+
+
+// Rigid motion of the profile along a section of the sweep path
+enum SweepStep {
+  Translation(Vec3),
+  Rotation(Axis, Deg<f64>),
+  Screw(Helix), // Rotation about the axis of a cylindrical helix, while rising along it
+  Glide(Box<TrimmedCurve>), // Translation along a curved section of the path
+}
+
+
+/// Create a new [Solid] by moving `profile` along the open `path`, starting at its first element.
+///
+/// Inner rings of the profile turn into holes along the solid.
+/// The path needs to be tangent continuous when the [Frenet](SweepOrientation::Frenet) orientation is used.
+
+pub fn sweep(profile: &Profile, path: &Wire, orientation: SweepOrientation) -> Result<Solid, String> {
+  let steps = sweep_steps(path, orientation)?;
+  let mut normal = profile.plane.normal();
+  let start_tangent = curve_tangent(&path[0], 0.0);
+  if normal.dot(start_tangent).almost(0.0) {
+    return Err("Sweep path must not start parallel to the profile".into())
+  }
+  let is_forward = normal.dot(start_tangent) > 0.0;
+  let has_circular_edges = profile.rings.iter().flat_map(|ring| ring.iter() ).any(|tcurve|
+    matches!(tcurve.base, CurveType::Arc(_) | CurveType::Circle(_))
+  );
+  let mut solid = Solid::lamina(profile.rings.clone(), PlanarSurface::new(profile.plane.clone()).into_enum());
+  let shell = &mut solid.shells[0];
+  let face = if is_forward {
+    shell.faces.last()
+  } else {
+    shell.faces.first()
+  }.unwrap().clone();
+  for step in steps {
+    match step {
+      SweepStep::Translation(vec) => {
+        //XXX Oblique cylinders can't be represented yet
+        if has_circular_edges && !normal.cross(vec.normalize()).magnitude().almost(0.0) {
+          return Err("Profiles containing arcs can only be swept perpendicular to their plane".into())
+        }
+        shell.sweep(
+          &face,
+          &Matrix4::from_translation(vec),
+          |point| extrusion_curve(point, vec),
+          |tcurve| extrusion_surface(tcurve, vec, is_forward),
+        );
+      },
+      SweepStep::Rotation(mut axis, mut angle) => {
+        if !is_forward {
+          axis.flip();
+          angle = -angle;
+        }
+        let transform = axis.rotation(angle);
+        shell.sweep(
+          &face,
+          &transform,
+          |point| revolution_curve(point, &axis, angle),
+          |tcurve| revolution_surface(tcurve, &axis, angle, is_forward),
+        );
+        normal = transform.transform_vector(normal);
+      },
+      SweepStep::Screw(helix) => {
+        let transform = screw_transform(&helix);
+        shell.sweep(
+          &face,
+          &transform,
+          |point| screw_curve(point, &helix).into_enum(),
+          |tcurve| screw_surface(tcurve, &helix, is_forward),
+        );
+        normal = transform.transform_vector(normal);
+      },
+      SweepStep::Glide(tcurve) => {
+        let rail = trimmed_spline(&tcurve);
+        shell.sweep(
+          &face,
+          &Matrix4::from_translation(tcurve.bounds.1 - tcurve.bounds.0),
+          |point| glide_curve(&tcurve, point),
+          |edge| glide_surface(edge, &rail, is_forward),
+        );
+      },
+    }
+  }
+  Ok(solid)
+}
+
+// Break path down into rigid motions
+fn sweep_steps(path: &Wire, orientation: SweepOrientation) -> Result<Vec<SweepStep>, String> {
+  if path.is_closed() {
+    return Err("Closed sweep paths are not supported".into()) //XXX
+  }
+  let mut steps = vec![];
+  let mut last_tangent: Option<Vec3> = None;
+  for tcurve in path.iter() {
+    match orientation {
+      SweepOrientation::Frenet => {
+        let tangent = curve_tangent(tcurve, 0.0);
+        if let Some(last_tangent) = last_tangent {
+          if last_tangent.dot(tangent) < 1.0 - TANGENT_TOLERANCE {
+            return Err("Sweep path needs to be tangent continuous".into())
           }
-          surface.into_enum()
+        }
+        match &tcurve.base {
+          CurveType::Line(_) => steps.push(SweepStep::Translation(tcurve.bounds.1 - tcurve.bounds.0)),
+          CurveType::Arc(arc) => {
+            let mut axis = Axis::new(arc.plane.origin, arc.plane.normal());
+            if axis.direction.cross(tcurve.bounds.0 - axis.origin).dot(tangent) < 0.0 {
+              axis.flip();
+            }
+            steps.push(SweepStep::Rotation(axis, Rad(tcurve.length() / arc.radius).into()));
+          },
+          CurveType::Circle(_) | CurveType::Ellipse(_) => return Err("Closed sweep paths are not supported".into()),
+          // Frenet frames of cylindrical helices move rigidly along with the curve
+          CurveType::Helix(helix) if helix.taper.0.almost(0.0) => steps.push(SweepStep::Screw(helix.section(tcurve.trims.0, tcurve.trims.1))),
+          // Approximate free-form curves by a tangent continuous chain of arcs through their tesselation
+          //XXX Arcs only revolve the profile correctly while it stays perpendicular to paths with torsion
+          CurveType::Spline(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => {
+            let points = tcurve.tesselate();
+            let mut tangent = tangent;
+            for pair in points.windows(2) {
+              let step = arc_step(pair[0], pair[1], tangent);
+              if let SweepStep::Rotation(axis, angle) = &step {
+                tangent = Matrix4::from_axis_angle(axis.direction, *angle).transform_vector(tangent);
+              }
+              steps.push(step);
+            }
+          },
+        }
+        last_tangent = Some(curve_tangent(tcurve, 1.0));
+      },
+      SweepOrientation::Fixed => match &tcurve.base {
+        CurveType::Line(_) => steps.push(SweepStep::Translation(tcurve.bounds.1 - tcurve.bounds.0)),
+        _ => steps.push(SweepStep::Glide(Box::new(tcurve.clone()))),
+      },
+    }
+  }
+  Ok(steps)
+}
+
+// Arc starting at p1 in direction of tangent, that passes through p2
+fn arc_step(p1: Point3, p2: Point3, tangent: Vec3) -> SweepStep {
+  let chord = p2 - p1;
+  let perpendicular = chord - tangent * chord.dot(tangent);
+  if perpendicular.magnitude() <= EPSILON * chord.magnitude() {
+    return SweepStep::Translation(chord)
+  }
+  let inward = perpendicular.normalize();
+  let radius = chord.magnitude2() / (2.0 * chord.dot(inward));
+  let axis = Axis::new(p1 + inward * radius, tangent.cross(inward));
+  let angle = Rad(2.0 * chord.dot(inward).atan2(chord.dot(tangent)));
+  SweepStep::Rotation(axis, angle.into())
+}
+
+// Rigid motion carrying the start of `helix` to its end
+fn screw_transform(helix: &Helix) -> Matrix4 {
+  let axis = helix.axis();
+  let turns = if helix.right_handed { helix.turns } else { -helix.turns };
+  Matrix4::from_translation(axis.direction * helix.height()) * axis.rotation(Deg(turns * 360.0))
+}
+
+// Helix that `point` follows while screwing along `helix`
+fn screw_curve(point: Point3, helix: &Helix) -> Helix {
+  let axis = helix.axis();
+  let origin = axis.closest_point(point);
+  let radial = point - origin;
+  let u = if radial.magnitude().almost(0.0) { helix.plane.u } else { radial.normalize() };
+  let mut curve = helix.clone();
+  curve.id = Uuid::new_v4();
+  curve.plane = Plane { origin, u, v: axis.direction.cross(u) };
+  curve.radius = radial.magnitude();
+  curve
+}
+
+// Each control point of the edge follows its own helix, which the surface interpolates along v
+fn screw_surface(tcurve: &TrimmedCurve, helix: &Helix, is_forward: bool) -> SurfaceType {
+  let section = trimmed_spline(tcurve);
+  let paths: Vec<Spline> = section.controls.iter().map(|p| screw_curve(*p, helix).to_spline() ).collect();
+  let mut surface = SplineSurface {
+    degree: (section.degree, paths[0].degree),
+    controls: (0..paths[0].controls.len()).map(|k| paths.iter().map(|path| path.controls[k] ).collect() ).collect(),
+    knots: (section.knots.clone(), paths[0].knots.clone()),
+    weights: vec![section.weights.clone(); paths[0].controls.len()],
+  };
+  if !is_forward {
+    surface.flip();
+  }
+  surface.into_enum()
+}
+
+// Copy of the path section `tcurve`, moved to start at `point`
+fn glide_curve(tcurve: &TrimmedCurve, point: Point3) -> CurveType {
+  let mut curve = tcurve.base.clone();
+  curve.as_curve_mut().transform(&Matrix4::from_translation(point - tcurve.bounds.0));
+  curve.set_id(Uuid::new_v4());
+  curve
+}
+
+// Edge translated along the rail, which multiplies their weights and adds their control points
+fn glide_surface(tcurve: &TrimmedCurve, rail: &Spline, is_forward: bool) -> SurfaceType {
+  let section = trimmed_spline(tcurve);
+  let origin = rail.controls[0];
+  let mut surface = SplineSurface {
+    degree: (section.degree, rail.degree),
+    controls: rail.controls.iter().map(|p| section.controls.iter().map(|q| q + (p - origin) ).collect() ).collect(),
+    knots: (section.knots.clone(), rail.knots.clone()),
+    weights: rail.weights.iter().map(|w| section.weights.iter().map(|v| v * w ).collect() ).collect(),
+  };
+  if !is_forward {
+    surface.flip();
+  }
+  surface.into_enum()
+}
+
+fn curve_tangent(tcurve: &TrimmedCurve, t: f64) -> Vec3 {
+  let step = 1.0e-6;
+  let (t0, t1) = ((t - step).max(0.0), (t + step).min(1.0));
+  (tcurve.sample(t1) - tcurve.sample(t0)).normalize()
+}
+
+
+/// Create a new [Solid] by connecting `profiles` with spline surfaces, in the given order.
+///
+/// All profiles need the same number of edges. Ruled lofts connect consecutive profiles by straight lines,
+/// otherwise smooth surfaces are interpolated through all profiles.
+
+pub fn loft(profiles: &[Profile], ruled: bool) -> Result<Solid, String> {
+  loft_along(profiles, None, ruled)
+}
+
+
+/// Create a lofted [Solid], whose sections additionally follow the given guide `rail`.
+///
+/// The rail needs to pass through corresponding vertices of all profiles.
+
+pub fn loft_guided(profiles: &[Profile], rail: &Region, ruled: bool) -> Result<Solid, String> {
+  loft_along(profiles, Some(rail), ruled)
+}
+
+fn loft_along(profiles: &[Profile], rail: Option<&Region>, ruled: bool) -> Result<Solid, String> {
+  if profiles.len() < 2 {
+    return Err("Lofts need at least two profiles".into())
+  }
+  let mut sections = loft_sections(profiles)?;
+  if let Some(rail) = rail {
+    sections = guide_sections(sections, rail)?;
+  }
+  let directions = section_directions(&sections)?;
+  let first = &profiles[0].plane;
+  let last = &profiles.last().unwrap().plane;
+  let wire = profiles[0].rings[0].clone();
+  let mut solid = Solid::lamina(vec![wire], PlanarSurface::new(first.clone()).into_enum());
+  let shell = &mut solid.shells[0];
+  let face = if first.normal().dot(directions[0]) > 0.0 {
+    shell.faces.last()
+  } else {
+    shell.faces.first()
+  }.unwrap().clone();
+  let groups: Vec<&[Section]> = if ruled {
+    sections.windows(2).collect()
+  } else {
+    vec![&sections[..]]
+  };
+  for group in groups {
+    let (from, to) = (&group[0], group.last().unwrap());
+    let surfaces: Vec<SplineSurface> = (0..from.len()).map(|j| SplineSurface::lofted(&section_splines(group, j)) ).collect();
+    let vertex_index = |p: Point3| from.iter().position(|tcurve| tcurve.bounds.0.almost(p) ).unwrap();
+    shell.sweep_mapped(
+      &face,
+      |point| to[vertex_index(point)].bounds.0,
+      |tcurve| to[vertex_index(tcurve.bounds.0)].base.clone(),
+      |point| {
+        let j = vertex_index(point);
+        if group.len() == 2 {
+          Line::new(to[j].bounds.0, point).into_enum()
+        } else {
+          isocurve(&surfaces[j], 0).into_enum()
+        }
+      },
+      |tcurve| surfaces[vertex_index(tcurve.bounds.0)].clone().into_enum(),
+    );
+  }
+  let mut plane = last.clone();
+  if plane.normal().dot(*directions.last().unwrap()) < 0.0 {
+    plane.flip();
+  }
+  face.borrow_mut().surface = PlanarSurface::new(plane).into_enum();
+  Ok(solid)
+}
+
+// Edge loop of a loft, running counter-clockwise around the loft direction
+type Section = Vec<TrimmedCurve>;
+
+// Orient outer rings of profiles consistently and align their starting vertices
+fn loft_sections(profiles: &[Profile]) -> Result<Vec<Section>, String> {
+  let num_edges = profiles[0].rings[0].len();
+  let centers: Vec<Point3> = profiles.iter().map(|profile| section_center(&profile.rings[0]) ).collect();
+  let mut sections: Vec<Section> = vec![];
+  for (k, profile) in profiles.iter().enumerate() {
+    let mut section: Section = profile.rings[0].to_vec();
+    if section.len() != num_edges {
+      return Err("Loft profiles need matching edge counts".into())
+    }
+    let direction = if k + 1 < profiles.len() {
+      centers[k + 1] - centers[k]
+    } else {
+      centers[k] - centers[k - 1]
+    };
+    if profile.plane.normal().dot(direction) < 0.0 {
+      reverse_section(&mut section);
+    }
+    // Start at vertex that best matches the previous section
+    if let Some(previous) = sections.last() {
+      let offset = centers[k] - centers[k - 1];
+      let deviation = |shift: usize| (0..num_edges).fold(0.0, |acc, j|
+        acc + (section[(j + shift) % num_edges].bounds.0 - offset).distance2(previous[j].bounds.0)
+      );
+      let shift = (0..num_edges).min_by(|&a, &b| deviation(a).partial_cmp(&deviation(b)).unwrap() ).unwrap();
+      section.rotate_left(shift);
+    }
+    sections.push(section);
+  }
+  Ok(sections)
+}
+
+fn section_center(section: &[TrimmedCurve]) -> Point3 {
+  Point3::centroid(&section.iter().map(|tcurve| tcurve.bounds.0 ).collect::<Vec<Point3>>())
+}
+
+fn reverse_section(section: &mut Section) {
+  section.reverse();
+  for tcurve in section.iter_mut() {
+    tcurve.flip();
+  }
+}
+
+fn section_directions(sections: &[Section]) -> Result<Vec<Vec3>, String> {
+  let centers: Vec<Point3> = sections.iter().map(|section| section_center(section) ).collect();
+  let mut directions: Vec<Vec3> = centers.windows(2).map(|pair| pair[1] - pair[0] ).collect();
+  if directions.iter().any(|direction| direction.magnitude().almost(0.0) ) {
+    return Err("Loft profiles must not share the same center".into())
+  }
+  directions.push(*directions.last().unwrap());
+  Ok(directions)
+}
+
+// Represent the edges at `index` of all sections by compatible splines
+fn section_splines(sections: &[Section], index: usize) -> Vec<Spline> {
+  let mut splines: Vec<Spline> = sections.iter().map(|section| trimmed_spline(&section[index]) ).collect();
+  Spline::make_compatible(&mut splines);
+  splines
+}
+
+// Spline running along the bounds of a trimmed curve, which is exact for all curves but helices
+fn trimmed_spline(tcurve: &TrimmedCurve) -> Spline {
+  let (start, end) = tcurve.trims;
+  match &tcurve.base {
+    CurveType::Line(_) => Spline::new(vec![tcurve.bounds.0, tcurve.bounds.1]),
+    CurveType::Arc(arc) => Arc::from_plane(arc.plane.clone(), arc.radius, arc.param_to_circle(start), arc.param_to_circle(end)).to_spline(),
+    CurveType::Circle(circle) => Arc::from_plane(circle.plane.clone(), circle.radius, start, end).to_spline(),
+    CurveType::Ellipse(ellipse) => EllipticalArc::from_plane(ellipse.plane.clone(), ellipse.radii, start, end).to_spline(),
+    CurveType::EllipticalArc(arc) => EllipticalArc::from_plane(arc.plane.clone(), arc.radii, arc.param_to_ellipse(start), arc.param_to_ellipse(end)).to_spline(),
+    CurveType::Spline(_) | CurveType::Helix(_) => {
+      let mut spline = spline_form(&tcurve.base);
+      let (low, high) = if tcurve.is_forward() { (start, end) } else { (end, start) };
+      if let Some((_, tail)) = spline.split_at(low) {
+        spline = tail;
+      }
+      if let Some((head, _)) = spline.split_at((high - low) / (1.0 - low)) {
+        spline = head;
+      }
+      if !tcurve.is_forward() {
+        spline.reverse();
+      }
+      spline
+    },
+  }
+}
+
+// Insert intermediate sections, such that the corresponding vertex of each section lies on `rail`
+fn guide_sections(sections: Vec<Section>, rail: &Region) -> Result<Vec<Section>, String> {
+  let mut rail = rail.clone();
+  align_region(&mut rail);
+  let mut points: PolyLine = vec![];
+  for tcurve in &rail {
+    let mut polyline = tcurve.tesselate();
+    if !points.is_empty() { polyline.remove(0); }
+    points.append(&mut polyline);
+  }
+  // Find vertex the rail starts at
+  let index = (0..sections[0].len()).find(|&j| {
+    let vertex = sections[0][j].bounds.0;
+    vertex.almost(points[0]) || vertex.almost(*points.last().unwrap())
+  }).ok_or("Guide rails need to start at a profile vertex")?;
+  if !sections[0][index].bounds.0.almost(points[0]) {
+    points.reverse();
+  }
+  // Rail positions closest to each section
+  let mut positions = vec![];
+  for section in &sections {
+    let vertex = section[index].bounds.0;
+    let position = (0..points.len()).min_by(|&a, &b|
+      points[a].distance2(vertex).partial_cmp(&points[b].distance2(vertex)).unwrap()
+    ).unwrap();
+    if points[position].distance(vertex) > RAIL_TOLERANCE {
+      return Err("Guide rails need to pass through a vertex of each profile".into())
+    }
+    positions.push(position);
+  }
+  let mut guided = vec![sections[0].clone()];
+  for k in 0..sections.len() - 1 {
+    let splines: Vec<Vec<Spline>> = (0..sections[k].len())
+      .map(|j| section_splines(&sections[k..=k + 1], j) )
+      .collect();
+    for (i, point) in points.iter().enumerate().take(positions[k + 1]).skip(positions[k] + 1) {
+      let t = (i - positions[k]) as f64 / (positions[k + 1] - positions[k]) as f64;
+      let mut section: Section = splines.iter().map(|pair| {
+        let mut spline = pair[0].clone();
+        spline.controls = pair[0].controls.iter().zip(&pair[1].controls).map(|(a, b)| a + (b - a) * t ).collect();
+        spline.weights = pair[0].weights.iter().zip(&pair[1].weights).map(|(a, b)| a + (b - a) * t ).collect();
+        TrimmedCurve::new(spline.into_enum())
+      }).collect();
+      let offset = point - section[index].bounds.0;
+      for tcurve in &mut section {
+        tcurve.translate(offset);
+      }
+      guided.push(section);
+    }
+    guided.push(sections[k + 1].clone());
+  }
+  Ok(guided)
+}
+
+
+/// Round off the given [Edge]s of `solid` by rolling a ball of `radius` along them.
+///
+/// Edges between planar faces are replaced by exact cylindrical faces, all others by spline approximations.
+/// Edges may share vertices where they continue each other tangentially, forming chains of blend faces.
+/// The open ends of each chain need to join exactly three edges, whose end faces are planar.
+
+pub fn fillet(solid: &mut Solid, edges: &[Ref<Edge>], radius: f64) -> Result<(), String> {
+  if radius <= 0.0 {
+    return Err("Fillet radius needs to be positive".into())
+  }
+  blend_edges(solid, edges, |edge| fillet_blend(edge, radius) )
+}
+
+// Replacement geometry for an edge, oriented along one of its half edges
+struct Blend {
+  half_edge: Ref<HalfEdge>,
+  contacts: [(Point3, Point3); 2], // Contact points on left and right face, at start and end vertex
+  sections: [CurveType; 2], // Cross sections at start and end vertex
+  rails: [CurveType; 2], // Contact curves on left and right face
+  surface: SurfaceType,
+}
+
+// Plan blends for all chains of edges, before modifying the solid
+fn blend_edges<F>(solid: &mut Solid, edges: &[Ref<Edge>], make_blend: F) -> Result<(), String>
+where
+  F: Fn(&Ref<HalfEdge>) -> Result<Blend, String>,
+{
+  let mut chains = vec![];
+  for chain in blend_chains(edges)? {
+    let edge = chain[0].borrow().edge();
+    let shell_index = solid.shells.iter()
+      .position(|shell| shell.edges.iter().any(|other| Rc::ptr_eq(other, &edge) ) )
+      .ok_or("Edge does not belong to solid")?;
+    let blends = chain.iter().map(&make_blend).collect::<Result<Vec<Blend>, String>>()?;
+    check_blend_ends(&blends)?;
+    chains.push((shell_index, blends));
+  }
+  for (shell_index, blends) in chains {
+    apply_blend(&mut solid.shells[shell_index], blends);
+  }
+  Ok(())
+}
+
+// Group edges into chains of consecutive half edges, running along the first edge of each chain
+fn blend_chains(edges: &[Ref<Edge>]) -> Result<Vec<Vec<Ref<HalfEdge>>>, String> {
+  let mut remaining = edges.to_vec();
+  let mut chains = vec![];
+  while !remaining.is_empty() {
+    let edge = remaining.remove(0);
+    let mut chain = vec![edge.borrow().left_half.clone()];
+    loop {
+      let (start, end) = chain_ends(&chain);
+      if Rc::ptr_eq(&start, &end) { break }
+      let Some(he) = take_half_edge(&mut remaining, &end) else { break };
+      chain.push(he);
+    }
+    loop {
+      let (start, end) = chain_ends(&chain);
+      if Rc::ptr_eq(&start, &end) { break }
+      let Some(he) = take_half_edge(&mut remaining, &start) else { break };
+      let mate = he.borrow().mate();
+      chain.insert(0, mate);
+    }
+    let (start, end) = chain_ends(&chain);
+    if chain.len() == 1 && Rc::ptr_eq(&start, &end) {
+      return Err("Blending closed edges is not supported yet".into()) //XXX Requires splitting the edge
+    }
+    let touches_chain = |edge: &Ref<Edge>| {
+      let edge = edge.borrow();
+      [&edge.left_half, &edge.right_half].iter().any(|he| {
+        let vertex = he.borrow().origin.clone();
+        chain.iter().any(|other| Rc::ptr_eq(&other.borrow().origin, &vertex) ) || Rc::ptr_eq(&end, &vertex)
+      })
+    };
+    if remaining.iter().any(touches_chain) {
+      return Err("Blended edges must not share vertices with more than one other edge".into()) //XXX Requires vertex blends
+    }
+    chains.push(chain);
+  }
+  Ok(chains)
+}
+
+fn chain_ends(chain: &[Ref<HalfEdge>]) -> (Ref<Vertex>, Ref<Vertex>) {
+  (chain[0].borrow().origin.clone(), chain.last().unwrap().borrow().end_vertex())
+}
+
+// Remove an edge ending in `vertex` and return its half edge leaving `vertex`
+fn take_half_edge(edges: &mut Vec<Ref<Edge>>, vertex: &Ref<Vertex>) -> Option<Ref<HalfEdge>> {
+  edges.iter().enumerate().find_map(|(i, edge)| {
+    let edge = edge.borrow();
+    let he = [&edge.left_half, &edge.right_half].into_iter()
+      .find(|he| Rc::ptr_eq(&he.borrow().origin, vertex) )?;
+    Some((i, he.clone()))
+  }).map(|(i, he)| {
+    edges.remove(i);
+    he
+  })
+}
+
+fn is_closed_chain(blends: &[Blend]) -> bool {
+  let start = blends[0].half_edge.borrow().origin.clone();
+  let end = blends.last().unwrap().half_edge.borrow().end_vertex();
+  Rc::ptr_eq(&start, &end)
+}
+
+// Pairs of consecutive blends in a chain, meeting at a common vertex
+fn chain_joints(blends: &[Blend]) -> Vec<(&Blend, &Blend)> {
+  let count = if is_closed_chain(blends) { blends.len() } else { blends.len() - 1 };
+  (0..count).map(|i| (&blends[i], &blends[(i + 1) % blends.len()]) ).collect()
+}
+
+fn vertex_degree(he: &Ref<HalfEdge>) -> usize {
+  let mut degree = 1;
+  let mut scan = he.borrow().mate().borrow().next();
+  while !Rc::ptr_eq(&scan, he) {
+    degree += 1;
+    let mate = scan.borrow().mate();
+    scan = mate.borrow().next();
+  }
+  degree
+}
+
+// Cross sections at open chain ends need to lie on the planar end faces, with their contact points on the adjacent edges.
+// Consecutive edges need to continue each other tangentially, with a seam between the faces on one side of the chain.
+fn check_blend_ends(blends: &[Blend]) -> Result<(), String> {
+  if !is_closed_chain(blends) {
+    let (first, last) = (&blends[0], blends.last().unwrap());
+    let (left, right) = (first.half_edge.clone(), first.half_edge.borrow().mate());
+    let start = (left.borrow().previous(), right.borrow().next(), left.clone());
+    let (left, right) = (last.half_edge.clone(), last.half_edge.borrow().mate());
+    let end = (left.borrow().next(), right.borrow().previous(), right.clone());
+    let ends = [(start, &first.sections[0], first.contacts[0]), (end, &last.sections[1], last.contacts[1])];
+    for ((left_side, right_side, outgoing), section, contacts) in ends {
+      if vertex_degree(&outgoing) != 3 {
+        return Err("Blended edges need to end in vertices joining three edges".into())
+      }
+      if !splits_edge(&left_side, contacts.0) || !splits_edge(&right_side, contacts.1) {
+        return Err("Blend is too large for the adjacent edges".into())
+      }
+      let end_face = left_side.borrow().mate().borrow().face();
+      let is_contained = match &end_face.borrow().surface {
+        SurfaceType::Planar(surface) => {
+          let plane = &surface.plane;
+          (section.as_curve().sample(0.5) - plane.origin).dot(plane.normal()).abs() < BLEND_TOLERANCE
         },
+        _ => false,
+      };
+      if !is_contained {
+        return Err("Blends need planar end faces that contain their cross sections".into())
       }
     }
-  );
-  if distance > 200.0 {
-    Err(format!("Maximum extrusion distance exceeded"))
+  }
+  for (incoming, outgoing) in chain_joints(blends) {
+    let (incoming_he, outgoing_he) = (&incoming.half_edge, &outgoing.half_edge);
+    let tangents = (
+      curve_tangent(&incoming_he.borrow().make_curve(), 1.0),
+      curve_tangent(&outgoing_he.borrow().make_curve(), 0.0),
+    );
+    if vertex_degree(outgoing_he) != 3 || tangents.0.dot(tangents.1) < 1.0 - TANGENT_TOLERANCE {
+      return Err("Blended edges may only share vertices where they continue each other tangentially".into()) //XXX Requires vertex blends
+    }
+    let contacts = incoming.contacts[1];
+    let (seam, contact) = if Rc::ptr_eq(&incoming_he.borrow().next(), outgoing_he) {
+      (outgoing_he.borrow().mate().borrow().next(), contacts.1)
+    } else {
+      (incoming_he.borrow().next(), contacts.0)
+    };
+    if !splits_edge(&seam, contact) {
+      return Err("Blend is too large for the adjacent edges".into())
+    }
+  }
+  Ok(())
+}
+
+fn splits_edge(he: &Ref<HalfEdge>, p: Point3) -> bool {
+  let tcurve = he.borrow().make_curve();
+  let t = tcurve.unsample(p);
+  EPSILON < t && t < 1.0 - EPSILON && tcurve.sample(t).distance(p) < BLEND_TOLERANCE
+}
+
+// Split off the part of `he`'s edge next to its origin, returning the new edge between origin and p
+fn split_edge(shell: &mut Shell, he: &Ref<HalfEdge>, p: Point3) -> Ref<Edge> {
+  let next = he.borrow().mate().borrow().next();
+  let curve = he.borrow().edge().borrow().curve.clone();
+  shell.lmev(he, &next, curve, p).0
+}
+
+// Replace a chain of edges with new faces, running between the contact curves
+//
+// At open chain ends, the adjacent edges are split at the contact points and the corners are cut off.
+// Where two edges of the chain meet, the seam between the faces on one side is split at the contact point,
+// while the contact point on the face common to both edges is connected to the vertex by a temporary strut.
+// After cutting off strips along all edges, strips and corners are merged into one blend face per edge,
+// and the struts are collapsed, turning the split off seams into the cross sections between the blend faces.
+fn apply_blend(shell: &mut Shell, blends: Vec<Blend>) {
+  let is_closed = is_closed_chain(&blends);
+  let half_edges: Vec<Ref<HalfEdge>> = blends.iter().map(|blend| blend.half_edge.clone() ).collect();
+  let edges: Vec<Ref<Edge>> = half_edges.iter().map(|he| he.borrow().edge() ).collect();
+  let (first, last) = (&blends[0], blends.last().unwrap());
+  // Split adjacent edges at open ends
+  let mut ends = vec![];
+  if !is_closed {
+    let left = first.half_edge.clone();
+    let start = left.borrow().origin.clone();
+    let start_side = left.borrow().previous().borrow().mate();
+    let start_left = split_edge(shell, &start_side, first.contacts[0].0);
+    let right_next = left.borrow().mate().borrow().next();
+    let start_right = split_edge(shell, &right_next, first.contacts[0].1);
+    let left = last.half_edge.clone();
+    let end = left.borrow().end_vertex();
+    let left_next = left.borrow().next();
+    let end_left = split_edge(shell, &left_next, last.contacts[1].0);
+    let end_side = left.borrow().mate().borrow().previous().borrow().mate();
+    let end_right = split_edge(shell, &end_side, last.contacts[1].1);
+    // Cut off corners on end faces
+    let he = start_right.borrow().left_half.clone();
+    let start_side = start_left.borrow().right_half.borrow().next();
+    shell.lmef(&he, &start_side, first.sections[0].clone(), first.surface.clone());
+    let he = end_left.borrow().left_half.clone();
+    let end_side = end_right.borrow().right_half.borrow().next();
+    shell.lmef(&he, &end_side, last.sections[1].clone(), last.surface.clone());
+    ends.push((start_left, start_right, start));
+    ends.push((end_left, end_right, end));
+  }
+  // Split seams and add struts where edges meet
+  let mut joints = vec![];
+  for (incoming, outgoing) in chain_joints(&blends) {
+    let contacts = incoming.contacts[1];
+    let vertex = outgoing.half_edge.borrow().origin.clone();
+    let point = vertex.borrow().point;
+    let is_left_common = Rc::ptr_eq(&incoming.half_edge.borrow().next(), &outgoing.half_edge);
+    let (strut, seam) = if is_left_common {
+      let he = outgoing.half_edge.clone();
+      let (strut, _) = shell.lmev(&he, &he, Line::new(point, contacts.0).into_enum(), contacts.0);
+      let seam = he.borrow().mate().borrow().next();
+      (strut, split_edge(shell, &seam, contacts.1))
+    } else {
+      let he = incoming.half_edge.borrow().mate();
+      let (strut, _) = shell.lmev(&he, &he, Line::new(point, contacts.1).into_enum(), contacts.1);
+      let seam = incoming.half_edge.borrow().next();
+      (strut, split_edge(shell, &seam, contacts.0))
+    };
+    joints.push((vertex, strut, seam, incoming.sections[1].clone()));
+  }
+  // Cut off strips along the edges
+  for blend in &blends {
+    let [left_rail, right_rail] = blend.rails.clone();
+    let mate = blend.half_edge.borrow().mate();
+    for (he, rail) in [(blend.half_edge.clone(), left_rail), (mate, right_rail)] {
+      let previous = he.borrow().previous();
+      let after_next = he.borrow().next().borrow().next();
+      shell.lmef(&previous, &after_next, rail, blend.surface.clone());
+    }
+  }
+  // Merge pieces
+  for edge in &edges {
+    shell.lkef(edge);
+  }
+  for (side_left, side_right, vertex) in ends {
+    shell.lkef(&side_left);
+    shell.lkev(&side_right, &vertex);
+  }
+  for (vertex, strut, seam, section) in joints {
+    shell.lkev(&strut, &vertex);
+    seam.borrow_mut().curve = section;
+  }
+}
+
+fn fillet_blend(he: &Ref<HalfEdge>, radius: f64) -> Result<Blend, String> {
+  let (left, right, tcurve) = blend_faces(he);
+  let (left, right) = (left.borrow(), right.borrow());
+  // The ball runs inside of convex edges and outside of concave ones
+  let side = edge_side(&left, &right, &tcurve)?;
+  let ball = |t: f64| rolling_ball(&left, &right, tcurve.sample(t), radius * side);
+  let (start, end) = (ball(0.0)?, ball(1.0)?);
+  let contacts = [(start.1, start.2), (end.1, end.2)];
+  let is_exact = matches!(tcurve.base, CurveType::Line(_))
+    && matches!(left.surface, SurfaceType::Planar(_))
+    && matches!(right.surface, SurfaceType::Planar(_));
+  let (mut sections, mut rails, mut surface) = if is_exact {
+    (
+      [fillet_arc(start, radius), fillet_arc(end, radius)],
+      [Line::new(start.1, end.1).into_enum(), Line::new(start.2, end.2).into_enum()],
+      fillet_cylinder(start, end, radius),
+    )
+  } else {
+    let splines = (0..BLEND_SECTIONS).map(|i| {
+      let (center, p1, p2) = ball(i as f64 / (BLEND_SECTIONS - 1) as f64)?;
+      Ok(arc_spline(center, p1, p2))
+    }).collect::<Result<Vec<Spline>, String>>()?;
+    let surface = SplineSurface::lofted(&splines);
+    let rails = [isocurve(&surface, 0).into_enum(), isocurve(&surface, surface.controls[0].len() - 1).into_enum()];
+    (
+      [splines[0].clone().into_enum(), splines.last().unwrap().clone().into_enum()],
+      rails,
+      surface.into_enum(),
+    )
+  };
+  orient_blend(&mut surface, ball(0.5)?.0, side);
+  set_blend_ids(&he.borrow().edge(), &mut rails, &mut sections);
+  Ok(Blend {
+    half_edge: he.clone(),
+    contacts,
+    sections,
+    rails,
+    surface,
+  })
+}
+
+// Faces to the left and right of a half edge, along with its curve
+fn blend_faces(he: &Ref<HalfEdge>) -> (Ref<Face>, Ref<Face>, TrimmedCurve) {
+  let he = he.borrow();
+  let right = he.mate().borrow().face();
+  (he.face(), right, he.make_curve())
+}
+
+// -1 for convex edges, 1 for concave ones
+fn edge_side(left: &Face, right: &Face, tcurve: &TrimmedCurve) -> Result<f64, String> {
+  let middle = tcurve.sample(0.5);
+  let (_, left_normal) = project_to_face(left, middle)?;
+  let (_, right_normal) = project_to_face(right, middle)?;
+  let cross = left_normal.cross(right_normal);
+  if cross.magnitude2() < BLEND_TOLERANCE {
+    return Err("Can't blend edges between tangent faces".into())
+  }
+  Ok(if curve_tangent(tcurve, 0.5).dot(cross) > 0.0 { -1.0 } else { 1.0 })
+}
+
+// Derive stable ids from the blended edge
+fn set_blend_ids(edge: &Ref<Edge>, rails: &mut [CurveType; 2], sections: &mut [CurveType; 2]) {
+  let curve_id = edge.borrow().curve.id();
+  let fields = curve_id.as_fields();
+  for (i, curve) in rails.iter_mut().chain(sections.iter_mut()).enumerate() {
+    curve.set_id(Uuid::from_fields(fields.0, fields.1, fields.2.wrapping_add(i as u16 + 1), fields.3));
+  }
+}
+
+// Center of a ball with signed radius `offset`, that touches both faces near p, along with its contact points
+fn rolling_ball(left: &Face, right: &Face, p: Point3, offset: f64) -> Result<(Point3, Point3, Point3), String> {
+  let mut center = p;
+  for _ in 0..BLEND_ITERATIONS {
+    let (q1, n1) = project_to_face(left, center)?;
+    let (q2, n2) = project_to_face(right, center)?;
+    let cos = n1.dot(n2);
+    let sin2 = 1.0 - cos * cos;
+    if sin2 < BLEND_TOLERANCE {
+      return Err("Can't blend edges between tangent faces".into())
+    }
+    // Move along both normals until the ball is at distance `offset` from the tangent planes
+    let d1 = offset - (p - q1).dot(n1);
+    let d2 = offset - (p - q2).dot(n2);
+    center = p + n1 * ((d1 - d2 * cos) / sin2) + n2 * ((d2 - d1 * cos) / sin2);
+  }
+  let (q1, _) = project_to_face(left, center)?;
+  let (q2, _) = project_to_face(right, center)?;
+  Ok((center, q1, q2))
+}
+
+// Closest point on the surface of `face` and its outward normal
+fn project_to_face(face: &Face, p: Point3) -> Result<(Point3, Vec3), String> {
+  match &face.surface {
+    SurfaceType::Planar(surface) => {
+      let plane = &surface.plane;
+      let normal = plane.normal();
+      Ok((p - normal * (p - plane.origin).dot(normal), normal))
+    },
+    SurfaceType::Revolution(surface) => {
+      let (u, v) = surface.unsample(p);
+      let (du, dv) = surface.derivatives(u, v);
+      Ok((surface.sample(u, v), du.cross(dv).normalize()))
+    },
+    SurfaceType::Spline(surface) => {
+      let (u, v) = surface.unsample(p);
+      Ok((surface.sample(u, v), surface.normal_at(u, v)))
+    },
+  }
+}
+
+fn fillet_arc(ball: (Point3, Point3, Point3), radius: f64) -> CurveType {
+  let (center, p1, p2) = ball;
+  let u = (p1 - center).normalize();
+  let to_end = p2 - center;
+  let v = (to_end - u * to_end.dot(u)).normalize();
+  let plane = Plane { origin: center, u, v };
+  let angle = u.angle(to_end.normalize());
+  Arc::from_plane(plane, radius, 0.0, angle / Rad::full_turn()).into_enum()
+}
+
+fn fillet_cylinder(start: (Point3, Point3, Point3), end: (Point3, Point3, Point3), radius: f64) -> SurfaceType {
+  let axis = Axis::from_points((start.0, end.0));
+  let to_local = axis.as_transform().invert().unwrap();
+  let turn = |p: Point3| {
+    let local = to_local.transform_point(p);
+    Rad(local.y.atan2(local.x)) / Rad::full_turn()
+  };
+  let (t1, t2) = (turn(start.1), turn(start.2));
+  let span = (t2 - t1).rem_euclid(1.0);
+  let u_bounds = if span < 0.5 { (t1, t1 + span) } else { (t2, t2 + 1.0 - span) };
+  let height = start.0.distance(end.0);
+  RevolutionSurface {
+    axis,
+    curve: TrimmedCurve::new(Line::new(Point3::new(radius, 0.0, 0.0), Point3::new(radius, 0.0, height)).into_enum()),
+    u_bounds,
+  }.into_enum()
+}
+
+// Cubic approximation of the circular arc around `center` from p1 to p2
+fn arc_spline(center: Point3, p1: Point3, p2: Point3) -> Spline {
+  let (r1, r2) = (p1 - center, p2 - center);
+  let angle = r1.angle(r2);
+  let length = 4.0 / 3.0 * (angle / 4.0).tan() * r1.magnitude();
+  let t1 = (r2 - r1 * (r2.dot(r1) / r1.magnitude2())).normalize();
+  let t2 = (r1 - r2 * (r1.dot(r2) / r2.magnitude2())).normalize();
+  Spline::new(vec![p1, p1 + t1 * length, p2 + t2 * length, p2])
+}
+
+// Spline along v direction at the given column of control points
+fn isocurve(surface: &SplineSurface, column: usize) -> Spline {
+  let mut spline = Spline::new(surface.controls.iter().map(|row| row[column] ).collect());
+  spline.degree = surface.degree.1;
+  spline.knots = surface.knots.1.clone();
+  spline.weights = (0..surface.controls.len()).map(|row| surface.weight(row, column) ).collect();
+  spline
+}
+
+// Spline passing through all points, at uniform parameters
+pub(super) fn interpolate(points: &[Point3]) -> Spline {
+  let sections: Vec<Spline> = points.iter().map(|p| Spline::new(vec![*p, *p]) ).collect();
+  isocurve(&SplineSurface::lofted(&sections), 0)
+}
+
+// Blend surfaces face away from the ball on convex edges and towards it on concave ones
+fn orient_blend(surface: &mut SurfaceType, center: Point3, side: f64) {
+  let (du, dv) = surface.derivatives(0.5, 0.5);
+  let outward = (surface.as_surface().sample(0.5, 0.5) - center) * -side;
+  if du.cross(dv).dot(outward) < 0.0 {
+    surface.as_surface_mut().flip();
+  }
+}
+
+
+/// Setback of the faces adjacent to a [chamfer]ed edge.
+///
+/// Distances are measured perpendicular to the edge, the first one on the face to the left of the edge.
+/// Along chains of tangent edges, the left side is taken from the first edge of the chain.
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChamferMode {
+  /// Same distance on both faces
+  Distance(f64),
+  /// Separate distances on the left and right face
+  TwoDistances(f64, f64),
+  /// Distance on the left face and angle between the chamfer and the left face
+  DistanceAngle(f64, Deg<f64>),
+}
+
+
+/// Bevel the given [Edge]s of `solid` by cutting them off along a ruled face.
+///
+/// Edges between planar faces are replaced by exact planar faces, all others by spline approximations.
+/// The same restrictions as for [fillet] apply to shared and end vertices.
+
+pub fn chamfer(solid: &mut Solid, edges: &[Ref<Edge>], mode: ChamferMode) -> Result<(), String> {
+  let is_valid = match mode {
+    ChamferMode::Distance(distance) => distance > 0.0,
+    ChamferMode::TwoDistances(distance1, distance2) => distance1 > 0.0 && distance2 > 0.0,
+    ChamferMode::DistanceAngle(distance, angle) => distance > 0.0 && angle > Deg(0.0) && angle < Deg(180.0),
+  };
+  if !is_valid {
+    return Err("Chamfer distances and angles need to be positive".into())
+  }
+  blend_edges(solid, edges, |edge| chamfer_blend(edge, mode) )
+}
+
+fn chamfer_blend(he: &Ref<HalfEdge>, mode: ChamferMode) -> Result<Blend, String> {
+  let (left, right, tcurve) = blend_faces(he);
+  let (left, right) = (left.borrow(), right.borrow());
+  let side = edge_side(&left, &right, &tcurve)?;
+  let contact = |t: f64| chamfer_contacts(&left, &right, &tcurve, t, mode);
+  let (start, end) = (contact(0.0)?, contact(1.0)?);
+  let mut sections = [Line::new(start.0, start.1).into_enum(), Line::new(end.0, end.1).into_enum()];
+  let is_exact = matches!(tcurve.base, CurveType::Line(_))
+    && matches!(left.surface, SurfaceType::Planar(_))
+    && matches!(right.surface, SurfaceType::Planar(_));
+  let (mut rails, mut surface) = if is_exact {
+    let u = (end.0 - start.0).normalize();
+    let across = start.1 - start.0;
+    let plane = Plane { origin: start.0, u, v: (across - u * across.dot(u)).normalize() };
+    (
+      [Line::new(start.0, end.0).into_enum(), Line::new(start.1, end.1).into_enum()],
+      PlanarSurface::new(plane).into_enum(),
+    )
+  } else {
+    let splines = (0..BLEND_SECTIONS).map(|i| {
+      let (p1, p2) = contact(i as f64 / (BLEND_SECTIONS - 1) as f64)?;
+      Ok(Spline::new(vec![p1, p2]))
+    }).collect::<Result<Vec<Spline>, String>>()?;
+    let surface = SplineSurface::lofted(&splines);
+    ([isocurve(&surface, 0).into_enum(), isocurve(&surface, 1).into_enum()], surface.into_enum())
+  };
+  // Mirror the original edge on the chamfer, to get a reference point on the opposite side of the solid's material
+  let middle = contact(0.5)?;
+  let center = middle.0.midpoint(middle.1) * 2.0 - tcurve.sample(0.5).to_vec();
+  orient_blend(&mut surface, center, side);
+  set_blend_ids(&he.borrow().edge(), &mut rails, &mut sections);
+  Ok(Blend {
+    half_edge: he.clone(),
+    contacts: [start, end],
+    sections,
+    rails,
+    surface,
+  })
+}
+
+// Contact points of the chamfer on left and right face near the edge point at t
+fn chamfer_contacts(left: &Face, right: &Face, tcurve: &TrimmedCurve, t: f64, mode: ChamferMode) -> Result<(Point3, Point3), String> {
+  let p = tcurve.sample(t);
+  let tangent = curve_tangent(tcurve, t);
+  let (_, left_normal) = project_to_face(left, p)?;
+  let (_, right_normal) = project_to_face(right, p)?;
+  // Directions pointing into the faces, perpendicular to the edge
+  let left_dir = left_normal.cross(tangent).normalize();
+  let right_dir = tangent.cross(right_normal).normalize();
+  let (distance1, distance2) = match mode {
+    ChamferMode::Distance(distance) => (distance, distance),
+    ChamferMode::TwoDistances(distance1, distance2) => (distance1, distance2),
+    ChamferMode::DistanceAngle(distance, angle) => {
+      // Law of sines in the triangle between edge point and contact points
+      let opening = left_dir.angle(right_dir);
+      let far_angle = Rad::turn_div_2() - opening - Rad::from(angle);
+      if far_angle <= Rad(0.0) {
+        return Err("Chamfer angle is too large for the edge".into())
+      }
+      (distance, distance * Rad::from(angle).sin() / far_angle.sin())
+    },
+  };
+  let (q1, _) = project_to_face(left, p + left_dir * distance1)?;
+  let (q2, _) = project_to_face(right, p + right_dir * distance2)?;
+  Ok((q1, q2))
+}
+
+
+/// Hollow out `solid`, leaving walls of the given `thickness` behind.
+///
+/// All faces are offset inwards, except for `open_faces`, which are removed to open up the cavity.
+/// Adjacent open faces merge into a single opening, but must not form a loop around the solid.
+/// Without open faces, the cavity becomes a void inside the solid.
+
+pub fn shell(solid: &mut Solid, open_faces: &[Ref<Face>], thickness: f64) -> Result<(), String> {
+  if thickness <= 0.0 {
+    return Err("Wall thickness needs to be positive".into())
+  }
+  if solid.shells.len() != 1 {
+    return Err("Shelling solids with voids is not supported yet".into()) //XXX
+  }
+  let outer = &solid.shells[0];
+  if open_faces.iter().any(|face| !outer.faces.iter().any(|other| Rc::ptr_eq(other, face) ) ) {
+    return Err("Face does not belong to solid".into())
+  }
+  let is_open = |face: &Ref<Face>| open_faces.iter().any(|other| Rc::ptr_eq(other, face) );
+  if outer.faces.iter().all(is_open) {
+    return Err("At least one face needs to remain closed".into())
+  }
+  for face in open_faces {
+    if face.borrow().rings.len() > 1 {
+      return Err("Open faces must not contain holes".into()) //XXX
+    }
+  }
+  // Edges between open faces, which get removed from their middle part
+  let shared: Vec<usize> = outer.edges.iter().enumerate()
+    .filter(|(_, edge)| is_open(&edge.borrow().left_face()) && is_open(&edge.borrow().right_face()) )
+    .map(|(i, _)| i )
+    .collect();
+  let face_index = |face: &Ref<Face>| outer.faces.iter().position(|other| Rc::ptr_eq(other, face) ).unwrap();
+  let mut groups: Vec<usize> = (0..outer.faces.len()).collect();
+  for &i in &shared {
+    let edge = outer.edges[i].borrow();
+    for he in [&edge.left_half, &edge.right_half] {
+      let vertex = he.borrow().origin.clone();
+      if vertex.borrow().edges_iter().all(|he| is_open(&he.borrow().face()) ) {
+        return Err("Open faces need to meet closed faces at their corners".into()) //XXX
+      }
+    }
+    let root = |mut index: usize| {
+      while groups[index] != index { index = groups[index] }
+      index
+    };
+    let (left, right) = (root(face_index(&edge.left_face())), root(face_index(&edge.right_face())));
+    if left == right {
+      return Err("Open faces must not form a loop".into()) //XXX Requires splitting the walls
+    }
+    groups[right] = left;
+  }
+  // Open faces stay in place and cut through the walls instead
+  let wall = |face: &Ref<Face>| if is_open(face) { 0.0 } else { thickness };
+  // Offset geometry, indexed like the entities of the shell
+  let mut surfaces = outer.faces.iter().map(|face| {
+    let surface = &face.borrow().surface;
+    if is_open(face) { Ok(surface.clone()) } else { offset_surface(surface, thickness) }
+  }).collect::<Result<Vec<SurfaceType>, String>>()?;
+  let points = outer.vertices.iter().map(|vertex| {
+    let point = vertex.borrow().point;
+    let faces: Vec<Ref<Face>> = vertex.borrow().edges_iter().map(|he| he.borrow().face() ).collect();
+    let walls: Vec<(Ref<Face>, f64)> = faces.into_iter().map(|face| { let offset = wall(&face); (face, offset) }).collect();
+    offset_point(&walls, point)
+  }).collect::<Result<Vec<Point3>, String>>()?;
+  let vertex_point = |vertex: &Ref<Vertex>| points[outer.vertices.iter().position(|other| Rc::ptr_eq(other, vertex) ).unwrap()];
+  let curves = outer.edges.iter().map(|edge| {
+    let edge = edge.borrow();
+    let walls = [edge.left_face(), edge.right_face()].map(|face| { let offset = wall(&face); (face, offset) });
+    let left_half = edge.left_half.borrow();
+    let ends = (vertex_point(&left_half.origin), vertex_point(&left_half.end_vertex()));
+    offset_curve(&left_half.make_curve(), &walls, ends)
+  }).collect::<Result<Vec<CurveType>, String>>()?;
+  // Curved faces need to be trimmed to their new boundaries
+  for (surface, face) in surfaces.iter_mut().zip(&outer.faces) {
+    if let (SurfaceType::Revolution(surface), false) = (surface, is_open(face)) {
+      let boundary: Vec<Point3> = face.borrow().rings.iter()
+        .flat_map(|ring| ring.borrow().iter().collect::<Vec<_>>() )
+        .flat_map(|he| {
+          let he = he.borrow();
+          let edge = he.edge();
+          let curve = &curves[outer.edges.iter().position(|other| Rc::ptr_eq(other, &edge) ).unwrap()];
+          let tcurve = TrimmedCurve::from_bounds(curve.clone(), (vertex_point(&he.origin), vertex_point(&he.end_vertex())), curve.clone());
+          (0..OFFSET_SAMPLES).map(move |i| tcurve.sample(i as f64 / (OFFSET_SAMPLES - 1) as f64) )
+        }).collect();
+      retrim_revolution(surface, &boundary);
+    }
+  }
+  // Build the cavity as an inverted copy of the shell
+  let mut inner = outer.deep_clone();
+  for (vertex, point) in inner.vertices.iter().zip(points) {
+    vertex.borrow_mut().point = point;
+  }
+  for (edge, mut curve) in inner.edges.iter().zip(curves) {
+    let mut edge = edge.borrow_mut();
+    // Derive stable ids from the original edges
+    let curve_id = edge.curve.id();
+    let fields = curve_id.as_fields();
+    curve.set_id(Uuid::from_fields(fields.0.wrapping_add(1), fields.1, fields.2, fields.3));
+    edge.id = Uuid::new_v4();
+    edge.curve = curve;
+  }
+  for (face, surface) in inner.faces.iter().zip(surfaces) {
+    let mut face = face.borrow_mut();
+    face.id = Uuid::new_v4();
+    face.surface = surface;
+  }
+  inner.invert();
+  if open_faces.is_empty() {
+    solid.shells.push(inner);
+    return Ok(())
+  }
+  // Punch copies of the open faces as holes into the originals, which joins both shells
+  let openings: Vec<(Ref<Face>, Ref<Face>)> = outer.faces.iter().zip(&inner.faces)
+    .filter(|(face, _)| is_open(face) )
+    .map(|(face, inner_face)| (face.clone(), inner_face.clone()) )
+    .collect();
+  // Remember open faces by their remaining half edges, and shared edges by the vertices at both ends
+  let rims: Vec<(Uuid, SurfaceType, Vec<Ref<HalfEdge>>)> = openings.iter().map(|(face, _)| {
+    let half_edges = face.borrow().outer_ring.borrow().iter().filter(|he| !shared.iter().any(|&i|
+      Rc::ptr_eq(&he.borrow().edge(), &outer.edges[i])
+    )).collect();
+    (face.borrow().id, face.borrow().surface.clone(), half_edges)
+  }).collect();
+  let vertex_pair = |vertex: &Ref<Vertex>| {
+    let index = outer.vertices.iter().position(|other| Rc::ptr_eq(other, vertex) ).unwrap();
+    (vertex.clone(), inner.vertices[index].clone())
+  };
+  let joints: Vec<_> = shared.iter().map(|&i| {
+    let edge = outer.edges[i].borrow();
+    let ends = [vertex_pair(&edge.left_half.borrow().origin), vertex_pair(&edge.right_half.borrow().origin)];
+    (outer.edges[i].clone(), inner.edges[i].clone(), ends)
+  }).collect();
+  let shell = &mut solid.shells[0];
+  shell.faces.extend(inner.faces);
+  shell.edges.extend(inner.edges);
+  shell.vertices.extend(inner.vertices);
+  // Merge adjacent open faces, along with their copies
+  for (edge, inner_edge, _) in &joints {
+    shell.lkef(edge);
+    shell.lkef(inner_edge);
+  }
+  for (face, inner_face) in openings {
+    if shell.faces.iter().any(|other| Rc::ptr_eq(other, &face) ) {
+      shell.lkfmrh(&face, &inner_face);
+    }
+  }
+  // Reconnect the outer and inner corners of merged openings along the shared edges, splitting them into rims again
+  for (edge, _, ends) in joints {
+    let curve = edge.borrow().curve.clone();
+    for (vertex, inner_vertex) in ends {
+      let (he, inner_he) = shell.faces.iter().find_map(|face| {
+        let half_edges: Vec<Ref<HalfEdge>> = face.borrow().rings.iter().flat_map(|ring| ring.borrow().iter().collect::<Vec<_>>() ).collect();
+        let find = |vertex: &Ref<Vertex>| half_edges.iter().find(|he| Rc::ptr_eq(&he.borrow().origin, vertex) ).cloned();
+        Some((find(&vertex)?, find(&inner_vertex)?))
+      }).ok_or("Can't connect merged openings")?;
+      if Rc::ptr_eq(&he.borrow().ring.upgrade().unwrap(), &inner_he.borrow().ring.upgrade().unwrap()) {
+        let surface = he.borrow().face().borrow().surface.clone();
+        shell.lmef(&he, &inner_he, curve.clone(), surface);
+      } else {
+        shell.lmekr(&he, &inner_he, curve.clone());
+      }
+    }
+  }
+  // Restore surfaces of the rims, keeping the ids of the original faces on one piece each
+  let mut used_ids = vec![];
+  for face in &shell.faces {
+    let rim = rims.iter().find(|(_, _, half_edges)| half_edges.iter().any(|he|
+      face.borrow().rings.iter().any(|ring| Rc::ptr_eq(&he.borrow().ring.upgrade().unwrap(), ring) )
+    ));
+    if let Some((id, surface, _)) = rim {
+      let mut face = face.borrow_mut();
+      face.surface = surface.clone();
+      face.id = if used_ids.contains(id) { Uuid::new_v4() } else { *id };
+      used_ids.push(*id);
+    }
+  }
+  Ok(())
+}
+
+// Point at the given distances below all faces
+fn offset_point(walls: &[(Ref<Face>, f64)], p: Point3) -> Result<Point3, String> {
+  let mut point = p;
+  for _ in 0..BLEND_ITERATIONS {
+    // Least squares fit to the offset tangent planes
+    let mut lhs = Matrix3::identity() * OFFSET_REGULARIZATION;
+    let mut rhs = p.to_vec() * OFFSET_REGULARIZATION;
+    for (face, offset) in walls {
+      let (q, normal) = project_to_face(&face.borrow(), point)?;
+      lhs += Matrix3::from_cols(normal * normal.x, normal * normal.y, normal * normal.z);
+      rhs += normal * (normal.dot(q.to_vec()) - offset);
+    }
+    point = Point3::from_vec(lhs.invert().ok_or("Can't offset degenerate vertices")? * rhs);
+  }
+  Ok(point)
+}
+
+// Edge curve between the offset faces on both sides of an edge
+fn offset_curve(tcurve: &TrimmedCurve, walls: &[(Ref<Face>, f64); 2], ends: (Point3, Point3)) -> Result<CurveType, String> {
+  let samples = (0..OFFSET_SAMPLES).map(|i| {
+    match i {
+      0 => Ok(ends.0),
+      i if i == OFFSET_SAMPLES - 1 => Ok(ends.1),
+      _ => offset_point(walls, tcurve.sample(i as f64 / (OFFSET_SAMPLES - 1) as f64)),
+    }
+  }).collect::<Result<Vec<Point3>, String>>()?;
+  // Circles stay circles, as long as they run around the offset direction
+  let circle_offset = |plane: &Plane| {
+    let center = circumcenter(samples[0], samples[OFFSET_SAMPLES / 2], samples[OFFSET_SAMPLES / 3])?;
+    let radius = center.distance(samples[0]);
+    let normal = plane.normal();
+    let is_circular = samples.iter().all(|p|
+      (p.distance(center) - radius).abs() < BLEND_TOLERANCE && (p - center).dot(normal).abs() < BLEND_TOLERANCE
+    );
+    if is_circular { Some((center, radius)) } else { None }
+  };
+  let curve = match &tcurve.base {
+    CurveType::Line(_) => Some(Line::new(ends.0, ends.1).into_enum()),
+    CurveType::Arc(arc) => circle_offset(&arc.plane).map(|(center, radius)| {
+      let mut arc = arc.clone();
+      arc.plane.origin = center;
+      arc.radius = radius;
+      arc.into_enum()
+    }),
+    CurveType::Circle(circle) => circle_offset(&circle.plane).map(|(center, radius)| {
+      let mut circle = circle.clone();
+      circle.plane.origin = center;
+      circle.radius = radius;
+      circle.into_enum()
+    }),
+    CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => None,
+  };
+  Ok(curve.unwrap_or_else(|| interpolate(&samples).into_enum() ))
+}
+
+// Center of the circle through three points
+fn circumcenter(p1: Point3, p2: Point3, p3: Point3) -> Option<Point3> {
+  let (a, b) = (p1 - p3, p2 - p3);
+  let normal = a.cross(b);
+  let denominator = 2.0 * normal.magnitude2();
+  if denominator < BLEND_TOLERANCE { return None }
+  Some(p3 + (b * a.magnitude2() - a * b.magnitude2()).cross(normal) / denominator)
+}
+
+// Parallel surface at `distance` below the given one
+fn offset_surface(surface: &SurfaceType, distance: f64) -> Result<SurfaceType, String> {
+  match surface {
+    SurfaceType::Planar(surface) => {
+      let mut surface = surface.clone();
+      surface.plane.origin -= surface.plane.normal() * distance;
+      Ok(surface.into_enum())
+    },
+    SurfaceType::Revolution(surface) => offset_revolution(surface, distance),
+    SurfaceType::Spline(surface) => {
+      let n = OFFSET_SAMPLES;
+      let rows: Vec<Spline> = (0..n).map(|j| {
+        let points: Vec<Point3> = (0..n).map(|i| {
+          let (u, v) = (i as f64 / (n - 1) as f64, j as f64 / (n - 1) as f64);
+          let (du, dv) = surface.derivatives(u, v);
+          surface.sample(u, v) - du.cross(dv).normalize() * distance
+        }).collect();
+        interpolate(&points)
+      }).collect();
+      Ok(SplineSurface::lofted(&rows).into_enum())
+    },
+  }
+}
+
+// Offset the generatrix of a revolution surface within its local XZ plane
+fn offset_revolution(surface: &RevolutionSurface, distance: f64) -> Result<SurfaceType, String> {
+  let to_local = surface.axis.as_transform().invert().unwrap();
+  // Outward direction of the surface in local space, at parameter v of the generatrix
+  let normal_at = |v: f64| {
+    let (du, dv) = surface.derivatives(0.5, v);
+    let sample = to_local.transform_point(surface.sample(0.5, v));
+    let normal = to_local.transform_vector(du.cross(dv).normalize());
+    Matrix4::from_angle_z(Rad(-sample.y.atan2(sample.x))).transform_vector(normal)
+  };
+  let tcurve = &surface.curve;
+  let base = match &tcurve.base {
+    CurveType::Line(line) => {
+      let offset = normal_at(0.5) * -distance;
+      Some(Line::new(line.points.0 + offset, line.points.1 + offset).into_enum())
+    },
+    CurveType::Arc(arc) => {
+      let mut arc = arc.clone();
+      let outward = normal_at(0.5).dot(tcurve.sample(0.5) - arc.plane.origin) > 0.0;
+      arc.radius += if outward { -distance } else { distance };
+      Some(arc.into_enum())
+    },
+    CurveType::Circle(circle) => {
+      let mut circle = circle.clone();
+      let outward = normal_at(0.5).dot(tcurve.sample(0.5) - circle.plane.origin) > 0.0;
+      circle.radius += if outward { -distance } else { distance };
+      Some(circle.into_enum())
+    },
+    CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => None,
+  };
+  let curve = if let Some(base) = base {
+    // Analytic offsets preserve the parameterization of the generatrix
+    let mut curve = tcurve.clone();
+    curve.bounds = (base.as_curve().sample(tcurve.trims.0), base.as_curve().sample(tcurve.trims.1));
+    curve.base = base.clone();
+    curve.cache = base;
+    curve
   } else {
-    Ok(solid)
+    let points: Vec<Point3> = (0..OFFSET_SAMPLES).map(|i| {
+      let v = i as f64 / (OFFSET_SAMPLES - 1) as f64;
+      tcurve.sample(v) - normal_at(v) * distance
+    }).collect();
+    TrimmedCurve::new(interpolate(&points).into_enum())
+  };
+  if curve.sample(0.5).x < 0.0 {
+    return Err("Wall thickness exceeds the radius of revolved faces".into())
   }
+  Ok(RevolutionSurface {
+    axis: surface.axis.clone(),
+    curve,
+    u_bounds: surface.u_bounds,
+  }.into_enum())
 }
 
 
-/// Create a new [Solid] by sweeping `profile` around the given `axis`.
-
-pub fn revolve(profile: &Profile, mut axis: Axis, angle: Deg<f64>) -> Result<Solid, String> {
-  let wire = profile.rings[0].clone();
-  let mut solid = Solid::lamina(wire, PlanarSurface::new(profile.plane.clone()).into_enum());
-  let shell = &mut solid.shells[0];
-  if axis.direction.dot(profile.plane.u).signum() < 0.0 {
-    axis.flip();
+// Restrict the parametric range of a revolution surface to the given boundary points
+fn retrim_revolution(surface: &mut RevolutionSurface, boundary: &[Point3]) {
+  let params: Vec<(f64, f64)> = boundary.iter().map(|p| surface.unsample(*p) ).collect();
+  let range = |values: &mut dyn Iterator<Item = f64>| values.fold((f64::MAX, f64::MIN), |(min, max), x| (min.min(x), max.max(x)) );
+  let (v_min, v_max) = range(&mut params.iter().map(|param| param.1 ));
+  let tcurve = &mut surface.curve;
+  tcurve.trims = (tcurve.param_to_base(v_min), tcurve.param_to_base(v_max));
+  let base = tcurve.base.as_curve();
+  tcurve.bounds = (base.sample(tcurve.trims.0), base.sample(tcurve.trims.1));
+  //XXX Partial revolutions are bounded by u isolines, which offset side faces don't follow in general
+  let (start, end) = surface.u_bounds;
+  if (end - start).abs() < 1.0 - EPSILON {
+    let (u_min, u_max) = range(&mut params.iter().map(|param| param.0 ));
+    surface.u_bounds = (start + u_min * (end - start), start + u_max * (end - start));
   }
-  let is_forward = angle >= Deg(0.0);
-  let face = if is_forward {
-    shell.faces.last()
-  } else {
-    shell.faces.first()
-  }.unwrap().clone();
-  let transform = axis.rotation(angle);
-  shell.sweep(
-    &face,
-    &transform,
-    |point| {
-      let p_axis = axis.closest_point(point);
-      let radius = p_axis.distance(point);
-      let mut plane: Plane = (&axis).into();
-      plane.origin = p_axis;
-      let mut arc = Arc::from_plane(plane, radius, 0.0, 1.0);
-      let t = arc.unsample(point);
-      arc.bounds.0 = t;
-      arc.bounds.1 = t + (angle / Deg(360.0));
-      arc.into_enum()
-    },
-    |tcurve| {
-      let mut tcurve = tcurve.clone();
-      if is_forward {
-        tcurve.flip();
-      }
-      let mut surface = RevolutionSurface::with_bounds(axis.clone(), tcurve.clone(), (0.0, angle / Deg(360.0)));
-      if !is_forward {
-        surface.flip();
-      }
-      surface.into_enum()
-    }
-  );
-  Ok(solid)
 }
 
 
 /// Tilt the given [Face]s by `angle` degrees, such that their intersection with `fixed_plane` remains constant.
 ///
 /// The faces may belong to different solids or compounds.
+/// Positive angles tilt faces inwards on the side that `fixed_plane` faces towards.
+/// Cylindrical faces turn into cones and ruled spline faces remain ruled.
 
-pub fn draft(faces: &Vec<Ref<Face>>, fixed_plane: &Plane, angle: Deg<f64>) -> Result<(), String> {
+pub fn draft(faces: &[Ref<Face>], fixed_plane: &Plane, angle: Deg<f64>) -> Result<(), String> {
+  if angle.0.abs() >= 90.0 {
+    return Err("Draft angle must be smaller than 90°".into())
+  }
+  let draft = Draft { plane: fixed_plane.clone(), slope: Rad::from(angle).0.tan() };
+  let mut edges: Vec<Ref<Edge>> = vec![];
+  let mut vertices: Vec<Ref<Vertex>> = vec![];
+  let mut tilted: Vec<Ref<Edge>> = vec![];
   for face in faces {
-    let mut face = face.borrow_mut();
-    match &face.surface {
+    let surface = face.borrow().surface.clone();
+    match surface {
       SurfaceType::Planar(plane) => {
         if let Some(intersection) = intersection::plane_plane(&plane.plane, fixed_plane) {
           if let Some(line) = intersection.get_line() {
             let axis = Axis::from_points(line.endpoints());
-            face.surface.as_surface_mut().rotate_about_axis(&axis, angle);
+            face.borrow_mut().surface.as_surface_mut().rotate_about_axis(&axis, angle);
+          }
+        }
+        collect_boundary(face, &mut tilted, &mut vec![]);
+      },
+      SurfaceType::Revolution(mut surface) => {
+        let outward = Outward::revolution(&surface, fixed_plane)?;
+        // The generatrix is stored relative to the axis
+        let to_world = surface.axis.as_transform();
+        let to_local = to_world.invert().unwrap();
+        let map = |p: Point3| draft.map(to_world.transform_point(p), &outward).map(|p| to_local.transform_point(p) );
+        if let CurveType::Line(line) = &mut surface.curve.base {
+          line.points = (map(line.points.0)?, map(line.points.1)?);
+        } else {
+          return Err("Only cylindrical and conical faces of revolution can be drafted".into())
+        }
+        let bounds = (map(surface.curve.bounds.0)?, map(surface.curve.bounds.1)?);
+        surface.curve.set_bounds(bounds);
+        draft_boundary(face, &draft, &outward, &mut edges, &mut vertices)?;
+        face.borrow_mut().surface = surface.into_enum();
+      },
+      SurfaceType::Spline(mut surface) => {
+        if surface.degree.1 != 1 {
+          return Err("Only ruled spline faces can be drafted".into())
+        }
+        let outward = Outward::spline(&surface);
+        draft_boundary(face, &draft, &outward, &mut edges, &mut vertices)?;
+        for row in &mut surface.controls {
+          for point in row {
+            *point = draft.map(*point, &outward)?;
           }
         }
+        face.borrow_mut().surface = surface.into_enum();
+      },
+    }
+  }
+  // Re-trim neighbours to tilted planar faces, along the intersections of their surfaces
+  for edge in tilted {
+    edge.borrow_mut().repair()?;
+  }
+  Ok(())
+}
+
+// Tilts faces by moving their points parallel to the fixed plane, proportionally to their distance from it
+struct Draft {
+  plane: Plane,
+  slope: f64,
+}
+
+impl Draft {
+  fn map(&self, p: Point3, outward: &Outward) -> Result<Point3, String> {
+    let normal = self.plane.normal();
+    let outward = outward.at(p);
+    let direction = outward - normal * outward.dot(normal);
+    if direction.magnitude().almost(0.0) {
+      return Err("Faces parallel to the fixed plane can't be drafted".into())
+    }
+    let height = (p - self.plane.origin).dot(normal);
+    Ok(p - direction.normalize() * (height * self.slope))
+  }
+}
+
+// Direction pointing away from the solid at points of a drafted face
+enum Outward {
+  // Away from the axis, or towards it for negative signs
+  Radial(Axis, f64),
+  // Surface normals at the Greville abscissae of the control points
+  Normal(SplineSurface, Vec<(Point3, Vec3)>),
+}
+
+impl Outward {
+  fn revolution(surface: &RevolutionSurface, fixed_plane: &Plane) -> Result<Self, String> {
+    if !surface.axis.direction.cross(fixed_plane.normal()).magnitude().almost(0.0) {
+      return Err("Faces of revolution can only be drafted along their axis".into())
+    }
+    let (du, dv) = surface.derivatives(0.5, 0.5);
+    let radial = Self::Radial(surface.axis.clone(), 1.0).at(surface.sample(0.5, 0.5));
+    Ok(Self::Radial(surface.axis.clone(), du.cross(dv).dot(radial).signum()))
+  }
+
+  fn spline(surface: &SplineSurface) -> Self {
+    let greville = |knots: &[f64], degree: usize, i: usize|
+      knots[i + 1..=i + degree].iter().sum::<f64>() / degree as f64 / knots.last().unwrap();
+    let controls = surface.controls.iter().enumerate().flat_map(|(i, row)| {
+      let v = greville(&surface.knots.1, surface.degree.1, i);
+      row.iter().enumerate().map(move |(j, point)| {
+        (*point, surface.normal_at(greville(&surface.knots.0, surface.degree.0, j), v))
+      })
+    }).collect();
+    Self::Normal(surface.clone(), controls)
+  }
+
+  fn at(&self, p: Point3) -> Vec3 {
+    match self {
+      Self::Radial(axis, sign) => {
+        let offset = p - axis.origin;
+        (offset - axis.direction * offset.dot(axis.direction)) * *sign
+      },
+      Self::Normal(surface, controls) => {
+        if let Some((_, normal)) = controls.iter().find(|(point, _)| point.almost(p) ) {
+          *normal
+        } else {
+          let (u, v) = surface.unsample(p);
+          surface.normal_at(u, v)
+        }
+      },
+    }
+  }
+}
+
+// Move the vertices and edges of a curved face along with its surface, unless another face moved them already
+fn draft_boundary(face: &Ref<Face>, draft: &Draft, outward: &Outward, edges: &mut Vec<Ref<Edge>>, vertices: &mut Vec<Ref<Vertex>>) -> Result<(), String> {
+  let (known_edges, known_vertices) = (edges.len(), vertices.len());
+  collect_boundary(face, edges, vertices);
+  for vertex in &vertices[known_vertices..] {
+    let point = vertex.borrow().point;
+    vertex.borrow_mut().point = draft.map(point, outward)?;
+  }
+  for edge in &edges[known_edges..] {
+    match &mut edge.borrow_mut().curve {
+      CurveType::Line(line) => line.points = (draft.map(line.points.0, outward)?, draft.map(line.points.1, outward)?),
+      CurveType::Arc(arc) => arc.radius = draft.map(arc.sample(0.0), outward)?.distance(arc.plane.origin),
+      CurveType::Circle(circle) => circle.radius = draft.map(circle.sample(0.0), outward)?.distance(circle.plane.origin),
+      CurveType::Spline(spline) => for point in &mut spline.controls {
+        *point = draft.map(*point, outward)?;
+      },
+      //XXX Mapping the control points of the spline form only approximates the drafted curve
+      curve @ (CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_)) => {
+        let mut spline = spline_form(curve);
+        for point in &mut spline.controls {
+          *point = draft.map(*point, outward)?;
+        }
+        *curve = spline.into_enum();
       },
-      SurfaceType::Revolution(_) => todo!(),
-      SurfaceType::Spline(_) => todo!(),
     }
   }
   Ok(())
 }
 
+// Append the edges and vertices of all rings of `face` that haven't been seen yet
+fn collect_boundary(face: &Ref<Face>, edges: &mut Vec<Ref<Edge>>, vertices: &mut Vec<Ref<Vertex>>) {
+  for ring in &face.borrow().rings {
+    for he in ring.borrow().iter() {
+      let he = he.borrow();
+      let edge = he.edge();
+      if !edges.iter().any(|other| Rc::ptr_eq(other, &edge) ) {
+        edges.push(edge);
+      }
+      if !vertices.iter().any(|other| Rc::ptr_eq(other, &he.origin) ) {
+        vertices.push(he.origin.clone());
+      }
+    }
+  }
+}
+
 
 /// Create a solid cube, centered on [EuclideanSpace::origin].
 
@@ -193,7 +2231,6 @@ mod tests {
   }
 
   #[test]
-  #[ignore]
   fn cylinder() {
     let cube = &make_cylinder(1.0, 1.0).unwrap();
     let shell = &cube.shells[0];
@@ -207,4 +2244,1016 @@ mod tests {
     assert_eq!(shell.faces[2].borrow().outer_ring.borrow().iter().count(), 4);
     // panic!("Test trap");
   }
+
+  fn square_profile() -> Profile {
+    let points = [
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 0.0, 0.0),
+      Point3::new(1.0, 1.0, 0.0),
+      Point3::new(0.0, 1.0, 0.0),
+    ];
+    let wire = (0..4).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    ).collect();
+    Profile::new(Plane::new(), vec![Wire::new(wire)])
+  }
+
+  fn circle_profile() -> Profile {
+    let wire = vec![TrimmedCurve::new(Circle::new(Point3::new(0.5, 0.5, 0.0), 0.5).into_enum())];
+    Profile::new(Plane::new(), vec![Wire::new(wire)])
+  }
+
+  // Straight segment, followed by a quarter turn with radius 3 and another straight segment
+  fn bent_path() -> Wire {
+    let plane = Plane {
+      origin: Point3::new(3.5, 0.5, 2.0),
+      u: Vec3::new(-1.0, 0.0, 0.0),
+      v: Vec3::new(0.0, 0.0, 1.0),
+    };
+    let arc = Arc::from_plane(plane, 3.0, 0.0, 0.25);
+    Wire::open(vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 0.0), Point3::new(0.5, 0.5, 2.0)).into_enum()),
+      TrimmedCurve::new(arc.into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(3.5, 0.5, 5.0), Point3::new(5.5, 0.5, 5.0)).into_enum()),
+    ]).unwrap()
+  }
+
+  #[test]
+  fn sweep_straight() {
+    let path = Wire::open(vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 0.0), Point3::new(0.5, 0.5, 3.0)).into_enum()),
+    ]).unwrap();
+    let solid = sweep(&square_profile(), &path, SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 6);
+    assert!(solid.volume().almost(3.0));
+    let path = Wire::open(vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 0.0), Point3::new(0.5, 0.5, -3.0)).into_enum()),
+    ]).unwrap();
+    let solid = sweep(&square_profile(), &path, SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!(solid.volume().almost(3.0));
+  }
+
+  #[test]
+  fn sweep_spline() {
+    let spline = Spline::new(vec![
+      Point3::new(0.5, 0.5, 0.0),
+      Point3::new(0.5, 0.5, 2.0),
+      Point3::new(2.5, 0.5, 3.0),
+      Point3::new(4.5, 0.5, 3.0),
+    ]);
+    let path = Wire::open(vec![TrimmedCurve::new(spline.into_enum())]).unwrap();
+    let solid = sweep(&square_profile(), &path, SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!(solid.volume() > 0.0);
+  }
+
+  #[test]
+  fn sweep_bent() {
+    let length = 4.0 + 1.5 * std::f64::consts::PI;
+    let solid = sweep(&square_profile(), &bent_path(), SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 14);
+    assert!((solid.volume() - length).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn sweep_bent_circle() {
+    let length = 4.0 + 1.5 * std::f64::consts::PI;
+    let solid = sweep(&circle_profile(), &bent_path(), SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!((solid.volume() - length * std::f64::consts::PI * 0.25).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn sweep_helix() {
+    let helix = Helix::new(&Axis::new(Point3::origin(), Vec3::unit_z()), 3.0, 2.0, 1.0);
+    let length = helix.length();
+    // Unit square centered on the start of the spring, perpendicular to its tangent
+    let u = Vec3::unit_x();
+    let plane = Plane { origin: helix.sample(0.0), u, v: helix.tangent_at(0.0).cross(u) };
+    let corners = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)].map(|(x, y)| plane.sample(x, y) );
+    let wire = (0..4).map(|i|
+      TrimmedCurve::new(Line::new(corners[i], corners[(i + 1) % 4]).into_enum())
+    ).collect();
+    let profile = Profile::new(plane, vec![Wire::new(wire)]);
+    let path = Wire::open(vec![TrimmedCurve::new(helix.clone().into_enum())]).unwrap();
+    let solid = sweep(&profile, &path, SweepOrientation::Frenet).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!((solid.volume() - length).abs() < 1.0e-5, "{} != {}", solid.volume(), length);
+    // Corners of the profile travel along exact helices, ending up around the end of the path
+    assert_eq!(solid.edges_iter().filter(|edge| matches!(edge.borrow().curve, CurveType::Helix(_)) ).count(), 4);
+    let ends: Vec<Point3> = corners.iter().map(|corner| screw_transform(&helix).transform_point(*corner) ).collect();
+    almost_eq!(Point3::centroid(&ends), helix.sample(1.0));
+    for end in ends {
+      assert!(solid.shells[0].vertices.iter().any(|vertex| vertex.borrow().point.almost(end) ));
+    }
+  }
+
+  #[test]
+  fn sweep_fixed() {
+    let path = Wire::open(vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 0.0), Point3::new(0.5, 0.5, 2.0)).into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 2.0), Point3::new(2.5, 0.5, 4.0)).into_enum()),
+    ]).unwrap();
+    let solid = sweep(&square_profile(), &path, SweepOrientation::Fixed).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!(solid.volume().almost(4.0));
+    assert!(sweep(&square_profile(), &path, SweepOrientation::Frenet).is_err());
+  }
+
+  #[test]
+  fn sweep_fixed_arc() {
+    // Straight segment, followed by an eighth turn with radius 3
+    let plane = Plane {
+      origin: Point3::new(3.5, 0.5, 2.0),
+      u: Vec3::new(-1.0, 0.0, 0.0),
+      v: Vec3::new(0.0, 0.0, 1.0),
+    };
+    let path = Wire::open(vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.5, 0.5, 0.0), Point3::new(0.5, 0.5, 2.0)).into_enum()),
+      TrimmedCurve::new(Arc::from_plane(plane, 3.0, 0.0, 0.125).into_enum()),
+    ]).unwrap();
+    // Translated profiles sweep out the area of the profile times the rise of the path
+    let height = 2.0 + 3.0 * std::f64::consts::FRAC_1_SQRT_2;
+    let solid = sweep(&square_profile(), &path, SweepOrientation::Fixed).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert!((solid.volume() - height).abs() < 1.0e-6, "{}", solid.volume());
+    // Vertices follow exact copies of the arc
+    assert_eq!(solid.edges_iter().filter(|edge| matches!(edge.borrow().curve, CurveType::Arc(_)) ).count(), 4);
+    let solid = sweep(&circle_profile(), &path, SweepOrientation::Fixed).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - height * std::f64::consts::PI * 0.25).abs() < 1.0e-6, "{}", solid.volume());
+  }
+
+  #[test]
+  fn sweep_hole() {
+    let length = 4.0 + 1.5 * std::f64::consts::PI;
+    let mut profile = square_profile();
+    let points = [
+      Point3::new(0.25, 0.25, 0.0),
+      Point3::new(0.25, 0.75, 0.0),
+      Point3::new(0.75, 0.75, 0.0),
+      Point3::new(0.75, 0.25, 0.0),
+    ];
+    profile.rings.push(Wire::new((0..4).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    ).collect()));
+    let solid = sweep(&profile, &bent_path(), SweepOrientation::Frenet).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    assert!((solid.volume() - length * 0.75).abs() < 1.0e-3);
+  }
+
+  fn square_at(z: f64, size: f64, flip: bool) -> Profile {
+    let half = size / 2.0;
+    let mut points = [
+      Point3::new(-half, -half, z),
+      Point3::new(half, -half, z),
+      Point3::new(half, half, z),
+      Point3::new(-half, half, z),
+    ];
+    let mut plane = Plane::from_point(Point3::new(0.0, 0.0, z));
+    if flip {
+      points.reverse();
+      points.rotate_left(1);
+      plane.flip();
+    }
+    let wire = (0..4).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    ).collect();
+    Profile::new(plane, vec![Wire::new(wire)])
+  }
+
+  #[test]
+  fn loft_frustum() {
+    let profiles = [square_at(0.0, 1.0, false), square_at(1.0, 2.0, false)];
+    let solid = loft(&profiles, true).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 6);
+    assert!((solid.volume() - 7.0 / 3.0).abs() < 1.0e-6);
+    let solid = loft(&profiles, false).unwrap();
+    assert!((solid.volume() - 7.0 / 3.0).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn loft_ruled() {
+    let profiles = [square_at(0.0, 1.0, false), square_at(1.0, 2.0, true), square_at(2.0, 1.0, false)];
+    let solid = loft(&profiles, true).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert!((solid.volume() - 14.0 / 3.0).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn loft_smooth() {
+    let profiles = [square_at(0.0, 1.0, false), square_at(1.0, 1.0, true), square_at(2.0, 1.0, false), square_at(3.0, 1.0, false)];
+    let solid = loft(&profiles, false).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 6);
+    assert!((solid.volume() - 3.0).abs() < 1.0e-6);
+    // Bulging loft is larger than its ruled counterpart
+    let profiles = [square_at(0.0, 1.0, false), square_at(1.0, 2.0, false), square_at(2.0, 1.0, false)];
+    let solid = loft(&profiles, false).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!(solid.volume() > 14.0 / 3.0);
+  }
+
+  #[test]
+  fn loft_circles() {
+    let circle_at = |z: f64, radius: f64| {
+      let wire = vec![TrimmedCurve::new(Circle::new(Point3::new(0.0, 0.0, z), radius).into_enum())];
+      Profile::new(Plane::from_point(Point3::new(0.0, 0.0, z)), vec![Wire::new(wire)])
+    };
+    let profiles = [circle_at(0.0, 1.0), circle_at(1.0, 0.5)];
+    let solid = loft(&profiles, true).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 3);
+    let frustum = std::f64::consts::PI / 3.0 * (1.0 + 0.5 + 0.25);
+    assert!((solid.volume() - frustum).abs() < 1.0e-6);
+    // Sections stay exactly circular
+    let side = solid.faces_iter().find(|face| matches!(face.borrow().surface, SurfaceType::Spline(_)) ).unwrap().clone();
+    let side = side.borrow();
+    for i in 0..=8 {
+      let p = side.surface.as_surface().sample(i as f64 / 8.0, 0.5);
+      almost_eq!(Vec3::new(p.x, p.y, 0.0).magnitude(), 0.75);
+    }
+    // Radius follows the parabola interpolating all three profiles
+    let profiles = [circle_at(0.0, 1.0), circle_at(1.0, 0.5), circle_at(2.0, 1.0)];
+    let solid = loft(&profiles, false).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert!((solid.volume() - std::f64::consts::PI * 14.0 / 15.0).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn loft_arcs() {
+    // Half discs of growing radius, whose sides are lines and arcs
+    let half_disc_at = |z: f64, radius: f64| {
+      let center = Point3::new(0.0, 0.0, z);
+      let plane = Plane::from_point(center);
+      let wire = vec![
+        TrimmedCurve::new(Line::new(center - Vec3::unit_x() * radius, center + Vec3::unit_x() * radius).into_enum()),
+        TrimmedCurve::new(Arc::from_plane(plane.clone(), radius, 0.0, 0.5).into_enum()),
+      ];
+      Profile::new(plane, vec![Wire::new(wire)])
+    };
+    let profiles = [half_disc_at(0.0, 1.0), half_disc_at(1.0, 2.0)];
+    let solid = loft(&profiles, true).unwrap();
+    solid.shells[0].validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 4);
+    let frustum = std::f64::consts::PI / 6.0 * (1.0 + 2.0 + 4.0);
+    assert!((solid.volume() - frustum).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn loft_guide_rail() {
+    let profiles = [square_at(0.0, 1.0, false), square_at(2.0, 1.0, false)];
+    let rail = vec![TrimmedCurve::new(Spline::new(vec![
+      Point3::new(-0.5, -0.5, 0.0),
+      Point3::new(-1.5, -0.5, 1.0),
+      Point3::new(-0.5, -0.5, 2.0),
+    ]).into_enum())];
+    let solid = loft_guided(&profiles, &rail, false).unwrap();
+    solid.shells[0].validate().unwrap();
+    // Sections are shifted sideways, keeping their area
+    assert!((solid.volume() - 2.0).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn loft_mismatched_profiles() {
+    let profiles = [square_at(0.0, 1.0, false), circle_profile()];
+    assert!(loft(&profiles, true).is_err());
+    assert!(loft(&profiles[..1], true).is_err());
+  }
+
+  fn vertical_edge(solid: &Solid, x: f64, y: f64) -> Ref<Edge> {
+    solid.shells[0].edges.iter().find(|edge| {
+      let (start, end) = edge.borrow().left_half.borrow().make_curve().bounds;
+      start.x.almost(x) && start.y.almost(y) && end.x.almost(x) && end.y.almost(y)
+    }).unwrap().clone()
+  }
+
+  #[test]
+  fn fillet_convex() {
+    let mut solid = extrude(&square_profile(), 2.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    fillet(&mut solid, &[edge], 0.25).unwrap();
+    solid.validate().unwrap();
+    let shell = &solid.shells[0];
+    assert_eq!(shell.faces.len(), 7);
+    assert_eq!(shell.edges.len(), 15);
+    assert_eq!(shell.vertices.len(), 10);
+    assert!(shell.faces.iter().any(|face| matches!(face.borrow().surface, SurfaceType::Revolution(_)) ));
+    let removed = (1.0 - std::f64::consts::PI / 4.0) * 0.25 * 0.25 * 2.0;
+    assert!((solid.volume() - (2.0 - removed)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn fillet_concave() {
+    let points = [
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(2.0, 0.0, 0.0),
+      Point3::new(2.0, 1.0, 0.0),
+      Point3::new(1.0, 1.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(0.0, 2.0, 0.0),
+    ];
+    let wire = (0..6).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 6]).into_enum())
+    ).collect();
+    let mut solid = extrude(&Profile::new(Plane::new(), vec![Wire::new(wire)]), 1.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    fillet(&mut solid, &[edge], 0.5).unwrap();
+    solid.validate().unwrap();
+    let added = (1.0 - std::f64::consts::PI / 4.0) * 0.5 * 0.5;
+    assert!((solid.volume() - (3.0 + added)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn fillet_curved_edge() {
+    let wire = Wire::new(vec![
+      TrimmedCurve::new(Line::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::new(Point3::origin(), 1.0, 0.0, 0.25).into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(0.0, 1.0, 0.0), Point3::origin()).into_enum()),
+    ]);
+    let mut solid = extrude(&Profile::new(Plane::new(), vec![wire]), 1.0).unwrap();
+    let edge = solid.shells[0].edges.iter().find(|edge| {
+      let edge = edge.borrow();
+      matches!(edge.curve, CurveType::Arc(_)) && edge.left_half.borrow().origin.borrow().point.z.almost(1.0)
+    }).unwrap().clone();
+    fillet(&mut solid, &[edge], 0.2).unwrap();
+    solid.validate().unwrap();
+    let shell = &solid.shells[0];
+    assert_eq!(shell.faces.len(), 6);
+    let face = shell.faces.iter().find(|face| matches!(face.borrow().surface, SurfaceType::Spline(_)) ).unwrap();
+    // Blend approximates a torus around the rolling ball's path
+    let p = face.borrow().surface.as_surface().sample(0.5, 0.5);
+    let radial = (p.x * p.x + p.y * p.y).sqrt() - 0.8;
+    assert!(((radial * radial + (p.z - 0.8).powi(2)).sqrt() - 0.2).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn fillet_invalid() {
+    let mut solid = extrude(&square_profile(), 1.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    assert!(fillet(&mut solid, &[edge.clone()], 0.0).is_err());
+    assert!(fillet(&mut solid, &[edge.clone()], 1.5).is_err());
+    let other = solid.shells[0].edges.iter().find(|other|
+      !Rc::ptr_eq(other, &edge) && Rc::ptr_eq(&other.borrow().left_half.borrow().origin, &edge.borrow().left_half.borrow().origin)
+    ).unwrap().clone();
+    assert!(fillet(&mut solid, &[edge, other], 0.25).is_err());
+    // Solid stays untouched
+    assert_eq!(solid.shells[0].faces.len(), 6);
+    assert!((solid.volume() - 1.0).abs() < 1.0e-6);
+  }
+
+  // Lines along x, joined by a half turn at x = 2 and either another half turn or a line at x = 0
+  fn rounded_profile(is_stadium: bool) -> Profile {
+    let right = Plane { origin: Point3::new(2.0, 1.0, 0.0), u: -Vec3::unit_y(), v: Vec3::unit_x() };
+    let left = Plane { origin: Point3::new(0.0, 1.0, 0.0), u: Vec3::unit_y(), v: -Vec3::unit_x() };
+    let closing = if is_stadium {
+      Arc::from_plane(left, 1.0, 0.0, 0.5).into_enum()
+    } else {
+      Line::new(Point3::new(0.0, 2.0, 0.0), Point3::origin()).into_enum()
+    };
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::origin(), Point3::new(2.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::from_plane(right, 1.0, 0.0, 0.5).into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(2.0, 2.0, 0.0), Point3::new(0.0, 2.0, 0.0)).into_enum()),
+      TrimmedCurve::new(closing),
+    ];
+    Profile::new(Plane::new(), vec![Wire::new(wire)])
+  }
+
+  // Edges around the top face, except for the straight one at x = 0
+  fn top_chain(solid: &Solid) -> Vec<Ref<Edge>> {
+    solid.shells[0].edges.iter().filter(|edge| {
+      let edge = edge.borrow();
+      let (start, end) = edge.left_half.borrow().make_curve().bounds;
+      let is_closing_line = matches!(edge.curve, CurveType::Line(_)) && start.x.almost(0.0) && end.x.almost(0.0);
+      start.z.almost(1.0) && end.z.almost(1.0) && !is_closing_line
+    }).cloned().collect()
+  }
+
+  // Volume removed by blending the top edges of a rounded profile, with the given cross section and its distance to the edge
+  fn removed_volume(area: f64, centroid: f64, turns: f64) -> f64 {
+    area * (4.0 + std::f64::consts::PI * 2.0 * turns * (1.0 - centroid))
+  }
+
+  #[test]
+  fn fillet_tangent_chain() {
+    let mut solid = extrude(&rounded_profile(false), 1.0).unwrap();
+    let edges = top_chain(&solid);
+    assert_eq!(edges.len(), 3);
+    fillet(&mut solid, &edges, 0.2).unwrap();
+    solid.validate().unwrap();
+    assert_watertight(&solid);
+    assert_eq!(solid.shells[0].faces.len(), 9);
+    let area = 0.2 * 0.2 * (1.0 - std::f64::consts::PI / 4.0);
+    let centroid = 0.2 - 0.2 / (6.0 * (1.0 - std::f64::consts::PI / 4.0));
+    let volume = 4.0 + std::f64::consts::PI / 2.0 - removed_volume(area, centroid, 0.5);
+    assert!((solid.volume() - volume).abs() < 1.0e-4);
+  }
+
+  #[test]
+  fn chamfer_closed_chain() {
+    let mut solid = extrude(&rounded_profile(true), 1.0).unwrap();
+    let edges = top_chain(&solid);
+    assert_eq!(edges.len(), 4);
+    chamfer(&mut solid, &edges, ChamferMode::Distance(0.2)).unwrap();
+    solid.validate().unwrap();
+    assert_watertight(&solid);
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    let volume = 4.0 + std::f64::consts::PI - removed_volume(0.2 * 0.2 / 2.0, 0.2 / 3.0, 1.0);
+    assert!((solid.volume() - volume).abs() < 1.0e-4);
+  }
+
+  #[test]
+  fn chamfer_convex() {
+    let mut solid = extrude(&square_profile(), 2.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    chamfer(&mut solid, &[edge], ChamferMode::Distance(0.25)).unwrap();
+    solid.validate().unwrap();
+    let shell = &solid.shells[0];
+    assert_eq!(shell.faces.len(), 7);
+    assert_eq!(shell.edges.len(), 15);
+    assert_eq!(shell.vertices.len(), 10);
+    assert!(shell.faces.iter().all(|face| matches!(face.borrow().surface, SurfaceType::Planar(_)) ));
+    assert!((solid.volume() - (2.0 - 0.25 * 0.25 / 2.0 * 2.0)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn chamfer_modes() {
+    let mut solid = extrude(&square_profile(), 1.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    chamfer(&mut solid, &[edge], ChamferMode::TwoDistances(0.2, 0.4)).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - (1.0 - 0.2 * 0.4 / 2.0)).abs() < 1.0e-6);
+    // Angle of 30° to the left face sets back the right face by tan(30°) on a right-angled edge
+    let edge = vertical_edge(&solid, 0.0, 0.0);
+    chamfer(&mut solid, &[edge], ChamferMode::DistanceAngle(0.3, Deg(30.0))).unwrap();
+    solid.validate().unwrap();
+    let removed = 0.3 * 0.3 * (30.0f64).to_radians().tan() / 2.0;
+    assert!((solid.volume() - (1.0 - 0.2 * 0.4 / 2.0 - removed)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn chamfer_concave() {
+    let points = [
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(2.0, 0.0, 0.0),
+      Point3::new(2.0, 1.0, 0.0),
+      Point3::new(1.0, 1.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(0.0, 2.0, 0.0),
+    ];
+    let wire = (0..6).map(|i|
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 6]).into_enum())
+    ).collect();
+    let mut solid = extrude(&Profile::new(Plane::new(), vec![Wire::new(wire)]), 1.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    chamfer(&mut solid, &[edge], ChamferMode::Distance(0.5)).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - (3.0 + 0.5 * 0.5 / 2.0)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn chamfer_curved_edge() {
+    let wire = Wire::new(vec![
+      TrimmedCurve::new(Line::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::new(Point3::origin(), 1.0, 0.0, 0.25).into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(0.0, 1.0, 0.0), Point3::origin()).into_enum()),
+    ]);
+    let mut solid = extrude(&Profile::new(Plane::new(), vec![wire]), 1.0).unwrap();
+    let edge = solid.shells[0].edges.iter().find(|edge| {
+      let edge = edge.borrow();
+      matches!(edge.curve, CurveType::Arc(_)) && edge.left_half.borrow().origin.borrow().point.z.almost(1.0)
+    }).unwrap().clone();
+    chamfer(&mut solid, &[edge], ChamferMode::Distance(0.2)).unwrap();
+    solid.validate().unwrap();
+    let shell = &solid.shells[0];
+    assert_eq!(shell.faces.len(), 6);
+    let face = shell.faces.iter().find(|face| matches!(face.borrow().surface, SurfaceType::Spline(_)) ).unwrap();
+    // Chamfer approximates a cone between the setback circles
+    let p = face.borrow().surface.as_surface().sample(0.5, 0.5);
+    let radial = (p.x * p.x + p.y * p.y).sqrt();
+    assert!((radial + p.z - 1.8).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn chamfer_invalid() {
+    let mut solid = extrude(&square_profile(), 1.0).unwrap();
+    let edge = vertical_edge(&solid, 1.0, 1.0);
+    assert!(chamfer(&mut solid, &[edge.clone()], ChamferMode::Distance(-0.1)).is_err());
+    assert!(chamfer(&mut solid, &[edge.clone()], ChamferMode::TwoDistances(0.2, 1.5)).is_err());
+    assert!(chamfer(&mut solid, &[edge.clone()], ChamferMode::DistanceAngle(0.2, Deg(95.0))).is_err());
+    assert_eq!(solid.shells[0].faces.len(), 6);
+    assert!((solid.volume() - 1.0).abs() < 1.0e-6);
+  }
+
+  fn top_face(solid: &Solid) -> Ref<Face> {
+    solid.faces_iter().find(|face| match &face.borrow().surface {
+      SurfaceType::Planar(surface) => surface.plane.normal().z.almost(1.0),
+      _ => false,
+    }).unwrap().clone()
+  }
+
+  #[test]
+  fn shell_closed() {
+    let mut solid = make_cube(2.0, 2.0, 2.0).unwrap();
+    shell(&mut solid, &[], 0.25).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells.len(), 2);
+    assert_eq!(solid.shells[1].faces.len(), 6);
+    assert!((solid.volume() - (8.0 - 1.5f64.powi(3))).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn shell_open() {
+    let mut solid = make_cube(2.0, 2.0, 2.0).unwrap();
+    let face = top_face(&solid);
+    shell(&mut solid, &[face.clone()], 0.25).unwrap();
+    solid.validate().unwrap();
+    let shell = &solid.shells[0];
+    assert_eq!(solid.shells.len(), 1);
+    assert_eq!(shell.faces.len(), 11);
+    assert_eq!(shell.edges.len(), 24);
+    assert_eq!(shell.vertices.len(), 16);
+    assert_eq!(face.borrow().rings.len(), 2);
+    assert!((solid.volume() - (8.0 - 1.5 * 1.5 * 1.75)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn shell_open_corner() {
+    let mut solid = make_cube(2.0, 2.0, 2.0).unwrap();
+    let face = top_face(&solid);
+    let side = solid.faces_iter().find(|other|
+      other.borrow().outer_ring.borrow().iter().any(|he| Rc::ptr_eq(&he.borrow().mate().borrow().face(), &face) )
+    ).unwrap().clone();
+    let ids = [face.borrow().id, side.borrow().id];
+    shell(&mut solid, &[face.clone(), side], 0.25).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert_eq!(face.borrow().rings.len(), 1);
+    assert!(ids.iter().all(|id| solid.find_face(*id).is_some() ));
+    assert!((solid.volume() - (8.0 - 1.5 * 1.75 * 1.75)).abs() < 1.0e-6);
+    // Opposite sides split the top into two strips
+    let mut solid = make_cube(2.0, 2.0, 2.0).unwrap();
+    let face = top_face(&solid);
+    let is_adjacent = |other: &Ref<Face>, face: &Ref<Face>|
+      other.borrow().outer_ring.borrow().iter().any(|he| Rc::ptr_eq(&he.borrow().mate().borrow().face(), face) );
+    let sides: Vec<Ref<Face>> = solid.faces_iter().filter(|other| is_adjacent(other, &face) ).cloned().collect();
+    let opposite = sides[1..].iter().find(|other| !is_adjacent(other, &sides[0]) ).unwrap().clone();
+    shell(&mut solid, &[face, sides[0].clone(), opposite], 0.25).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert!((solid.volume() - (8.0 - 1.5 * 2.0 * 1.75)).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn shell_cylinder() {
+    let mut solid = make_cylinder(1.0, 1.0).unwrap();
+    let face = top_face(&solid);
+    shell(&mut solid, &[face], 0.2).unwrap();
+    solid.validate().unwrap();
+    let cavity = std::f64::consts::PI * 0.8 * 0.8 * 0.8;
+    assert!((solid.volume() - (std::f64::consts::PI - cavity)).abs() < 1.0e-3);
+  }
+
+  #[test]
+  fn shell_invalid() {
+    let mut solid = make_cube(1.0, 1.0, 1.0).unwrap();
+    let face = top_face(&solid);
+    assert!(shell(&mut solid, &[face.clone()], 0.0).is_err());
+    let side = solid.faces_iter().find(|other|
+      other.borrow().outer_ring.borrow().iter().any(|he| Rc::ptr_eq(&he.borrow().mate().borrow().face(), &face) )
+    ).unwrap().clone();
+    let corner = solid.faces_iter().find(|other| {
+      let ring = other.borrow().outer_ring.clone();
+      let is_adjacent = |face: &Ref<Face>| ring.borrow().iter().any(|he| Rc::ptr_eq(&he.borrow().mate().borrow().face(), face) );
+      is_adjacent(&face) && is_adjacent(&side)
+    }).unwrap().clone();
+    assert!(shell(&mut solid, &[face, side, corner], 0.1).is_err());
+    let all_faces: Vec<Ref<Face>> = solid.faces_iter().cloned().collect();
+    assert!(shell(&mut solid, &all_faces, 0.1).is_err());
+    assert_eq!(solid.shells.len(), 1);
+    assert_eq!(solid.shells[0].faces.len(), 6);
+  }
+
+  #[test]
+  fn extrude_holes() {
+    let outer = (0..4).map(|i| {
+      let points = [
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(4.0, 0.0, 0.0),
+        Point3::new(4.0, 2.0, 0.0),
+        Point3::new(0.0, 2.0, 0.0),
+      ];
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    }).collect();
+    // Clockwise square hole
+    let square = (0..4).map(|i| {
+      let points = [
+        Point3::new(0.5, 0.5, 0.0),
+        Point3::new(0.5, 1.5, 0.0),
+        Point3::new(1.5, 1.5, 0.0),
+        Point3::new(1.5, 0.5, 0.0),
+      ];
+      TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum())
+    }).collect();
+    let circle = vec![TrimmedCurve::new(Circle::new(Point3::new(3.0, 1.0, 0.0), 0.5).into_enum())];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(outer), Wire::new(square), Wire::new(circle)]);
+    for distance in [1.0, -1.0] {
+      let solid = extrude(&profile, distance).unwrap();
+      solid.validate().unwrap();
+      let shell = &solid.shells[0];
+      assert_eq!(shell.genus(), 2);
+      assert_eq!(shell.faces.len(), 11);
+      let caps: Vec<&Ref<Face>> = shell.faces.iter().filter(|face| face.borrow().rings.len() == 3 ).collect();
+      assert_eq!(caps.len(), 2);
+      let volume = 8.0 - 1.0 - std::f64::consts::PI * 0.25;
+      assert!((solid.volume() - volume).abs() < 1.0e-6);
+      assert!(!solid.contains_point(Point3::new(1.0, 1.0, distance / 2.0)));
+      assert!(!solid.contains_point(Point3::new(3.0, 1.0, distance / 2.0)));
+      assert!(solid.contains_point(Point3::new(2.2, 1.0, distance / 2.0)));
+    }
+  }
+
+  #[test]
+  fn revolve_holes() {
+    let axis = Axis::new(Point3::origin(), Vec3::unit_y());
+    let washer = Profile::new(Plane::new(), vec![
+      Wire::new(vec![TrimmedCurve::new(Circle::new(Point3::new(2.0, 0.0, 0.0), 0.5).into_enum())]),
+      Wire::new(vec![TrimmedCurve::new(Circle::new(Point3::new(2.0, 0.0, 0.0), 0.25).into_enum())]),
+    ]);
+    let torus_volume = |radius: f64| 2.0 * std::f64::consts::PI.powi(2) * 2.0 * radius.powi(2);
+    for angle in [90.0, -90.0] {
+      let solid = revolve(&washer, axis.clone(), Deg(angle)).unwrap();
+      solid.validate().unwrap();
+      assert_eq!(solid.shells.len(), 1);
+      assert_eq!(solid.shells[0].genus(), 1);
+      assert_eq!(solid.shells[0].faces.len(), 4);
+      assert!((solid.volume() - (torus_volume(0.5) - torus_volume(0.25)) / 4.0).abs() < 1.0e-6);
+    }
+    let solid = revolve(&washer, axis, Deg(360.0)).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells.len(), 2);
+    assert_eq!(solid.shells[0].faces.len(), 3);
+    assert_eq!(solid.shells[1].faces.len(), 3);
+    // The inner ring sweeps out an inverted cavity shell
+    assert!((solid.shells[0].volume() - torus_volume(0.5)).abs() < 1.0e-6);
+    assert!((solid.shells[1].volume() + torus_volume(0.25)).abs() < 1.0e-6);
+    assert!((solid.volume() - (torus_volume(0.5) - torus_volume(0.25))).abs() < 1.0e-6);
+    assert!(!solid.contains_point(Point3::new(0.0, 0.0, 2.0)));
+    assert!(solid.contains_point(Point3::new(0.0, 0.0, 2.4)));
+  }
+
+  #[test]
+  fn revolve_square_hole() {
+    let square = |size: f64| -> Vec<TrimmedCurve> {
+      let points = [
+        Point3::new(2.0 - size, -size, 0.0),
+        Point3::new(2.0 + size, -size, 0.0),
+        Point3::new(2.0 + size, size, 0.0),
+        Point3::new(2.0 - size, size, 0.0),
+      ];
+      (0..4).map(|i| TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum()) ).collect()
+    };
+    let mut hole = Wire::new(square(0.25));
+    hole.reverse();
+    let profile = Profile::new(Plane::new(), vec![Wire::new(square(0.5)), hole]);
+    let axis = Axis::new(Point3::origin(), Vec3::unit_y());
+    let solid = revolve(&profile, axis.clone(), Deg(90.0)).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert!((solid.volume() - std::f64::consts::PI * 0.75).abs() < 1.0e-6);
+    let solid = revolve(&profile, axis, Deg(360.0)).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells.len(), 2);
+    assert_eq!(solid.shells[1].faces.len(), 6);
+    assert!((solid.shells[0].volume() - std::f64::consts::PI * 4.0).abs() < 1.0e-6);
+    assert!((solid.shells[1].volume() + std::f64::consts::PI).abs() < 1.0e-6);
+    assert!((solid.volume() - std::f64::consts::PI * 3.0).abs() < 1.0e-6);
+    assert!(!solid.contains_point(Point3::new(0.0, 0.0, 2.0)));
+    assert!(solid.contains_point(Point3::new(0.0, 0.0, 2.4)));
+  }
+
+  #[test]
+  fn extrude_offsets() {
+    let profile = square_profile();
+    let solid = extrude(&profile, 500.0).unwrap();
+    assert!((solid.volume() - 500.0).abs() < 1.0e-6);
+    assert!(extrude(&profile, 0.0).is_err());
+    let solid = extrude_between(&profile, -1.0, 2.0).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - 3.0).abs() < 1.0e-6);
+    assert!(solid.contains_point(Point3::new(0.5, 0.5, -0.5)));
+    assert!(solid.contains_point(Point3::new(0.5, 0.5, 1.5)));
+    assert!(!solid.contains_point(Point3::new(0.5, 0.5, 2.5)));
+    let solid = extrude_between(&profile, 3.0, 1.0).unwrap();
+    assert!((solid.volume() - 2.0).abs() < 1.0e-6);
+    assert!(solid.contains_point(Point3::new(0.5, 0.5, 2.0)));
+  }
+
+  #[test]
+  fn extrusion_distances() {
+    let mut base = square_profile();
+    base.transform(&(Matrix4::from_translation(Vec3::new(-1.0, -1.0, 0.0)) * Matrix4::from_scale(3.0)));
+    let body = extrude_between(&base, 4.0, 5.0).unwrap().into_compound();
+    let profile = square_profile();
+    let bottom = body.faces_iter().find(|face| match &face.borrow().surface {
+      SurfaceType::Planar(surf) => surf.plane.origin.z.almost(4.0),
+      _ => false,
+    }).unwrap().clone();
+    assert!((distance_to_face(&profile, &bottom.borrow()).unwrap() - 4.0).abs() < 1.0e-9);
+    assert!((distance_to_next(&profile, &body, true).unwrap() - 4.0).abs() < 1.0e-9);
+    assert!(distance_to_next(&profile, &body, false).is_err());
+    assert!((distance_through_all(&profile, &body, true).unwrap() - 5.05).abs() < 1.0e-9);
+    assert!(distance_through_all(&profile, &body, false).is_err());
+    // Profiles behind the body extrude in the other direction
+    let mut profile = profile;
+    let offset = Matrix4::from_translation(Vec3::new(0.0, 0.0, 7.0));
+    profile.transform(&offset);
+    profile.plane.transform(&offset);
+    assert!((distance_to_face(&profile, &bottom.borrow()).unwrap() + 3.0).abs() < 1.0e-9);
+    assert!((distance_to_next(&profile, &body, false).unwrap() + 2.0).abs() < 1.0e-9);
+    // Bodies in front of the interior of the profile are found as well
+    let mut base = square_profile();
+    base.transform(&(Matrix4::from_translation(Vec3::new(0.4, 0.4, 0.0)) * Matrix4::from_scale(0.2)));
+    let body = extrude_between(&base, 2.0, 3.0).unwrap().into_compound();
+    assert!((distance_to_next(&square_profile(), &body, true).unwrap() - 2.0).abs() < 1.0e-9);
+  }
+
+  // Slab with its bottom face running through (0.5, 0.5, 4) and rising along x
+  fn inclined_face() -> (Solid, Ref<Face>) {
+    let normal = Vec3::new(-0.2, 0.0, 1.0).normalize();
+    let plane = Plane::from_normal(Point3::new(0.5, 0.5, 4.0), normal);
+    let corners = [(-3.0, -3.0), (3.0, -3.0), (3.0, 3.0), (-3.0, 3.0)].map(|(u, v)| plane.sample(u, v) );
+    let wire = (0..4).map(|i|
+      TrimmedCurve::new(Line::new(corners[i], corners[(i + 1) % 4]).into_enum())
+    ).collect();
+    let body = extrude(&Profile::new(plane, vec![Wire::new(wire)]), 1.0).unwrap();
+    let face = body.faces_iter().find(|face| match &face.borrow().surface {
+      SurfaceType::Planar(surface) => surface.plane.normal().dot(normal).almost(-1.0),
+      _ => false,
+    }).unwrap().clone();
+    (body, face)
+  }
+
+  #[test]
+  fn extrude_to_inclined_face() {
+    let (_body, face) = inclined_face();
+    let solid = extrude_to_face(&square_profile(), &face.borrow(), Deg(0.0)).unwrap();
+    solid.validate().unwrap();
+    assert!(solid.shells[0].vertices.iter().any(|vertex| vertex.borrow().point.almost(Point3::new(1.0, 1.0, 4.1)) ));
+    assert!((solid.volume() - 4.0).abs() < 1.0e-6);
+    assert!(solid.contains_point(Point3::new(0.9, 0.5, 4.05)));
+    assert!(!solid.contains_point(Point3::new(0.1, 0.5, 3.95)));
+    let solid = extrude_to_face(&circle_profile(), &face.borrow(), Deg(0.0)).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - std::f64::consts::PI).abs() < 1.0e-3);
+    let solid = extrude_to_face(&square_profile(), &face.borrow(), Deg(5.0)).unwrap();
+    solid.validate().unwrap();
+    assert!(solid.shells[0].vertices.iter().all(|vertex| vertex.borrow().point.z < 4.1 ));
+  }
+
+  #[test]
+  fn extrude_taper() {
+    let taper = Deg::from(Rad(0.1f64.atan()));
+    let frustum = |a1: f64, a2: f64| (a1 + a2 + (a1 * a2).sqrt()) / 3.0;
+    for distance in [1.0, -1.0] {
+      let solid = extrude_tapered(&square_profile(), distance, taper).unwrap();
+      solid.validate().unwrap();
+      assert_eq!(solid.shells[0].faces.len(), 6);
+      assert!((solid.volume() - frustum(1.0, 0.64)).abs() < 1.0e-6);
+      assert!(solid.contains_point(Point3::new(0.5, 0.5, distance * 0.9)));
+      assert!(!solid.contains_point(Point3::new(0.05, 0.05, distance * 0.9)));
+    }
+    let solid = extrude_tapered(&square_profile(), 1.0, -taper).unwrap();
+    assert!((solid.volume() - frustum(1.0, 1.44)).abs() < 1.0e-6);
+    let solid = extrude_tapered(&circle_profile(), 1.0, taper).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - frustum(0.25, 0.16) * std::f64::consts::PI).abs() < 1.0e-6);
+    assert!(extrude_tapered(&circle_profile(), 10.0, taper).is_err());
+    assert!(extrude_tapered(&square_profile(), 1.0, Deg(90.0)).is_err());
+    // Insets of the sides would collapse into a point or turn around
+    let mut square = square_profile();
+    square.transform(&Matrix4::from_scale(2.0));
+    assert_eq!(extrude_tapered(&square, 1.0, Deg(45.0)).unwrap_err(), "Taper angle is too steep for the profile");
+    assert!(extrude_tapered(&square, 2.0, Deg(45.0)).is_err());
+    assert!(extrude_tapered_between(&square, -0.5, 1.0, Deg(45.0)).is_err());
+  }
+
+  #[test]
+  fn extrude_taper_two_sided() {
+    let taper = Deg::from(Rad(0.1f64.atan()));
+    let frustum = |a1: f64, a2: f64| (a1 + a2 + (a1 * a2).sqrt()) / 3.0;
+    // Both sides shrink away from the profile
+    let solid = extrude_tapered_between(&square_profile(), -1.0, 2.0, taper).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 10);
+    assert!((solid.volume() - frustum(1.0, 0.64) - 2.0 * frustum(1.0, 0.36)).abs() < 1.0e-6);
+    assert!(solid.contains_point(Point3::new(0.5, 0.5, -0.9)));
+    assert!(solid.contains_point(Point3::new(0.5, 0.5, 1.9)));
+    assert!(!solid.contains_point(Point3::new(0.05, 0.05, -0.9)));
+    assert!(!solid.contains_point(Point3::new(0.15, 0.15, 1.9)));
+    let solid = extrude_tapered_between(&circle_profile(), 1.0, -1.0, taper).unwrap();
+    solid.validate().unwrap();
+    assert!((solid.volume() - 2.0 * frustum(0.25, 0.16) * std::f64::consts::PI).abs() < 1.0e-6);
+    assert!(extrude_tapered_between(&square_profile(), 1.0, 2.0, taper).is_err());
+  }
+
+  #[test]
+  fn extrude_taper_holes() {
+    let mut outer = square_profile();
+    outer.transform(&Matrix4::from_scale(3.0));
+    let hole = vec![TrimmedCurve::new(Circle::new(Point3::new(1.5, 1.5, 0.0), 0.5).into_enum())];
+    let profile = Profile::new(Plane::new(), vec![outer.rings[0].clone(), Wire::new(hole)]);
+    let taper = Deg::from(Rad(0.1f64.atan()));
+    let solid = extrude_tapered(&profile, 1.0, taper).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].genus(), 1);
+    // The hole widens as the outline shrinks
+    let frustum = |a1: f64, a2: f64| (a1 + a2 + (a1 * a2).sqrt()) / 3.0;
+    let volume = frustum(9.0, 7.84) - frustum(0.25, 0.36) * std::f64::consts::PI;
+    assert!((solid.volume() - volume).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn extrude_taper_arcs() {
+    // Half disc
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::from_plane(Plane::new(), 1.0, 0.0, 0.5).into_enum()),
+    ];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    let solid = extrude_tapered(&profile, 1.0, Deg::from(Rad(0.1f64.atan()))).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 4);
+    // Top edges meet where the inset chord crosses the inset arc
+    let corner = Point3::new((0.81f64 - 0.01).sqrt(), 0.1, 1.0);
+    assert!(solid.shells[0].vertices.iter().any(|vertex| vertex.borrow().point.almost(corner) ));
+    // Slot, whose tangent arcs keep spanning half a turn while tapering
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.0, -0.5, 0.0), Point3::new(2.0, -0.5, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::new(Point3::new(2.0, 0.0, 0.0), 0.5, -0.25, 0.25).into_enum()),
+      TrimmedCurve::new(Line::new(Point3::new(2.0, 0.5, 0.0), Point3::new(0.0, 0.5, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::new(Point3::new(0.0, 0.0, 0.0), 0.5, 0.25, 0.75).into_enum()),
+    ];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    let solid = extrude_tapered(&profile, 1.0, Deg::from(Rad(0.1f64.atan()))).unwrap();
+    solid.validate().unwrap();
+    let volume = 1.8 + (0.25 - 0.05 + 0.01 / 3.0) * std::f64::consts::PI;
+    assert!((solid.volume() - volume).abs() < 1.0e-4);
+  }
+
+  #[test]
+  fn extrude_ellipse() {
+    let wire = vec![TrimmedCurve::new(Ellipse::new(Point3::new(1.0, 0.5, 0.0), (1.0, 0.5)).into_enum())];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    for distance in [1.0, -1.0] {
+      let solid = extrude(&profile, distance).unwrap();
+      solid.validate().unwrap();
+      assert_eq!(solid.shells[0].faces.len(), 3);
+      assert!((solid.volume() - 0.5 * std::f64::consts::PI).abs() < 1.0e-6);
+      assert!(solid.contains_point(Point3::new(1.8, 0.5, distance * 0.5)));
+      assert!(!solid.contains_point(Point3::new(1.8, 0.9, distance * 0.5)));
+    }
+    // Half ellipse
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(-2.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(EllipticalArc::new(Point3::origin(), (2.0, 1.0), 0.0, 0.5).into_enum()),
+    ];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    let solid = extrude(&profile, 2.0).unwrap();
+    solid.validate().unwrap();
+    assert_eq!(solid.shells[0].faces.len(), 4);
+    assert!((solid.volume() - 2.0 * std::f64::consts::PI).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn extrude_spline() {
+    // Arch closed by a line, whose area is 1.8
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Spline::new(vec![
+        Point3::new(2.0, 0.0, 0.0),
+        Point3::new(2.0, 1.5, 0.0),
+        Point3::new(0.0, 1.5, 0.0),
+        Point3::new(0.0, 0.0, 0.0),
+      ]).into_enum()),
+    ];
+    let mut reversed = wire.clone();
+    reversed.reverse();
+    for tcurve in &mut reversed {
+      tcurve.flip();
+    }
+    // Side faces face outwards, regardless of the direction of extrusion and of the wire
+    for wire in [wire, reversed] {
+      let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+      for distance in [1.0, -1.0] {
+        let solid = extrude(&profile, distance).unwrap();
+        solid.validate().unwrap();
+        assert!((solid.volume() - 1.8).abs() < 1.0e-6);
+        assert!(solid.contains_point(Point3::new(1.0, 1.0, distance * 0.5)));
+        assert!(!solid.contains_point(Point3::new(1.0, 1.3, distance * 0.5)));
+      }
+    }
+  }
+
+  // Edges of drafted solids still lie on the surfaces of both adjacent faces
+  fn assert_watertight(solid: &Solid) {
+    for edge in solid.edges_iter() {
+      let edge = edge.borrow();
+      let tcurve = edge.left_half.borrow().make_curve();
+      for p in [tcurve.bounds.0, tcurve.sample(0.5), tcurve.bounds.1] {
+        for face in [edge.left_face(), edge.right_face()] {
+          let (q, _) = project_to_face(&face.borrow(), p).unwrap();
+          assert!(p.distance(q) < 1.0e-5);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn draft_planar() {
+    let cube = make_cube(1.5, 1.5, 1.5).unwrap();
+    let face = cube.faces_iter().find(|face| face.borrow().surface.as_surface().normal_at(0.0, 0.0).z.almost(0.0) ).unwrap().clone();
+    draft(&[face], &Plane::new(), Deg(10.0)).unwrap();
+    cube.validate().unwrap();
+    assert_watertight(&cube);
+    let wedge = 0.5 * 1.5 * 1.5 * 1.5 * Rad::from(Deg(10.0f64)).0.tan();
+    assert!((cube.volume() - (1.5f64.powi(3) - wedge)).abs() < 1.0e-6);
+    assert!(draft(&cube.shells[0].faces, &Plane::new(), Deg(90.0)).is_err());
+  }
+
+  #[test]
+  fn draft_cylinder() {
+    let cylinder = make_cylinder(1.0, 2.0).unwrap();
+    let side = cylinder.faces_iter().find(|face| matches!(face.borrow().surface, SurfaceType::Revolution(_)) ).unwrap().clone();
+    let tilted = Plane { origin: Point3::origin(), u: Vec3::unit_y(), v: Vec3::unit_z() };
+    assert!(draft(std::slice::from_ref(&side), &tilted, Deg(5.0)).is_err());
+    draft(&[side], &Plane::new(), Deg(5.0)).unwrap();
+    cylinder.validate().unwrap();
+    assert_watertight(&cylinder);
+    // Frustum with the original radius at the bottom
+    let r = 1.0 - 2.0 * Rad::from(Deg(5.0f64)).0.tan();
+    let volume = std::f64::consts::PI / 3.0 * 2.0 * (1.0 + r + r * r);
+    assert!((cylinder.volume() - volume).abs() < 1.0e-4);
+    // Negative angles widen the top instead
+    let cylinder = make_cylinder(1.0, 2.0).unwrap();
+    let side = cylinder.faces_iter().find(|face| matches!(face.borrow().surface, SurfaceType::Revolution(_)) ).unwrap().clone();
+    draft(&[side], &Plane::new(), Deg(-5.0)).unwrap();
+    assert!(cylinder.volume() > 2.0 * std::f64::consts::PI);
+  }
+
+  #[test]
+  fn draft_next_to_cylinder() {
+    // Half disc, whose flat side meets the curved one along vertical edges
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::from_plane(Plane::new(), 1.0, 0.0, 0.5).into_enum()),
+    ];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    let solid = extrude(&profile, 1.0).unwrap();
+    let side = solid.faces_iter().find(|face| {
+      let surface = &face.borrow().surface;
+      matches!(surface, SurfaceType::Planar(_)) && surface.as_surface().normal_at(0.0, 0.0).z.almost(0.0)
+    }).unwrap().clone();
+    draft(&[side], &Plane::new(), Deg(5.0)).unwrap();
+    solid.validate().unwrap();
+    assert_watertight(&solid);
+    // The flat side cuts a wedge off the half cylinder, whose cross sections are circle segments
+    let slope = Rad::from(Deg(5.0f64)).0.tan();
+    let antiderivative = |s: f64| s * s.asin() + (1.0 - s * s).sqrt() - (1.0 - s * s).powf(1.5) / 3.0;
+    let wedge = (antiderivative(slope) - antiderivative(0.0)) / slope;
+    assert!((solid.volume() - (std::f64::consts::FRAC_PI_2 - wedge)).abs() < 1.0e-4);
+  }
+
+  #[test]
+  fn draft_spline() {
+    let wire = vec![
+      TrimmedCurve::new(Line::new(Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Spline::new(vec![
+        Point3::new(2.0, 0.0, 0.0),
+        Point3::new(2.0, 1.5, 0.0),
+        Point3::new(0.0, 1.5, 0.0),
+        Point3::new(0.0, 0.0, 0.0),
+      ]).into_enum()),
+    ];
+    let profile = Profile::new(Plane::new(), vec![Wire::new(wire)]);
+    let solid = extrude(&profile, 1.0).unwrap();
+    let original = solid.volume();
+    let faces: Vec<Ref<Face>> = solid.faces_iter().filter(|face| !matches!(face.borrow().surface, SurfaceType::Planar(_)) ).cloned().collect();
+    assert_eq!(faces.len(), 1);
+    draft(&faces, &Plane::new(), Deg(5.0)).unwrap();
+    solid.validate().unwrap();
+    assert_watertight(&solid);
+    assert!(matches!(&faces[0].borrow().surface, SurfaceType::Spline(surface) if surface.degree.1 == 1));
+    assert!(solid.volume() < original);
+  }
+
 }
@@ -55,6 +55,12 @@ impl Repairable for Edge {
   fn repair(&mut self) -> Result<(), String> {
     let intersections = self.left_face().borrow().surface.intersect(&self.right_face().borrow().surface);
     if intersections.len() == 0 { return Err("Adjacent faces don't intersect".into()) }
+    // Faces may meet along several curves, of which the one passing closest to the current edge is kept
+    let bottom_vertex = self.left_half.borrow().origin.clone();
+    let top_vertex = self.right_half.borrow().origin.clone();
+    let bounds = (bottom_vertex.borrow().point, top_vertex.borrow().point);
+    let midpoint = TrimmedCurve::from_bounds(self.curve.clone(), bounds, self.curve.clone()).sample(0.5);
+    let mut curves = vec![];
     for intersection in intersections {
       match intersection {
         SurfaceIntersectionType::Contained
@@ -63,24 +69,29 @@ impl Repairable for Edge {
         SurfaceIntersectionType::Touch(curve)
         | SurfaceIntersectionType::Cross(curve)
         | SurfaceIntersectionType::Extended(curve)
-        => {
-          let top_sect = curve.intersect_surface(&self.top_face().borrow().surface);
-          let top_sect = top_sect.first().and_then(|isect| isect.get_point(true) ); //XXX Select correct intersection
-          let bottom_sect = curve.intersect_surface(&self.bottom_face().borrow().surface);
-          let bottom_sect = bottom_sect.first().and_then(|isect| isect.get_point(true) );
-          if let (Some(top_bound), Some(bottom_bound)) = (top_sect, bottom_sect) {
-            self.left_half.borrow().origin.borrow_mut().point = bottom_bound;
-            self.right_half.borrow().origin.borrow_mut().point = top_bound;
-            let id = self.curve.id();
-            self.curve = curve;
-            self.curve.set_id(id);
-          } else {
-            return Err("Edge could not be trimmed by surrounding faces".into())
-          }
-        },
+        => curves.push(curve),
       }
     }
-    Ok(())
+    let distance = |curve: &CurveType| curve.as_curve().tesselate().iter().map(|p| p.distance(midpoint) ).fold(f64::MAX, f64::min);
+    let curve = curves.into_iter()
+      .min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap() )
+      .unwrap();
+    // Bounds are the intersections with the surrounding faces closest to the current vertices
+    let bound = |face: Ref<Face>, vertex: Point3| curve.intersect_surface(&face.borrow().surface).iter()
+      .filter_map(|isect| isect.get_point(true) )
+      .min_by(|a, b| a.distance(vertex).partial_cmp(&b.distance(vertex)).unwrap() );
+    let bottom_bound = bound(self.bottom_face(), bounds.0);
+    let top_bound = bound(self.top_face(), bounds.1);
+    if let (Some(top_bound), Some(bottom_bound)) = (top_bound, bottom_bound) {
+      bottom_vertex.borrow_mut().point = bottom_bound;
+      top_vertex.borrow_mut().point = top_bound;
+      let id = self.curve.id();
+      self.curve = curve;
+      self.curve.set_id(id);
+      Ok(())
+    } else {
+      Err("Edge could not be trimmed by surrounding faces".into())
+    }
   }
 }
 
@@ -1,11 +1,134 @@
+use serde::{Serialize, Deserialize};
+
 use crate::solid::*;
+use crate::transform::*;
+use crate::mesh::*;
+use crate::geom2d;
+
+
+// Number of quadrature segments per parametric dimension
+const QUADRATURE_SEGMENTS: usize = 16;
+
+// Maximum number of sweeps used to diagonalize the inertia tensor
+const MAX_JACOBI_SWEEPS: usize = 50;
+
+// Grid resolution used to approximate curved faces during point classification
+const CLASSIFICATION_STEPS: usize = 48;
 
 
 /// All types that have a closed boundary, separating space into what's inside and outside the volume.
 
 pub trait Volume: SurfaceArea {
   fn volume(&self) -> f64;
-  fn contains_point(&self, p: Point3) -> bool;
+  fn classify_point(&self, p: Point3, tolerance: f64) -> Containment;
+  fn mass_properties(&self, density: f64) -> MassProperties;
+
+  fn contains_point(&self, p: Point3) -> bool {
+    self.classify_point(p, EPSILON) == Containment::Inside
+  }
+}
+
+
+/// Location of a point relative to a [Volume].
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+  Inside,
+  Outside,
+  /// Within tolerance of the boundary
+  OnBoundary,
+}
+
+
+/// Physical properties of a body with homogeneous `density`.
+///
+/// The inertia tensor is given relative to the center of mass.
+/// Principal axes are stored as the columns of `principal_axes`, ordered by ascending principal moments.
+/// Use a density of 1.0 to obtain purely geometric properties.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MassProperties {
+  pub density: f64,
+  pub volume: f64,
+  pub mass: f64,
+  pub center_of_mass: Point3,
+  pub inertia: Matrix3,
+  pub principal_moments: Vec3,
+  pub principal_axes: Matrix3,
+  pub radii_of_gyration: Vec3,
+}
+
+impl MassProperties {
+  fn from_moments(moments: &Moments, reference: Point3, density: f64) -> Self {
+    let volume = moments.volume;
+    let centroid = if volume.almost(0.0) { Vec3::zero() } else { moments.first / volume };
+    // Second moments relative to the centroid
+    let central = (moments.second - outer_product(centroid, centroid) * volume) * density;
+    let inertia = Matrix3::from_value(central.trace()) - central;
+    let mass = volume * density;
+    let (principal_moments, principal_axes) = eigen_decomposition(inertia);
+    Self {
+      density,
+      volume,
+      mass,
+      center_of_mass: reference + centroid,
+      inertia,
+      principal_moments,
+      principal_axes,
+      radii_of_gyration: principal_moments.map(|moment| Self::gyration(moment, mass) ),
+    }
+  }
+
+  /// Inertia tensor relative to an arbitrary point, using the parallel axis theorem.
+  pub fn inertia_about_point(&self, p: Point3) -> Matrix3 {
+    let r = self.center_of_mass - p;
+    self.inertia + (Matrix3::from_value(r.magnitude2()) - outer_product(r, r)) * self.mass
+  }
+
+  /// Moment of inertia around an arbitrary axis.
+  pub fn inertia_about_axis(&self, axis: &Axis) -> f64 {
+    let distance = self.center_of_mass.distance(axis.closest_point(self.center_of_mass));
+    axis.direction.dot(self.inertia * axis.direction) + self.mass * distance.powi(2)
+  }
+
+  /// Radius of gyration around an arbitrary axis.
+  pub fn radius_of_gyration(&self, axis: &Axis) -> f64 {
+    Self::gyration(self.inertia_about_axis(axis), self.mass)
+  }
+
+  fn gyration(moment: f64, mass: f64) -> f64 {
+    if mass.almost(0.0) {
+      0.0
+    } else {
+      (moment / mass).max(0.0).sqrt()
+    }
+  }
+}
+
+
+// Volume integrals of 1, p and p * p^T, relative to a reference point
+#[derive(Debug, Clone)]
+struct Moments {
+  volume: f64,
+  first: Vec3,
+  second: Matrix3,
+}
+
+impl Moments {
+  fn new() -> Self {
+    Self {
+      volume: 0.0,
+      first: Vec3::zero(),
+      second: Matrix3::zero(),
+    }
+  }
+
+  fn add(mut self, other: Self) -> Self {
+    self.volume += other.volume;
+    self.first += other.first;
+    self.second += other.second;
+    self
+  }
 }
 
 
@@ -20,8 +143,21 @@ impl Volume for Compound {
     self.solids.iter().fold(0.0, |acc, solid| acc + solid.volume() )
   }
 
-  fn contains_point(&self, p: Point3) -> bool {
-    self.solids.iter().any(|solid| solid.contains_point(p) )
+  fn classify_point(&self, p: Point3, tolerance: f64) -> Containment {
+    let locations: Vec<Containment> = self.solids.iter().map(|solid| solid.classify_point(p, tolerance) ).collect();
+    if locations.contains(&Containment::Inside) {
+      Containment::Inside
+    } else if locations.contains(&Containment::OnBoundary) {
+      Containment::OnBoundary
+    } else {
+      Containment::Outside
+    }
+  }
+
+  fn mass_properties(&self, density: f64) -> MassProperties {
+    let reference = self.solids.first().map_or(Point3::origin(), |solid| solid.shells[0].reference_point() );
+    let moments = self.solids.iter().fold(Moments::new(), |acc, solid| acc.add(solid.moments(reference)) );
+    MassProperties::from_moments(&moments, reference, density)
   }
 }
 
@@ -33,12 +169,24 @@ impl SurfaceArea for Solid {
 }
 
 impl Volume for Solid {
+  // Inner shells face towards their cavity, so their volume is negative
   fn volume(&self) -> f64 {
-    self.shells[0].volume() - self.shells.iter().skip(1).fold(0.0, |acc, shell| acc + shell.volume() )
+    self.shells.iter().fold(0.0, |acc, shell| acc + shell.volume() )
   }
 
-  fn contains_point(&self, p: Point3) -> bool {
-    self.shells[0].contains_point(p) && !self.shells.iter().skip(1).any(|shell| shell.contains_point(p) )
+  fn classify_point(&self, p: Point3, tolerance: f64) -> Containment {
+    classify(&self.shells.iter().collect::<Vec<_>>(), p, tolerance)
+  }
+
+  fn mass_properties(&self, density: f64) -> MassProperties {
+    let reference = self.shells[0].reference_point();
+    MassProperties::from_moments(&self.moments(reference), reference, density)
+  }
+}
+
+impl Solid {
+  fn moments(&self, reference: Point3) -> Moments {
+    self.shells.iter().fold(Moments::new(), |acc, shell| acc.add(shell.moments(reference)) )
   }
 }
 
@@ -51,21 +199,49 @@ impl SurfaceArea for Shell {
 
 impl Volume for Shell {
   fn volume(&self) -> f64 {
-    0.0 //XXX
+    // Divergence theorem: V = 1/3 * Integral of p.n over the boundary
+    let reference = self.reference_point();
+    self.faces.iter().fold(0.0, |mut acc, face| {
+      face.borrow().visit_quadrature(|p, normal| acc += (p - reference).dot(normal) / 3.0 );
+      acc
+    })
   }
 
-  fn contains_point(&self, p: Point3) -> bool {
-    let ray = TrimmedCurve::new(Line::new(p, p + Vec3::unit_x() * 999999.0).into_enum());
-    let num_hits: usize = self.faces.iter().flat_map(|face| {
-      let intersections = ray.intersect_surface(&face.borrow().make_surface());
-      intersections.iter().map(|isect| match isect {
-        CurveSurfaceIntersectionType::Pierce(_)
-        | CurveSurfaceIntersectionType::Cross(_)
-          => 1,
-        _ => 0,
-      }).collect::<Vec<usize>>()
-    }).sum();
-    num_hits % 2 != 0
+  fn classify_point(&self, p: Point3, tolerance: f64) -> Containment {
+    classify(&[self], p, tolerance)
+  }
+
+  fn mass_properties(&self, density: f64) -> MassProperties {
+    let reference = self.reference_point();
+    MassProperties::from_moments(&self.moments(reference), reference, density)
+  }
+}
+
+impl Shell {
+  // Integrals are taken relative to a point close to the shell, to limit round-off errors
+  fn reference_point(&self) -> Point3 {
+    self.vertices.first().map_or(Point3::origin(), |vertex| vertex.borrow().point )
+  }
+
+  fn moments(&self, reference: Point3) -> Moments {
+    // Each integrand is expressed as the divergence of a field along a single axis
+    self.faces.iter().fold(Moments::new(), |mut acc, face| {
+      face.borrow().visit_quadrature(|p, normal| {
+        let p = p - reference;
+        acc.volume += p.dot(normal) / 3.0;
+        acc.first += p.mul_element_wise(p).mul_element_wise(normal) / 2.0;
+        for i in 0..3 {
+          for j in 0..3 {
+            acc.second[i][j] += if i == j {
+              p[i].powi(3) / 3.0 * normal[i]
+            } else {
+              p[i].powi(2) * p[j] / 2.0 * normal[i]
+            };
+          }
+        }
+      });
+      acc
+    })
   }
 }
 
@@ -76,8 +252,463 @@ impl SurfaceArea for Face {
   }
 }
 
+impl Face {
+  // Call visit with quadrature points on the face and their area weighted normals
+  fn visit_quadrature<F: FnMut(Point3, Vec3)>(&self, mut visit: F) {
+    let surface = self.make_surface();
+    match &surface.base {
+      // Green's theorem turns the area integral into a boundary integral,
+      // whose integrand is itself integrated along the plane's u direction
+      SurfaceType::Planar(surf) => {
+        let plane = &surf.plane;
+        let normal = plane.normal();
+        for (i, wire) in surface.profile.iter().enumerate() {
+          // Closed edges can't encode the direction of both rings they belong to,
+          // so rings made from a single curve are oriented by their role instead
+          let sign = if wire.len() == 1 {
+            let (a, b, c) = (wire[0].sample(0.0), wire[0].sample(1.0 / 3.0), wire[0].sample(2.0 / 3.0));
+            let is_counter_clockwise = (b - a).cross(c - a).dot(normal) > 0.0;
+            if is_counter_clockwise == (i == 0) { 1.0 } else { -1.0 }
+          } else {
+            1.0
+          };
+          for tcurve in wire.iter() {
+            let segments = if let CurveType::Line(_) = tcurve.base { 1 } else { QUADRATURE_SEGMENTS };
+            for (t, weight) in quadrature(0.0, 1.0, segments) {
+              let p = tcurve.sample(t) - plane.origin;
+              let (u, v) = (p.dot(plane.u), p.dot(plane.v));
+              let dv = tcurve.derivative(t).dot(plane.v);
+              for (s, s_weight) in quadrature(0.0, u, 1) {
+                visit(plane.sample(s, v), normal * (sign * weight * dv * s_weight));
+              }
+            }
+          }
+        }
+      },
+      SurfaceType::Revolution(_) | SurfaceType::Spline(_) => visit_parametric(&surface, &mut visit),
+    }
+  }
+}
+
+
+// Classify point against a set of shells, whose orientations define their inside
+//
+// Points are located on the side of the boundary that its closest point faces,
+// if that point lies in the interior of a curved face. Otherwise the generalized winding number
+// of a polygonal approximation of the shells decides, which is robust against
+// rays grazing edges and vertices, as well as small gaps in the approximation.
+fn classify(shells: &[&Shell], p: Point3, tolerance: f64) -> Containment {
+  let mut closest = (f64::MAX, None);
+  let mut winding = 0.0;
+  for face in shells.iter().flat_map(|shell| shell.faces.iter() ) {
+    let face = face.borrow();
+    let approximation = FaceApproximation::new(&face);
+    let (distance, normal) = approximation.closest_point(p);
+    if distance <= tolerance {
+      return Containment::OnBoundary
+    }
+    if distance < closest.0 {
+      closest = (distance, normal);
+    }
+    winding += approximation.triangles.iter().fold(0.0, |acc, triangle| acc + solid_angle(triangle, p) );
+  }
+  let is_inside = if let (_, Some((q, normal))) = closest {
+    (p - q).dot(normal) < 0.0
+  } else {
+    winding / (4.0 * std::f64::consts::PI) > 0.5
+  };
+  if is_inside { Containment::Inside } else { Containment::Outside }
+}
+
+
+// Check if the shell encloses a point, regardless of its orientation
+pub(super) fn encloses(shell: &Shell, p: Point3) -> bool {
+  let winding = shell.faces.iter().fold(0.0, |acc, face| {
+    FaceApproximation::new(&face.borrow()).triangles.iter().fold(acc, |acc, triangle| acc + solid_angle(triangle, p) )
+  });
+  (winding / (4.0 * std::f64::consts::PI)).abs() > 0.5
+}
+
+
+// Triangulation of a face, oriented along its normal
+struct FaceApproximation {
+  triangles: Vec<[Point3; 3]>,
+  surface: Option<TrimmedSurface>,
+}
+
+impl FaceApproximation {
+  fn new(face: &Face) -> Self {
+    let surface = face.make_surface();
+    match &surface.base {
+      SurfaceType::Planar(surf) => {
+        let mesh = surface.tesselate();
+        let normal = surf.plane.normal();
+        let triangles = mesh.faces.chunks(3).map(|indices| {
+          let (a, b, c) = (mesh.vertices[indices[0]], mesh.vertices[indices[1]], mesh.vertices[indices[2]]);
+          if (b - a).cross(c - a).dot(normal) < 0.0 { [a, c, b] } else { [a, b, c] }
+        }).collect();
+        Self { triangles, surface: None }
+      },
+      // Triangles face along the surface normal, or against it where they subtract from the domain.
+      // Pieces of the trimmed domain may thus overlap with opposite orientations,
+      // which cancel each other out in the winding number.
+      SurfaceType::Revolution(_) | SurfaceType::Spline(_) => {
+        let triangles = domain_triangles(&surface, CLASSIFICATION_STEPS).into_iter().map(|(triangle, sign)| {
+          let [a, b, c] = triangle.map(|p| surface.base.as_surface().sample(p.x, p.y) );
+          // Sign already includes the orientation of the triangle in parameter space
+          if sign < 0.0 { [a, c, b] } else { [a, b, c] }
+        }).collect();
+        Self { triangles, surface: Some(surface) }
+      },
+    }
+  }
+
+  // Distance to the face, as well as the closest point and its normal, if it lies in the interior of a curved face
+  fn closest_point(&self, p: Point3) -> (f64, Option<(Point3, Vec3)>) {
+    if let Some(surface) = &self.surface {
+      let (u, v, distance) = surface.base.as_surface().project(p);
+      let closed = [surface.base.is_closed_u(), surface.base.is_closed_v()];
+      let is_interior = [u, v].iter().zip(closed).all(|(t, is_closed)| is_closed || (EPSILON < *t && *t < 1.0 - EPSILON) );
+      if is_interior && surface.on_surface(u, v) {
+        let (du, dv) = surface.base.derivatives(u, v);
+        (distance, Some((surface.base.as_surface().sample(u, v), du.cross(dv))))
+      } else {
+        // Points closest to the boundary of the face are classified by winding number
+        let distance = surface.profile.iter().flat_map(|wire| wire.iter() ).fold(f64::MAX, |acc, tcurve| {
+          acc.min(tcurve.sample(tcurve.unsample(p).clamp(0.0, 1.0)).distance(p))
+        });
+        (distance, None)
+      }
+    } else {
+      let distance = self.triangles.iter().fold(f64::MAX, |acc, triangle| {
+        acc.min(triangle_closest_point(triangle, p).distance(p))
+      });
+      (distance, None)
+    }
+  }
+}
+
+
+// Signed solid angle of a triangle, as seen from p (Van Oosterom and Strackee)
+fn solid_angle(triangle: &[Point3; 3], p: Point3) -> f64 {
+  let (a, b, c) = (triangle[0] - p, triangle[1] - p, triangle[2] - p);
+  let (la, lb, lc) = (a.magnitude(), b.magnitude(), c.magnitude());
+  let numerator = a.dot(b.cross(c));
+  let denominator = la * lb * lc + a.dot(b) * lc + a.dot(c) * lb + b.dot(c) * la;
+  2.0 * numerator.atan2(denominator)
+}
+
+// Parameters at which the ray from origin along direction crosses the approximation of face
+pub(super) fn ray_face_intersections(face: &Face, origin: Point3, direction: Vec3) -> Vec<f64> {
+  match &face.surface {
+    SurfaceType::Planar(_) => FaceApproximation::new(face).triangles.iter()
+      .filter_map(|triangle| ray_triangle_intersection(triangle, origin, direction) )
+      .collect(),
+    // Hits on the untrimmed surface are kept if they land inside the profile of the face
+    SurfaceType::Revolution(_) | SurfaceType::Spline(_) => {
+      let surface = face.make_surface();
+      ray_surface_intersections(&surface.base, origin, direction).into_iter().filter(|t| {
+        let (u, v) = surface.base.as_surface().unsample(origin + direction * *t);
+        surface.on_surface(u, v)
+      }).collect()
+    },
+  }
+}
+
+// Same as ray_face_intersections, but ignoring the boundary of the face
+pub(super) fn ray_surface_intersections(surface: &SurfaceType, origin: Point3, direction: Vec3) -> Vec<f64> {
+  match surface {
+    SurfaceType::Planar(surf) => {
+      let normal = surf.plane.normal();
+      let denominator = direction.dot(normal);
+      if denominator.almost(0.0) {
+        vec![]
+      } else {
+        vec![(surf.plane.origin - origin).dot(normal) / denominator]
+      }
+    },
+    _ => surface_triangles(surface).iter()
+      .filter_map(|triangle| ray_triangle_intersection(triangle, origin, direction) )
+      .collect(),
+  }
+}
+
+// Triangulation of the full parametric range of a surface
+fn surface_triangles(surface: &SurfaceType) -> Vec<[Point3; 3]> {
+  let steps = CLASSIFICATION_STEPS;
+  let mut grid = Vec::with_capacity((steps + 1).pow(2));
+  for j in 0..=steps {
+    for i in 0..=steps {
+      grid.push(surface.as_surface().sample(i as f64 / steps as f64, j as f64 / steps as f64));
+    }
+  }
+  let mut triangles = Vec::with_capacity(steps * steps * 2);
+  for j in 0..steps {
+    for i in 0..steps {
+      let corner = |di: usize, dj: usize| grid[(j + dj) * (steps + 1) + i + di];
+      triangles.push([corner(0, 0), corner(1, 0), corner(1, 1)]);
+      triangles.push([corner(0, 0), corner(1, 1), corner(0, 1)]);
+    }
+  }
+  triangles
+}
+
+// Ray parameter of the intersection with a triangle, from either side (Moeller and Trumbore)
+fn ray_triangle_intersection(triangle: &[Point3; 3], origin: Point3, direction: Vec3) -> Option<f64> {
+  let [a, b, c] = *triangle;
+  let (ab, ac) = (b - a, c - a);
+  let h = direction.cross(ac);
+  let det = ab.dot(h);
+  if det.abs() < f64::EPSILON { return None }
+  let s = origin - a;
+  let u = s.dot(h) / det;
+  if !(0.0..=1.0).contains(&u) { return None }
+  let q = s.cross(ab);
+  let v = direction.dot(q) / det;
+  if v < 0.0 || u + v > 1.0 { return None }
+  Some(ac.dot(q) / det)
+}
+
+// Closest point on triangle (Ericson, Real-Time Collision Detection)
+fn triangle_closest_point(triangle: &[Point3; 3], p: Point3) -> Point3 {
+  let [a, b, c] = *triangle;
+  let (ab, ac, ap) = (b - a, c - a, p - a);
+  let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+  if d1 <= 0.0 && d2 <= 0.0 { return a }
+  let bp = p - b;
+  let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+  if d3 >= 0.0 && d4 <= d3 { return b }
+  let vc = d1 * d4 - d3 * d2;
+  if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 { return a + ab * (d1 / (d1 - d3)) }
+  let cp = p - c;
+  let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+  if d6 >= 0.0 && d5 <= d6 { return c }
+  let vb = d5 * d2 - d1 * d6;
+  if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 { return a + ac * (d2 / (d2 - d6)) }
+  let va = d3 * d6 - d5 * d4;
+  if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+    return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)))
+  }
+  let denominator = 1.0 / (va + vb + vc);
+  a + ab * (vb * denominator) + ac * (vc * denominator)
+}
+
+// Integrate over the trimmed parameter space of a curved face.
+// Quadrature points on each triangle of its domain are collapsed from a square onto its first corner (Duffy transform).
+fn visit_parametric<F: FnMut(Point3, Vec3)>(surface: &TrimmedSurface, visit: &mut F) {
+  for ([a, b, c], sign) in domain_triangles(surface, QUADRATURE_SEGMENTS) {
+    let det = geom2d::cross_2d(b - a, c - a);
+    for (s, s_weight) in quadrature(0.0, 1.0, 1) {
+      for (t, t_weight) in quadrature(0.0, 1.0, 1) {
+        let p = a + ((b - a) + (c - b) * t) * s;
+        let (du, dv) = surface.base.derivatives(p.x, p.y);
+        visit(surface.base.as_surface().sample(p.x, p.y), du.cross(dv) * (sign * s_weight * t_weight * s * det));
+      }
+    }
+  }
+}
+
+// Split the trimmed parameter domain of a surface into triangles, whose orientation has to be multiplied by their sign.
+// The domain is cut into grid cells, whose pieces fan out from their first corner.
+// Parameters are wrapped into the unit square on closed surfaces.
+fn domain_triangles(surface: &TrimmedSurface, steps: usize) -> Vec<([Point3; 3], f64)> {
+  let closed = [surface.base.is_closed_u(), surface.base.is_closed_v()];
+  let wrap = |p: Point3| {
+    let [u, v] = [0, 1].map(|axis| if closed[axis] { p[axis].rem_euclid(1.0) } else { p[axis] } );
+    Point3::new(u, v, 0.0)
+  };
+  let step = 1.0 / steps as f64;
+  let mut triangles = vec![];
+  for (polygon, sign) in surface.parametric_domain() {
+    let sign = sign * geom2d::signed_polygon_area(&polygon).signum();
+    let (min, max) = polygon.iter().fold(
+      (Point3::new(f64::MAX, f64::MAX, 0.0), Point3::new(f64::MIN, f64::MIN, 0.0)),
+      |(min, max), p| (Point3::new(min.x.min(p.x), min.y.min(p.y), 0.0), Point3::new(max.x.max(p.x), max.y.max(p.y), 0.0))
+    );
+    for i in (min.x / step).floor() as i64 .. (max.x / step).ceil() as i64 {
+      for j in (min.y / step).floor() as i64 .. (max.y / step).ceil() as i64 {
+        let corner = Point3::new(i as f64 * step, j as f64 * step, 0.0);
+        let cell = clip_polygon(&polygon, corner, step);
+        // Move cells back into range as a whole, to keep their pieces adjacent
+        let offset = wrap(corner + Vec3::new(step, step, 0.0) * 0.5) - (corner + Vec3::new(step, step, 0.0) * 0.5);
+        for k in 1..cell.len().max(2) - 1 {
+          triangles.push(([cell[0] + offset, cell[k] + offset, cell[k + 1] + offset], sign));
+        }
+      }
+    }
+  }
+  triangles
+}
+
+// Clip polygon to an axis aligned square (Sutherland-Hodgman).
+// Concave polygons may leave overlapping edges along the sides of the square, which enclose no area.
+fn clip_polygon(polygon: &PolyLine, corner: Point3, size: f64) -> PolyLine {
+  let mut output = polygon.clone();
+  for (axis, bound, is_lower) in [(0, corner.x, true), (0, corner.x + size, false), (1, corner.y, true), (1, corner.y + size, false)] {
+    let input = std::mem::take(&mut output);
+    let inside = |p: Point3| if is_lower { p[axis] >= bound } else { p[axis] <= bound };
+    for (i, &p) in input.iter().enumerate() {
+      let q = input[(i + 1) % input.len()];
+      if inside(p) {
+        output.push(p);
+      }
+      if inside(p) != inside(q) {
+        output.push(p + (q - p) * ((bound - p[axis]) / (q[axis] - p[axis])));
+      }
+    }
+  }
+  output
+}
+
+fn outer_product(a: Vec3, b: Vec3) -> Matrix3 {
+  Matrix3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+// Eigenvalues and eigenvectors of a symmetric matrix using Jacobi rotations
+fn eigen_decomposition(mut m: Matrix3) -> (Vec3, Matrix3) {
+  let mut vectors = Matrix3::identity();
+  let scale = (0..3).fold(0.0, |acc: f64, i| acc.max(m[i][i].abs()) );
+  for _ in 0..MAX_JACOBI_SWEEPS {
+    let (p, q) = [(0, 1), (0, 2), (1, 2)].iter().copied()
+      .max_by(|a, b| m[a.0][a.1].abs().partial_cmp(&m[b.0][b.1].abs()).unwrap() )
+      .unwrap();
+    if m[p][q].abs() <= scale * f64::EPSILON {
+      break
+    }
+    let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+    let c = 1.0 / (t * t + 1.0).sqrt();
+    let mut rotation = Matrix3::identity();
+    rotation[p][p] = c;
+    rotation[q][q] = c;
+    rotation[q][p] = t * c;
+    rotation[p][q] = -t * c;
+    m = rotation.transpose() * m * rotation;
+    vectors = vectors * rotation;
+  }
+  let mut order = [0, 1, 2];
+  order.sort_by(|&a, &b| m[a][a].partial_cmp(&m[b][b]).unwrap() );
+  (
+    Vec3::new(m[order[0]][order[0]], m[order[1]][order[1]], m[order[2]][order[2]]),
+    Matrix3::from_cols(vectors[order[0]], vectors[order[1]], vectors[order[2]]),
+  )
+}
+
 
 #[cfg(test)]
 mod tests {
+  use super::*;
+  use crate::solid::features;
 
+  #[test]
+  fn cube_volume() {
+    let cube = features::make_cube(1.5, 1.5, 1.5).unwrap();
+    assert!((cube.volume() - 3.375).abs() < 1.0e-9);
+  }
+
+  #[test]
+  fn cylinder_volume() {
+    let cylinder = features::make_cylinder(1.0, 2.0).unwrap();
+    assert!((cylinder.volume() - std::f64::consts::PI * 2.0).abs() < 1.0e-6);
+  }
+
+  // Quarter of an annulus, whose curved faces are completed to full revolutions
+  fn trimmed_revolution() -> Solid {
+    let points = [
+      Point3::new(1.0, 0.0, 0.0),
+      Point3::new(2.0, 0.0, 0.0),
+      Point3::new(2.0, 1.0, 0.0),
+      Point3::new(1.0, 1.0, 0.0),
+    ];
+    let rect = Wire::new((0..4).map(|i| TrimmedCurve::new(Line::new(points[i], points[(i + 1) % 4]).into_enum()) ).collect());
+    let profile = Profile::new(Plane::new(), vec![rect]);
+    let solid = features::revolve(&profile, Axis::new(Point3::origin(), Vec3::unit_y()), Deg(90.0)).unwrap();
+    for face in &solid.shells[0].faces {
+      if let SurfaceType::Revolution(surface) = &mut face.borrow_mut().surface {
+        let (start, end) = surface.u_bounds;
+        surface.u_bounds.1 = start + (end - start).signum();
+      }
+    }
+    solid
+  }
+
+  #[test]
+  fn trimmed_revolution_volume() {
+    let solid = trimmed_revolution();
+    assert!((solid.volume() - std::f64::consts::PI / 4.0 * 3.0).abs() < 1.0e-6);
+    let props = solid.mass_properties(1.0);
+    // Centroid of an annular sector lies at 2/3 * (R^3 - r^3) / (R^2 - r^2) * sin(a) / a from the axis
+    let distance = 2.0 / 3.0 * 7.0 / 3.0 * (std::f64::consts::FRAC_PI_4).sin() / std::f64::consts::FRAC_PI_4;
+    assert!((Vec3::new(props.center_of_mass.x, 0.0, props.center_of_mass.z).magnitude() - distance).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn cube_mass_properties() {
+    let cube = features::make_cube(1.0, 2.0, 3.0).unwrap();
+    let props = cube.mass_properties(2.0);
+    assert!((props.mass - 12.0).abs() < 1.0e-9);
+    assert!((props.center_of_mass.z - 1.5).abs() < 1.0e-9);
+    // Cuboid moments are m/12 * (a^2 + b^2)
+    let expected = Vec3::new(12.0 / 12.0 * 5.0, 12.0 / 12.0 * 10.0, 12.0 / 12.0 * 13.0);
+    assert!((props.principal_moments - expected).magnitude() < 1.0e-8);
+    assert!((props.principal_axes.x.z.abs() - 1.0).abs() < 1.0e-9);
+    let axis = Axis::new(props.center_of_mass + Vec3::new(1.0, 0.0, 0.0), Vec3::unit_z());
+    assert!((props.inertia_about_axis(&axis) - 17.0).abs() < 1.0e-8);
+  }
+
+  #[test]
+  fn cylinder_mass_properties() {
+    let cylinder = features::make_cylinder(1.0, 2.0).unwrap();
+    let props = cylinder.mass_properties(1.0);
+    let mass = std::f64::consts::PI * 2.0;
+    assert!((props.center_of_mass - Point3::new(0.0, 0.0, 1.0)).magnitude() < 1.0e-6);
+    assert!((props.principal_moments.x - mass / 2.0).abs() < 1.0e-6);
+    assert!((props.principal_moments.z - mass / 12.0 * (3.0 + 4.0)).abs() < 1.0e-6);
+    assert!((props.radii_of_gyration.x - 0.5_f64.sqrt()).abs() < 1.0e-6);
+  }
+
+  #[test]
+  fn classify_cube() {
+    let cube = features::make_cube(1.0, 1.0, 1.0).unwrap();
+    let corner = cube.shells[0].vertices[0].borrow().point;
+    let center = Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 0.5);
+    assert_eq!(cube.classify_point(center, EPSILON), Containment::Inside);
+    assert_eq!(cube.classify_point(Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 1.5), EPSILON), Containment::Outside);
+    assert_eq!(cube.classify_point(Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 1.0), EPSILON), Containment::OnBoundary);
+    assert_eq!(cube.classify_point(corner, EPSILON), Containment::OnBoundary);
+    // Points in line with edges and vertices
+    assert_eq!(cube.classify_point(Point3::new(corner.x + 0.5, corner.y, 0.5), EPSILON), Containment::Outside);
+    assert_eq!(cube.classify_point(Point3::new(0.0, corner.y + 1.0e-3, corner.z + 1.0e-3), EPSILON), Containment::Inside);
+    assert_eq!(cube.classify_point(Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 1.001), 0.01), Containment::OnBoundary);
+  }
+
+  #[test]
+  fn classify_trimmed_revolution() {
+    let solid = trimmed_revolution();
+    let center = solid.mass_properties(1.0).center_of_mass;
+    let opposite = Point3::new(-center.x, center.y, -center.z);
+    let outer = |p: Point3| {
+      let scale = 2.0 / Vec3::new(p.x, 0.0, p.z).magnitude();
+      Point3::new(p.x * scale, p.y, p.z * scale)
+    };
+    assert_eq!(solid.classify_point(center, EPSILON), Containment::Inside);
+    assert_eq!(solid.classify_point(outer(center), EPSILON), Containment::OnBoundary);
+    // Points on the untrimmed part of the surfaces
+    assert_eq!(solid.classify_point(opposite, EPSILON), Containment::Outside);
+    assert_eq!(solid.classify_point(outer(opposite), EPSILON), Containment::Outside);
+    assert!(ray_face_intersections(&solid.shells[0].faces.iter().find(|face|
+      matches!(face.borrow().surface, SurfaceType::Revolution(_))
+    ).unwrap().borrow(), Point3::new(0.0, 0.5, 0.0), opposite - Point3::new(0.0, 0.5, 0.0)).is_empty());
+  }
+
+  #[test]
+  fn classify_cylinder() {
+    let cylinder = features::make_cylinder(1.0, 2.0).unwrap();
+    assert_eq!(cylinder.classify_point(Point3::new(0.0, 0.0, 1.0), EPSILON), Containment::Inside);
+    assert_eq!(cylinder.classify_point(Point3::new(0.0, 0.9999, 1.0), EPSILON), Containment::Inside);
+    assert_eq!(cylinder.classify_point(Point3::new(0.0, -1.0001, 1.0), EPSILON), Containment::Outside);
+    assert_eq!(cylinder.classify_point(Point3::new(0.6, 0.8, 1.0), EPSILON), Containment::OnBoundary);
+    assert_eq!(cylinder.classify_point(Point3::new(0.6, 0.8, 2.0), EPSILON), Containment::OnBoundary);
+    assert_eq!(cylinder.classify_point(Point3::new(0.0, 0.0, 2.1), EPSILON), Containment::Outside);
+  }
 }
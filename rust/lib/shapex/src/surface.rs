@@ -5,6 +5,7 @@ use crate::transform::*;
 use crate::curve::*;
 use crate::mesh::*;
 use crate::wire::*;
+use crate::geom2d;
 
 pub(crate) mod intersection;
 pub use intersection::SurfaceIntersectionType;
@@ -12,6 +13,25 @@ pub use intersection::CurveSurfaceIntersectionType;
 pub use intersection::CurveSurfaceIntersection;
 
 
+// Number of samples per knot span used to seed the projection onto spline surfaces
+const PROJECTION_SUBDIVISIONS: usize = 4;
+
+// Number of closest samples that are refined when projecting onto spline surfaces
+const PROJECTION_SEEDS: usize = 4;
+
+// Maximum number of Newton iterations used to project points onto spline surfaces
+const PROJECTION_ITERATIONS: usize = 30;
+
+// Parametric step below which projections are considered converged
+const PROJECTION_TOLERANCE: f64 = 1.0e-12;
+
+// Number of samples per curved edge, when mapping profiles into the parameter space of surfaces
+const DOMAIN_SEGMENTS: usize = 16;
+
+// Relative distance from degenerate points, at which the normals of spline surfaces are taken instead
+const DEGENERATE_NORMAL_OFFSET: f64 = 1.0e-6;
+
+
 /// Base trait for all parametric surfaces.
 
 pub trait Surface: Transformable {
@@ -58,6 +78,12 @@ pub trait Surface: Transformable {
     self.sample(u, v)
   }
 
+  // Parameters of the closest point on the surface, together with its distance to p
+  fn project(&self, p: Point3) -> (f64, f64, f64) {
+    let (u, v) = self.unsample(p);
+    (u, v, self.sample(u, v).distance(p))
+  }
+
   fn contains_point(&self, p: Point3, ) -> bool {
     let (u, v) = self.unsample(p);
     u >= 0.0 && u <= 1.0 && v >= 0.0 && v <= 1.0 && self.sample(u, v).almost(p)
@@ -102,27 +128,48 @@ impl SurfaceType {
     }
   }
 
+  /// Exact partial derivatives along u and v.
+  pub fn derivatives(&self, u: f64, v: f64) -> (Vec3, Vec3) {
+    match self {
+      Self::Planar(surf) => (surf.plane.u, surf.plane.v),
+      Self::Revolution(surf) => surf.derivatives(u, v),
+      Self::Spline(surf) => surf.derivatives(u, v),
+    }
+  }
+
+  /// Check if the surface joins itself along its u boundaries, like full revolutions do.
+  pub fn is_closed_u(&self) -> bool {
+    let surface = self.as_surface();
+    [0.0, 0.5, 1.0].iter().all(|&v| surface.sample(0.0, v).almost(surface.sample(1.0, v)) )
+  }
+
+  /// Check if the surface joins itself along its v boundaries, like revolved circles do.
+  pub fn is_closed_v(&self) -> bool {
+    let surface = self.as_surface();
+    [0.0, 0.5, 1.0].iter().all(|&u| surface.sample(u, 0.0).almost(surface.sample(u, 1.0)) )
+  }
+
   pub fn intersect(&self, other: &Self) -> Vec<SurfaceIntersectionType> {
     match self {
       // PlanarSurface
       SurfaceType::Planar(plane) => match other {
         SurfaceType::Planar(surface) => intersection::plane_plane(&plane.plane, &surface.plane).map_or(vec![], |isect| vec![isect] ),
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+        SurfaceType::Revolution(surface) => intersection::plane_revolution(&plane.plane, surface).unwrap_or_else(|| intersection::march(self, other) ),
+        SurfaceType::Spline(_surface) => intersection::march(self, other),
       },
 
       // RevolutionSurface
-      SurfaceType::Revolution(_surface) => match other {
-        SurfaceType::Planar(_surface) => vec![],
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+      SurfaceType::Revolution(surface) => match other {
+        SurfaceType::Planar(plane) => intersection::plane_revolution(&plane.plane, surface).unwrap_or_else(|| intersection::march(self, other) ),
+        SurfaceType::Revolution(other_surface) => intersection::revolution_revolution(surface, other_surface).unwrap_or_else(|| intersection::march(self, other) ),
+        SurfaceType::Spline(_surface) => intersection::march(self, other),
       },
 
       // SplineSurface
       SurfaceType::Spline(_surface) => match other {
-        SurfaceType::Planar(_surface) => vec![],
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+        SurfaceType::Planar(_surface) => intersection::march(self, other),
+        SurfaceType::Revolution(_surface) => intersection::march(self, other),
+        SurfaceType::Spline(_surface) => intersection::march(self, other),
       },
     }
   }
@@ -135,29 +182,29 @@ impl CurveType {
       // Line
       CurveType::Line(line) => match other {
         SurfaceType::Planar(surface) => intersection::line_plane(line, &surface.plane).map_or(vec![], |isect| vec![isect] ),
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+        SurfaceType::Revolution(surface) => intersection::line_revolution(line, surface).unwrap_or_else(|| intersection::curve_surface(self, other) ),
+        SurfaceType::Spline(_surface) => intersection::curve_surface(self, other),
       },
 
       // Arc
-      CurveType::Arc(_arc) => match other {
-        SurfaceType::Planar(_surface) => vec![],
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+      CurveType::Arc(arc) => match other {
+        SurfaceType::Planar(surface) => intersection::arc_plane(arc, &surface.plane),
+        SurfaceType::Revolution(_surface) => intersection::curve_surface(self, other),
+        SurfaceType::Spline(_surface) => intersection::curve_surface(self, other),
       },
 
       // Circle
-      CurveType::Circle(_circle) => match other {
-        SurfaceType::Planar(_surface) => vec![],
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+      CurveType::Circle(circle) => match other {
+        SurfaceType::Planar(surface) => intersection::circle_plane(circle, &surface.plane),
+        SurfaceType::Revolution(_surface) => intersection::curve_surface(self, other),
+        SurfaceType::Spline(_surface) => intersection::curve_surface(self, other),
       },
 
-      // Spline
-      CurveType::Spline(_spline) => match other {
-        SurfaceType::Planar(_surface) => vec![],
-        SurfaceType::Revolution(_surface) => vec![],
-        SurfaceType::Spline(_surface) => vec![],
+      // Spline, Ellipse, EllipticalArc, Helix
+      CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => match other {
+        SurfaceType::Planar(_surface) => intersection::curve_surface(self, other),
+        SurfaceType::Revolution(_surface) => intersection::curve_surface(self, other),
+        SurfaceType::Spline(_surface) => intersection::curve_surface(self, other),
       },
     }
   }
@@ -178,7 +225,7 @@ impl TrimmedCurve {
         => {
           isect.t = self.param_from_base(isect.t);
           let first_at_end = isect.t.almost(0.0) || isect.t.almost(1.0);
-          if other.contains_point(isect.point) {
+          if other.on_surface(isect.u, isect.v) {
             if first_at_end {
               CurveSurfaceIntersectionType::Pierce(isect)
             } else if 0.0 <= isect.t && isect.t <= 1.0 {
@@ -198,7 +245,7 @@ impl TrimmedCurve {
 
 /// Bounded section of another surface.
 ///
-/// Trimmed surfaces are bounded by a set of [Wire]s, defined in the parameter space of their [base surface](SurfaceType).
+/// Trimmed surfaces are bounded by a set of [Wire]s lying on their [base surface](SurfaceType), starting with the outer ring.
 /// They are especially useful to tessellate [Surface] types that are naturally unbounded in one or both parametric dimensions.
 
 #[derive(Debug)]
@@ -216,8 +263,15 @@ impl TrimmedSurface {
   }
 
   pub fn on_surface(&self, u: f64, v: f64) -> bool {
-    let p = Point3::new(u, v, 0.0);
-    self.profile[0].contains_point(p) && !self.profile.iter().skip(1).any(|wire| wire.contains_point(p) )
+    let periods = |is_closed: bool| if is_closed { vec![-2.0, -1.0, 0.0, 1.0, 2.0] } else { vec![0.0] };
+    let (u_periods, v_periods) = (periods(self.base.is_closed_u()), periods(self.base.is_closed_v()));
+    let coverage = self.parametric_domain().iter().fold(0.0, |acc, (polygon, sign)| {
+      let is_inside = u_periods.iter().any(|du| v_periods.iter().any(|dv|
+        geom2d::polygon_contains_point(polygon, Point3::new(u + du, v + dv, 0.0))
+      ));
+      if is_inside { acc + sign } else { acc }
+    });
+    coverage > 0.0
   }
 
   pub fn contains_point(&self, p: Point3) -> bool {
@@ -225,6 +279,170 @@ impl TrimmedSurface {
     self.base.as_surface().sample(u, v).almost(p) && self.on_surface(u, v)
   }
 
+  /// Map the profile into the parameter space of the base surface.
+  ///
+  /// The trimmed domain is given as polygons, that add to or subtract from the domain, depending on their sign.
+  /// On surfaces that are closed in u or v, rings that wind around the surface bound the domain together with another
+  /// such ring or a collapsed pole of the surface. Polygons may then extend outside of the unit square by whole periods.
+
+  pub fn parametric_domain(&self) -> Vec<(PolyLine, f64)> {
+    let closed = [self.base.is_closed_u(), self.base.is_closed_v()];
+    let mut polygons = vec![];
+    let mut bands = vec![];
+    for wire in &self.profile {
+      let (ring, winding) = self.parametric_ring(wire, closed);
+      if ring.is_empty() { continue }
+      //XXX Rings winding around both directions of a torus are treated like those winding around u
+      if let Some(axis) = (0..2).find(|&axis| winding[axis] != 0.0 ) {
+        // Continue rings until they reach their start again, one period over
+        let mut path = ring.clone();
+        let mut end = ring[0];
+        end[axis] += winding[axis];
+        path.push(end);
+        if winding[axis] < 0.0 {
+          path.reverse();
+        }
+        bands.push((path, axis, winding[axis]));
+      } else {
+        // Rings are holes in the domain, unless the outer ring bounds it
+        let sign = if polygons.is_empty() && bands.is_empty() { 1.0 } else { -1.0 };
+        polygons.push((ring, sign));
+      }
+    }
+    if bands.is_empty() {
+      if polygons.is_empty() {
+        polygons.push((vec![
+          Point3::new(0.0, 0.0, 0.0),
+          Point3::new(1.0, 0.0, 0.0),
+          Point3::new(1.0, 1.0, 0.0),
+          Point3::new(0.0, 1.0, 0.0),
+        ], 1.0));
+      }
+      return polygons
+    }
+    for polygon in &mut polygons {
+      polygon.1 = -1.0;
+    }
+    // Pair neighboring bands, which now all run towards increasing parameters
+    for axis in 0..2 {
+      let other = 1 - axis;
+      let offset = |path: &PolyLine| path.iter().fold(0.0, |acc, p| acc + p[other] ) / path.len() as f64;
+      let mut bands: Vec<&(PolyLine, usize, f64)> = bands.iter().filter(|band| band.1 == axis ).collect();
+      bands.sort_by(|a, b| offset(&a.0).partial_cmp(&offset(&b.0)).unwrap() );
+      for pair in bands.chunks(2) {
+        let mut polygon = pair[0].0.clone();
+        let end = *polygon.last().unwrap();
+        if let Some((upper, _, _)) = pair.get(1) {
+          let shift = (end[axis] - upper.last().unwrap()[axis]).round();
+          polygon.extend(upper.iter().rev().map(|p| {
+            let mut p = *p;
+            p[axis] += shift;
+            p
+          }));
+        } else {
+          // Close the band towards the collapsed side of the surface, or to the left of the ring otherwise
+          let pole = match (self.is_collapsed(other, 0.0), self.is_collapsed(other, 1.0)) {
+            (true, false) => 0.0,
+            (false, true) => 1.0,
+            _ => if (pair[0].2 > 0.0) == (axis == 0) { 1.0 } else { 0.0 },
+          };
+          for mut p in [end, polygon[0]] {
+            p[other] = pole;
+            polygon.push(p);
+          }
+        }
+        polygons.push((polygon, 1.0));
+      }
+    }
+    polygons
+  }
+
+  // Sample wire in parameter space, together with the number of times it winds around closed directions of the surface
+  //XXX Assumes rings turn by less than half a revolution when crossing a pole
+  fn parametric_ring(&self, wire: &Wire, closed: [bool; 2]) -> (PolyLine, [f64; 2]) {
+    let surface = self.base.as_surface();
+    let to_params = |p: Point3| {
+      let (u, v) = surface.unsample(p);
+      // Poles map to every u, so they get placed between their neighbors instead
+      let is_pole = self.base.derivatives(u, v).0.magnitude() < EPSILON;
+      (Point3::new(u, v, 0.0), is_pole)
+    };
+    // Parametric distance traveled along the samples of an edge
+    let displacement = |samples: &[(Point3, bool)]| {
+      let regular: Vec<Point3> = samples.iter().filter(|(_, is_pole)| !is_pole ).map(|(p, _)| *p ).collect();
+      regular.windows(2).fold(Vec3::zero(), |acc, pair| {
+        let mut delta = pair[1] - pair[0];
+        for axis in (0..2).filter(|&axis| closed[axis] ) {
+          delta[axis] -= delta[axis].round();
+        }
+        acc + delta
+      })
+    };
+    let mut edges = vec![];
+    let mut closed_edges = vec![];
+    let mut total = Vec3::zero();
+    for tcurve in wire.iter() {
+      let segments = if let CurveType::Line(_) = tcurve.base { 1 } else { DOMAIN_SEGMENTS };
+      let samples: Vec<(Point3, bool)> = (0..=segments).map(|i| to_params(tcurve.sample(i as f64 / segments as f64)) ).collect();
+      if tcurve.sample(0.0).almost(tcurve.sample(1.0)) {
+        closed_edges.push((edges.len(), displacement(&samples)));
+      } else {
+        total += displacement(&samples);
+      }
+      edges.push(samples);
+    }
+    // Closed edges can't encode the direction of both rings they belong to,
+    // so they are oriented to lead rings back to their start where possible
+    for (i, delta) in closed_edges {
+      if (total - delta).magnitude() < (total + delta).magnitude() - EPSILON {
+        edges[i].reverse();
+        total -= delta;
+      } else {
+        total += delta;
+      }
+    }
+    let mut samples: Vec<(Point3, bool)> = edges.into_iter().flat_map(|mut samples| {
+      samples.pop();
+      samples
+    }).collect();
+    // Unwrap parameters across the seams of closed surfaces
+    let mut previous: Option<Point3> = None;
+    for (p, is_pole) in &mut samples {
+      if let Some(q) = previous {
+        for axis in (0..2).filter(|&axis| closed[axis] && !(*is_pole && axis == 0) ) {
+          p[axis] += (q[axis] - p[axis]).round();
+        }
+      }
+      if !*is_pole {
+        previous = Some(*p);
+      }
+    }
+    let regular: Vec<Point3> = samples.iter().filter(|(_, is_pole)| !is_pole ).map(|(p, _)| *p ).collect();
+    if regular.is_empty() { return (vec![], [0.0, 0.0]) }
+    let (first, last) = (regular[0], *regular.last().unwrap());
+    let winding = [0, 1].map(|axis| if closed[axis] { (last[axis] - first[axis]).round() } else { 0.0 } );
+    let mut ring = Vec::with_capacity(samples.len() + 2);
+    for (i, (p, is_pole)) in samples.iter().enumerate() {
+      if *is_pole {
+        let previous = samples[..i].iter().rev().find(|(_, is_pole)| !is_pole ).map_or(last.x - winding[0], |(q, _)| q.x );
+        let next = samples[i + 1..].iter().find(|(_, is_pole)| !is_pole ).map_or(first.x + winding[0], |(q, _)| q.x );
+        ring.push(Point3::new(previous, p.y, 0.0));
+        ring.push(Point3::new(next, p.y, 0.0));
+      } else {
+        ring.push(*p);
+      }
+    }
+    (ring, winding)
+  }
+
+  // Check if the iso-line at the given parameter along axis collapses into a single point
+  fn is_collapsed(&self, axis: usize, t: f64) -> bool {
+    let surface = self.base.as_surface();
+    let sample = |s: f64| if axis == 0 { surface.sample(t, s) } else { surface.sample(s, t) };
+    let p = sample(0.0);
+    sample(1.0 / 3.0).almost(p) && sample(2.0 / 3.0).almost(p)
+  }
+
   pub fn intersect(&self, _other: Self) -> SurfaceIntersectionType {
     todo!()
   }
@@ -339,9 +557,13 @@ impl RevolutionSurface {
   }
 
   pub fn cylinder(axis: Axis, radius: f64, height: f64) -> Self {
+    Self::cone(axis, radius, radius, height)
+  }
+
+  pub fn cone(axis: Axis, radius: f64, top_radius: f64, height: f64) -> Self {
     Self {
       axis,
-      curve: TrimmedCurve::new(Line::new(Point3::new(radius, 0.0, 0.0), Point3::new(radius, 0.0, height)).into_enum()),
+      curve: TrimmedCurve::new(Line::new(Point3::new(radius, 0.0, 0.0), Point3::new(top_radius, 0.0, height)).into_enum()),
       u_bounds: (0.0, 1.0),
     }
   }
@@ -367,6 +589,26 @@ impl RevolutionSurface {
     Point3::new(u.cos() * radius, u.sin() * radius, height)
   }
 
+  // Derivatives of the revolved generatrix, whose radius changes along v
+  pub fn derivatives(&self, u: f64, v: f64) -> (Vec3, Vec3) {
+    let sample = self.curve.sample(v);
+    let derivative = self.curve.derivative(v);
+    let radius = (sample.x * sample.x + sample.y * sample.y).sqrt();
+    let radial = if radius.almost(0.0) {
+      (derivative.x * derivative.x + derivative.y * derivative.y).sqrt()
+    } else {
+      (sample.x * derivative.x + sample.y * derivative.y) / radius
+    };
+    let span = self.u_bounds.1 - self.u_bounds.0;
+    let angle = self.convert_param(u) * std::f64::consts::TAU;
+    let (sin, cos) = angle.sin_cos();
+    let to_world = self.axis.as_transform();
+    (
+      to_world.transform_vector(Vec3::new(-sin, cos, 0.0) * radius * std::f64::consts::TAU * span),
+      to_world.transform_vector(Vec3::new(cos * radial, sin * radial, derivative.z)),
+    )
+  }
+
   pub fn v_tangent_at(&self, u: f64, v: f64) -> Vec3 {
     let u = self.convert_param(u);
     let v_tangent = self.curve.tangent_at(v);
@@ -385,8 +627,21 @@ impl Surface for RevolutionSurface {
     self.axis.as_transform().transform_point(p)
   }
 
-  fn unsample(&self, _p: Point3) -> (f64, f64) {
-    todo!()
+  // The closest point lies on the meridian through p
+  // Points beyond the ends of line generators yield parameters outside the 0-1 range
+  fn unsample(&self, p: Point3) -> (f64, f64) {
+    let local = self.axis.as_transform().invert().unwrap().transform_point(p);
+    let angle = local.y.atan2(local.x);
+    let turn = (angle / std::f64::consts::TAU).rem_euclid(1.0);
+    // Pick the revolution that lands closest to the surface's parametric range
+    let (start, end) = self.u_bounds;
+    let overshoot = |u: f64| (-u).max(u - 1.0).max(0.0);
+    let u = [turn - 1.0, turn, turn + 1.0, turn + 2.0].iter()
+      .map(|t| (t - start) / (end - start) )
+      .min_by(|a, b| overshoot(*a).partial_cmp(&overshoot(*b)).unwrap() )
+      .unwrap();
+    let meridian = Matrix4::from_angle_z(Rad(-angle)).transform_point(local);
+    (u, self.curve.unsample(meridian))
   }
 
   fn normal_at(&self, u: f64, v: f64) -> Vec3 {
@@ -410,7 +665,9 @@ impl Surface for RevolutionSurface {
     let v_steps = match &self.curve.base {
       CurveType::Line(_) => 1,
       CurveType::Arc(arc) => (circle_steps as f64 * (arc.bounds.1 - arc.bounds.0).abs()).max(1.0) as usize,
-      CurveType::Circle(_) => circle_steps,
+      CurveType::Circle(_) | CurveType::Ellipse(_) => circle_steps,
+      CurveType::EllipticalArc(arc) => (circle_steps as f64 * arc.range().abs()).max(1.0) as usize,
+      CurveType::Helix(helix) => (circle_steps as f64 * helix.turns.abs()).max(1.0) as usize,
       CurveType::Spline(spline) => if spline.degree == 1 {
         1
       } else {
@@ -446,22 +703,150 @@ pub struct SplineSurface {
   pub degree: (usize, usize),
   pub controls: Vec<Vec<Point3>>,
   pub knots: (Vec<f64>, Vec<f64>),
+  #[serde(default)]
+  pub weights: Vec<Vec<f64>>, // Missing weights are treated as 1
 }
 
 impl SplineSurface {
   pub fn tabulated(spline: &Spline, vec: Vec3) -> Self {
     let mut other_spline = spline.clone();
     other_spline.translate(vec);
+    Self::ruled(spline, &other_spline)
+  }
+
+  // Connect two splines, which need to share their degree and knot vector, by straight lines
+  pub fn ruled(spline: &Spline, other: &Spline) -> Self {
     Self {
       degree: (spline.degree, 1),
       controls: vec![
-        other_spline.controls,
+        other.controls.clone(),
         spline.controls.clone(),
       ],
-      knots: (other_spline.knots, vec![0.0, 0.0, 1.0, 1.0]),
+      knots: (other.knots.clone(), vec![0.0, 0.0, 1.0, 1.0]),
+      weights: vec![
+        other.weights.clone(),
+        spline.weights.clone(),
+      ],
     }
   }
 
+  // Interpolate section curves, which need to share their degree and knot vector
+  // Sections are placed at uniform parameters along v
+  pub fn lofted(sections: &[Spline]) -> Self {
+    let n = sections.len();
+    let degree = (n - 1).min(3);
+    let knots = Spline::clamped_knots(n, degree);
+    // Solve for control rows, such that the surface passes through all sections
+    let matrix: Vec<Vec<f64>> = (0..n).map(|k|
+      Self::get_basis_function(degree, k as f64 / (n - 1) as f64, &knots)
+    ).collect();
+    // Rational sections are interpolated in homogeneous coordinates
+    let rows: Vec<Vec<Vec4>> = sections.iter().map(|section|
+      section.controls.iter().zip(&section.weights).map(|(p, w)| (p.to_vec() * *w).extend(*w) ).collect()
+    ).collect();
+    let rows = Self::solve_controls(matrix, rows);
+    Self {
+      degree: (sections[0].degree, degree),
+      controls: rows.iter().map(|row| row.iter().map(|h| Point3::from_vec(h.truncate() / h.w) ).collect() ).collect(),
+      knots: (sections[0].knots.clone(), knots),
+      weights: rows.iter().map(|row| row.iter().map(|h| h.w ).collect() ).collect(),
+    }
+  }
+
+  pub fn weight(&self, row: usize, column: usize) -> f64 {
+    self.weights.get(row).and_then(|weights| weights.get(column) ).copied().unwrap_or(1.0)
+  }
+
+  /// Insert a knot at the normalized parameter `t` along u, without changing the shape of the surface.
+  pub fn insert_knot_u(&mut self, t: f64) {
+    let x = self.knots.0[self.degree.0] + t * (self.knots.0[self.controls[0].len()] - self.knots.0[self.degree.0]);
+    let rows = self.rows().into_iter().map(|mut row| {
+      row.insert_knot(x);
+      row
+    }).collect();
+    *self = self.with_rows(rows);
+  }
+
+  /// Insert a knot at the normalized parameter `t` along v, without changing the shape of the surface.
+  pub fn insert_knot_v(&mut self, t: f64) {
+    let mut transposed = self.transposed();
+    transposed.insert_knot_u(t);
+    *self = transposed.transposed();
+  }
+
+  /// Split the surface along u at the normalized parameter `t`, into the parts before and after it.
+  pub fn split_u(&self, t: f64) -> Option<(Self, Self)> {
+    let (left, right): (Vec<Spline>, Vec<Spline>) = self.rows().iter()
+      .map(|row| row.split_at(t) )
+      .collect::<Option<Vec<_>>>()?
+      .into_iter().unzip();
+    Some((self.with_rows(left), self.with_rows(right)))
+  }
+
+  /// Split the surface along v at the normalized parameter `t`, into the parts before and after it.
+  pub fn split_v(&self, t: f64) -> Option<(Self, Self)> {
+    let (left, right) = self.transposed().split_u(t)?;
+    Some((left.transposed(), right.transposed()))
+  }
+
+  // Rows of controls as splines along u, sharing the knots of the surface
+  fn rows(&self) -> Vec<Spline> {
+    self.controls.iter().enumerate().map(|(i, row)| {
+      let mut spline = Spline::new(row.clone());
+      spline.degree = self.degree.0;
+      spline.knots = self.knots.0.clone();
+      spline.weights = (0..row.len()).map(|j| self.weight(i, j) ).collect();
+      spline
+    }).collect()
+  }
+
+  // Surface with the same v direction, whose rows were replaced
+  fn with_rows(&self, rows: Vec<Spline>) -> Self {
+    Self {
+      degree: (rows[0].degree, self.degree.1),
+      controls: rows.iter().map(|row| row.controls.clone() ).collect(),
+      knots: (rows[0].knots.clone(), self.knots.1.clone()),
+      weights: rows.iter().map(|row| row.weights.clone() ).collect(),
+    }
+  }
+
+  // Surface with u and v swapped
+  fn transposed(&self) -> Self {
+    let columns = 0..self.controls[0].len();
+    Self {
+      degree: (self.degree.1, self.degree.0),
+      controls: columns.clone().map(|j| self.controls.iter().map(|row| row[j] ).collect() ).collect(),
+      knots: (self.knots.1.clone(), self.knots.0.clone()),
+      weights: columns.map(|j| (0..self.controls.len()).map(|i| self.weight(i, j) ).collect() ).collect(),
+    }
+  }
+
+  // Solve for the control rows that make basis functions (one row per sample) reproduce the given rows
+  // Gauss-Jordan elimination with partial pivoting
+  fn solve_controls(mut matrix: Vec<Vec<f64>>, mut rows: Vec<Vec<Vec4>>) -> Vec<Vec<Vec4>> {
+    let n = matrix.len();
+    for col in 0..n {
+      let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().partial_cmp(&matrix[b][col].abs()).unwrap() ).unwrap();
+      matrix.swap(col, pivot);
+      rows.swap(col, pivot);
+      let pivot_coefficients = matrix[col].clone();
+      let pivot_controls = rows[col].clone();
+      for row in 0..n {
+        if row == col { continue }
+        let factor = matrix[row][col] / pivot_coefficients[col];
+        for (value, pivot) in matrix[row].iter_mut().zip(&pivot_coefficients) {
+          *value -= pivot * factor;
+        }
+        for (vec, pivot) in rows[row].iter_mut().zip(&pivot_controls) {
+          *vec -= pivot * factor;
+        }
+      }
+    }
+    rows.iter().enumerate().map(|(i, row)|
+      row.iter().map(|vec| vec / matrix[i][i] ).collect()
+    ).collect()
+  }
+
   fn get_basis_function(degree: usize, t: f64, knots: &Vec<f64>) -> Vec<f64> {
     // Remap t to actual curve range
     let low = knots[degree];
@@ -488,6 +873,119 @@ impl SplineSurface {
     basis
   }
 
+  // Normalized parameters that split every knot span into equal parts
+  fn subdivided_params(degree: usize, knots: &[f64]) -> Vec<f64> {
+    let low = knots[degree];
+    let high = knots[knots.len() - degree - 1];
+    let mut spans: Vec<f64> = knots[degree..knots.len() - degree].to_vec();
+    spans.dedup();
+    let mut params: Vec<f64> = spans.windows(2).flat_map(|span|
+      (0..PROJECTION_SUBDIVISIONS).map(move |i| span[0] + (span[1] - span[0]) * i as f64 / PROJECTION_SUBDIVISIONS as f64 )
+    ).map(|t| (t - low) / (high - low) ).collect();
+    params.push(1.0);
+    params
+  }
+
+  // Gauss-Newton iteration towards the foot point of p, kept inside the parametric range
+  fn refine_projection(&self, p: Point3, mut u: f64, mut v: f64) -> (f64, f64) {
+    for _ in 0..PROJECTION_ITERATIONS {
+      let (du, dv) = self.derivatives(u, v);
+      let delta = p - self.sample(u, v);
+      let (a, b, c) = (du.dot(du), du.dot(dv), dv.dot(dv));
+      let det = a * c - b * b;
+      if det.abs() < f64::EPSILON { break }
+      let (rhs_u, rhs_v) = (du.dot(delta), dv.dot(delta));
+      let new_u = (u + (c * rhs_u - b * rhs_v) / det).clamp(0.0, 1.0);
+      let new_v = (v + (a * rhs_v - b * rhs_u) / det).clamp(0.0, 1.0);
+      let converged = (new_u - u).abs() < PROJECTION_TOLERANCE && (new_v - v).abs() < PROJECTION_TOLERANCE;
+      (u, v) = (new_u, new_v);
+      if converged { break }
+    }
+    (u, v)
+  }
+
+  // First partial derivatives along u and v
+  pub fn derivatives(&self, u: f64, v: f64) -> (Vec3, Vec3) {
+    let derivatives = self.partial_derivatives(u, v, 1);
+    (derivatives[1][0], derivatives[0][1])
+  }
+
+  // Second partial derivatives along u, both directions and v
+  pub fn second_derivatives(&self, u: f64, v: f64) -> (Vec3, Vec3, Vec3) {
+    let derivatives = self.partial_derivatives(u, v, 2);
+    (derivatives[2][0], derivatives[1][1], derivatives[0][2])
+  }
+
+  // Mixed partial derivatives, indexed by the number of derivations along u and v
+  // Derivatives of the homogeneous surface are divided by the weight using the quotient rule
+  fn partial_derivatives(&self, u: f64, v: f64, order: usize) -> Vec<Vec<Vec3>> {
+    let basis_u = Self::get_basis_derivatives(self.degree.0, u, &self.knots.0, order);
+    let basis_v = Self::get_basis_derivatives(self.degree.1, v, &self.knots.1, order);
+    let homogeneous: Vec<Vec<Vec4>> = (0..=order).map(|k| (0..=order - k).map(|l|
+      self.weighted_sum(&basis_u[k], &basis_v[l])
+    ).collect()).collect();
+    let mut derivatives: Vec<Vec<Vec3>> = vec![];
+    for k in 0..=order {
+      let mut row: Vec<Vec3> = vec![];
+      for l in 0..=order - k {
+        let mut numerator = homogeneous[k][l].truncate();
+        for j in 1..=l {
+          numerator -= row[l - j] * (binomial(l, j) * homogeneous[0][j].w);
+        }
+        for i in 1..=k {
+          numerator -= derivatives[k - i][l] * (binomial(k, i) * homogeneous[i][0].w);
+          for j in 1..=l {
+            numerator -= derivatives[k - i][l - j] * (binomial(k, i) * binomial(l, j) * homogeneous[i][j].w);
+          }
+        }
+        row.push(numerator / homogeneous[0][0].w);
+      }
+      derivatives.push(row);
+    }
+    derivatives
+  }
+
+  // Weighted controls, combined by the given basis functions along u and v
+  fn weighted_sum(&self, basis_u: &[f64], basis_v: &[f64]) -> Vec4 {
+    self.controls.iter().zip(basis_v).enumerate().fold(Vec4::zero(), |acc, (i, (row, bv))| {
+      row.iter().zip(basis_u).enumerate().fold(acc, |acc, (j, (cv, bu))| {
+        let w = self.weight(i, j);
+        acc + (cv.to_vec() * w).extend(w) * (bu * bv)
+      })
+    })
+  }
+
+  // Derivatives of all basis functions with respect to the normalized parameter, up to the given order
+  pub(crate) fn get_basis_derivatives(degree: usize, t: f64, knots: &[f64], order: usize) -> Vec<Vec<f64>> {
+    let low = knots[degree];
+    let high = knots[knots.len() - degree - 1];
+    let x = low + t * (high - low);
+    // Cox-de Boor recursion, keeping the basis functions of all lower degrees
+    let span = (degree..knots.len() - degree - 1).find(|&i| x < knots[i + 1] ).unwrap_or(knots.len() - degree - 2);
+    let mut table = vec![(0..knots.len() - 1).map(|i| if i == span { 1.0 } else { 0.0 } ).collect::<Vec<f64>>()];
+    for p in 1..=degree {
+      let lower = &table[p - 1];
+      let basis = (0..knots.len() - 1 - p).map(|i|
+        Self::inv_or_zero(knots[i + p] - knots[i]) * (x - knots[i]) * lower[i] +
+        Self::inv_or_zero(knots[i + p + 1] - knots[i + 1]) * (knots[i + p + 1] - x) * lower[i + 1]
+      ).collect();
+      table.push(basis);
+    }
+    (0..=order).map(|k| {
+      let scale = (high - low).powi(k as i32);
+      (0..knots.len() - 1 - degree).map(|i| Self::basis_derivative(&table, knots, degree, i, k) * scale ).collect()
+    }).collect()
+  }
+
+  fn basis_derivative(table: &[Vec<f64>], knots: &[f64], degree: usize, i: usize, k: usize) -> f64 {
+    if k == 0 { return table[degree][i] }
+    if degree == 0 { return 0.0 }
+    degree as f64 * (
+      Self::inv_or_zero(knots[i + degree] - knots[i]) * Self::basis_derivative(table, knots, degree - 1, i, k - 1) -
+      Self::inv_or_zero(knots[i + degree + 1] - knots[i + 1]) * Self::basis_derivative(table, knots, degree - 1, i + 1, k - 1)
+    )
+  }
+
   fn inv_or_zero(delta: f64) -> f64 {
     if delta.almost(0.0) {
       0.0
@@ -513,19 +1011,35 @@ impl Surface for SplineSurface {
   fn sample(&self, u: f64, v: f64) -> Point3 {
     let basis_u = Self::get_basis_function(self.degree.0, u, &self.knots.0);
     let basis_v = Self::get_basis_function(self.degree.1, v, &self.knots.1);
-    self.controls.iter().zip(&basis_v).fold(Point3::origin(), |acc, (row, bu)| {
-      row.iter().zip(&basis_u).fold(acc, |acc, (cv, bv)| {
-        acc + cv.to_vec() * (bu * bv)
-      })
-    })
+    let homogeneous = self.weighted_sum(&basis_u, &basis_v);
+    Point3::from_vec(homogeneous.truncate() / homogeneous.w)
   }
 
-  fn unsample(&self, _p: Point3) -> (f64, f64) {
-    todo!()
+  // Newton iteration from the closest samples of a grid, that subdivides every knot span
+  fn unsample(&self, p: Point3) -> (f64, f64) {
+    let u_params = Self::subdivided_params(self.degree.0, &self.knots.0);
+    let v_params = Self::subdivided_params(self.degree.1, &self.knots.1);
+    let mut seeds: Vec<(f64, f64, f64)> = v_params.iter()
+      .flat_map(|&v| u_params.iter().map(move |&u| (u, v) ) )
+      .map(|(u, v)| (u, v, self.sample(u, v).distance2(p)) )
+      .collect();
+    seeds.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap() );
+    seeds.iter().take(PROJECTION_SEEDS)
+      .map(|&(u, v, _)| self.refine_projection(p, u, v) )
+      .min_by(|a, b| self.sample(a.0, a.1).distance2(p).partial_cmp(&self.sample(b.0, b.1).distance2(p)).unwrap() )
+      .unwrap()
   }
 
-  fn normal_at(&self, _u: f64, _v: f64) -> Vec3 {
-    Vec3::unit_x()
+  fn normal_at(&self, u: f64, v: f64) -> Vec3 {
+    let (du, dv) = self.derivatives(u, v);
+    let normal = du.cross(dv);
+    if normal.magnitude() <= EPSILON * du.magnitude() * dv.magnitude() {
+      // Collapsed edges take the normal from just inside the surface
+      let (du, dv) = self.derivatives(u + (0.5 - u) * DEGENERATE_NORMAL_OFFSET, v + (0.5 - v) * DEGENERATE_NORMAL_OFFSET);
+      du.cross(dv).normalize()
+    } else {
+      normal.normalize()
+    }
   }
 
   fn tesselate(&self, profile: &Vec<Wire>) -> Mesh {
@@ -537,7 +1051,8 @@ impl Surface for SplineSurface {
   }
 
   fn flip(&mut self) {
-    self.controls = self.controls.iter().rev().cloned().collect();
+    self.controls.reverse();
+    self.weights.reverse();
   }
 }
 
@@ -596,4 +1111,192 @@ mod tests {
     almost_eq!(vec, normal);
     almost_eq!(normal, gen_normal);
   }
+
+  #[test]
+  fn revolution_unsample() {
+    let cone = RevolutionSurface::cone(Axis::new(Point3::new(1.0, 0.0, 0.0), Vec3::unit_y()), 1.0, 0.5, 2.0);
+    for (u, v) in [(0.0, 0.0), (0.3, 0.5), (0.8, 0.25), (0.5, 1.0)] {
+      let (u2, v2) = cone.unsample(cone.sample(u, v));
+      almost_eq!(u2, u);
+      almost_eq!(v2, v);
+    }
+    assert!(cone.contains_point(cone.sample(0.6, 0.4)));
+    // Points off the surface project along the meridian
+    let cylinder = RevolutionSurface::cylinder(Axis::new(Point3::origin(), Vec3::unit_z()), 1.0, 1.0);
+    let (u, v, distance) = cylinder.project(Point3::new(0.0, 3.0, 0.5));
+    almost_eq!(u, 0.25);
+    almost_eq!(v, 0.5);
+    almost_eq!(distance, 2.0);
+    // The generator extends beyond the surface, like planes do
+    let (_, v, _) = cylinder.project(Point3::new(0.0, -0.5, 2.0));
+    almost_eq!(v, 2.0);
+    assert!(!cylinder.contains_point(Point3::new(0.0, -0.5, 0.5)));
+  }
+
+  #[test]
+  fn spline_unsample() {
+    let surface = SplineSurface::lofted(&[
+      Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 1.0), Point3::new(2.0, 0.0, 0.0)]),
+      Spline::new(vec![Point3::new(0.0, 1.0, 0.5), Point3::new(1.0, 1.0, -0.5), Point3::new(2.0, 1.0, 0.5)]),
+      Spline::new(vec![Point3::new(0.0, 2.0, 0.0), Point3::new(1.0, 2.0, 1.0), Point3::new(2.0, 2.0, 0.0)]),
+    ]);
+    for (u, v) in [(0.0, 0.0), (0.2, 0.7), (0.5, 0.5), (0.9, 0.1), (1.0, 1.0)] {
+      let (u2, v2) = surface.unsample(surface.sample(u, v));
+      assert!((u2 - u).abs() < 1.0e-6 && (v2 - v).abs() < 1.0e-6, "({}, {}) was unsampled to ({}, {})", u, v, u2, v2);
+    }
+    assert!(surface.contains_point(surface.sample(0.3, 0.6)));
+    // Offset along the normal projects back onto the foot point
+    let (du, dv) = surface.derivatives(0.4, 0.3);
+    let foot = surface.sample(0.4, 0.3);
+    let (u, v, distance) = surface.project(foot + du.cross(dv).normalize() * 0.1);
+    assert!((u - 0.4).abs() < 1.0e-6 && (v - 0.3).abs() < 1.0e-6);
+    almost_eq!(distance, 0.1);
+    // Points beyond the boundary project onto it
+    let (_, v, _) = surface.project(Point3::new(1.0, 3.0, 0.5));
+    almost_eq!(v, 1.0);
+  }
+
+
+  #[test]
+  fn spline_derivatives() {
+    let surface = SplineSurface::tabulated(&Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0), Point3::new(2.0, 0.0, 0.0)]), Vec3::new(0.0, 0.0, 2.0));
+    let (du, dv) = surface.derivatives(0.25, 0.5);
+    almost_eq!(du, Vec3::new(2.0, 1.0, 0.0));
+    almost_eq!(dv.cross(Vec3::unit_z()).magnitude(), 0.0);
+    almost_eq!(dv.magnitude(), 2.0);
+    let (duu, duv, dvv) = surface.second_derivatives(0.25, 0.5);
+    almost_eq!(duu, Vec3::new(0.0, -4.0, 0.0));
+    almost_eq!(duv, Vec3::zero());
+    almost_eq!(dvv, Vec3::zero());
+    // Normals follow the bend of the surface
+    let normal = surface.normal_at(0.25, 0.5);
+    almost_eq!(normal, du.cross(dv).normalize());
+    let normal = surface.normal_at(0.0, 0.5);
+    almost_eq!(surface.normal_at(1.0, 0.5).x, -normal.x);
+    let mut flipped = surface.clone();
+    flipped.flip();
+    almost_eq!(flipped.normal_at(0.5, 0.5), -surface.normal_at(0.5, 0.5));
+  }
+
+  #[test]
+  fn spline_derivatives_match_samples() {
+    let surface = SplineSurface::lofted(&[
+      Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 1.0), Point3::new(2.0, 0.0, 0.0), Point3::new(3.0, 0.0, 0.5)]),
+      Spline::new(vec![Point3::new(0.0, 1.0, 0.5), Point3::new(1.0, 1.0, -0.5), Point3::new(2.0, 1.0, 0.5), Point3::new(3.0, 1.0, 0.0)]),
+      Spline::new(vec![Point3::new(0.0, 2.0, 0.0), Point3::new(1.0, 2.0, 1.0), Point3::new(2.0, 2.0, 0.0), Point3::new(3.0, 2.0, 1.0)]),
+      Spline::new(vec![Point3::new(0.0, 3.0, 0.0), Point3::new(1.0, 3.0, 0.0), Point3::new(2.0, 3.0, 1.0), Point3::new(3.0, 3.0, 0.0)]),
+    ]);
+    let h = 1.0e-5;
+    for (u, v) in [(0.3, 0.6), (0.5, 0.5), (0.1, 0.9)] {
+      let (du, dv) = surface.derivatives(u, v);
+      assert!(du.distance((surface.sample(u + h, v) - surface.sample(u - h, v)) / (2.0 * h)) < 1.0e-6);
+      assert!(dv.distance((surface.sample(u, v + h) - surface.sample(u, v - h)) / (2.0 * h)) < 1.0e-6);
+      let (duu, duv, dvv) = surface.second_derivatives(u, v);
+      assert!(duu.distance((surface.derivatives(u + h, v).0 - surface.derivatives(u - h, v).0) / (2.0 * h)) < 1.0e-4);
+      assert!(duv.distance((surface.derivatives(u, v + h).0 - surface.derivatives(u, v - h).0) / (2.0 * h)) < 1.0e-4);
+      assert!(dvv.distance((surface.derivatives(u, v + h).1 - surface.derivatives(u, v - h).1) / (2.0 * h)) < 1.0e-4);
+    }
+    // Derivatives stay defined at the end of the parametric range
+    let (du, dv) = surface.derivatives(1.0, 1.0);
+    assert!(du.distance((surface.sample(1.0, 1.0) - surface.sample(1.0 - h, 1.0)) / h) < 1.0e-3);
+    assert!(dv.distance((surface.sample(1.0, 1.0) - surface.sample(1.0, 1.0 - h)) / h) < 1.0e-3);
+  }
+
+
+  #[test]
+  fn revolution_derivatives() {
+    // Partial cone around a tilted axis, with a reversed generatrix
+    let axis = Axis::new(Point3::new(1.0, 2.0, 0.0), Vec3::new(1.0, 0.0, 1.0).normalize());
+    let mut cone = RevolutionSurface::cone(axis, 1.0, 0.5, 2.0);
+    cone.curve.flip();
+    cone.u_bounds = (0.25, 0.6);
+    let h = 1.0e-6;
+    for (u, v) in [(0.0, 0.5), (0.3, 0.2), (0.9, 0.8)] {
+      let (du, dv) = cone.derivatives(u, v);
+      assert!(du.distance((cone.sample(u + h, v) - cone.sample(u - h, v)) / (2.0 * h)) < 1.0e-6);
+      assert!(dv.distance((cone.sample(u, v + h) - cone.sample(u, v - h)) / (2.0 * h)) < 1.0e-6);
+    }
+  }
+
+  #[test]
+  fn rational_spline_surface() {
+    // Quarter of a cylinder, tabulated from an exact arc
+    let mut arc = Spline::new(vec![Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0)]);
+    arc.weights = vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0];
+    let surface = SplineSurface::tabulated(&arc, Vec3::new(0.0, 0.0, 2.0));
+    let h = 1.0e-6;
+    for (u, v) in [(0.0, 0.0), (0.3, 0.6), (0.5, 0.5), (1.0, 0.2)] {
+      let p = surface.sample(u, v);
+      almost_eq!((p.x * p.x + p.y * p.y).sqrt(), 1.0);
+      let normal = surface.normal_at(u, v);
+      almost_eq!(normal.z, 0.0);
+      almost_eq!(normal.dot(Vec3::new(p.x, p.y, 0.0)).abs(), 1.0);
+      let (u2, v2) = surface.unsample(p);
+      assert!((u2 - u).abs() < 1.0e-6 && (v2 - v).abs() < 1.0e-6);
+    }
+    let (du, _) = surface.derivatives(0.4, 0.5);
+    let (duu, _, _) = surface.second_derivatives(0.4, 0.5);
+    assert!(du.distance((surface.sample(0.4 + h, 0.5) - surface.sample(0.4 - h, 0.5)) / (2.0 * h)) < 1.0e-6);
+    assert!(duu.distance((surface.derivatives(0.4 + h, 0.5).0 - surface.derivatives(0.4 - h, 0.5).0) / (2.0 * h)) < 1.0e-4);
+    // Lofting through rational sections keeps them exact
+    let sections: Vec<Spline> = (0..3).map(|i| {
+      let mut section = arc.clone();
+      section.translate(Vec3::new(0.0, 0.0, i as f64));
+      section
+    }).collect();
+    let lofted = SplineSurface::lofted(&sections);
+    let p = lofted.sample(0.3, 0.75);
+    almost_eq!((p.x * p.x + p.y * p.y).sqrt(), 1.0);
+    almost_eq!(p.z, 1.5);
+  }
+
+  // Rational surface with several knot spans in both directions
+  fn bumpy_surface() -> SplineSurface {
+    let mut surface = SplineSurface {
+      degree: (2, 2),
+      controls: (0..4).map(|i| (0..5).map(|j|
+        Point3::new(j as f64, i as f64, ((i + j) % 3) as f64 * 0.5)
+      ).collect() ).collect(),
+      knots: (Spline::clamped_knots(5, 2), Spline::clamped_knots(4, 2)),
+      weights: vec![],
+    };
+    surface.weights = (0..4).map(|i| (0..5).map(|j| 1.0 + ((i * 5 + j) % 4) as f64 * 0.25 ).collect() ).collect();
+    surface
+  }
+
+  #[test]
+  fn spline_surface_knot_insertion() {
+    let surface = bumpy_surface();
+    let mut refined = surface.clone();
+    refined.insert_knot_u(0.3);
+    refined.insert_knot_v(0.7);
+    refined.insert_knot_v(0.5);
+    assert_eq!(refined.controls.len(), 6);
+    assert_eq!(refined.controls[0].len(), 6);
+    assert_eq!(refined.knots.1.len(), refined.controls.len() + 3);
+    for (u, v) in [(0.0, 0.0), (0.3, 0.7), (0.45, 0.1), (0.9, 0.55), (1.0, 1.0)] {
+      almost_eq!(refined.sample(u, v), surface.sample(u, v));
+    }
+  }
+
+  #[test]
+  fn split_spline_surface() {
+    let surface = bumpy_surface();
+    for t in [0.4, 1.0 / 3.0] {
+      let (left, right) = surface.split_u(t).unwrap();
+      let (bottom, top) = surface.split_v(t).unwrap();
+      for i in 0..=4 {
+        for j in 0..=4 {
+          let (s, r) = (i as f64 / 4.0, j as f64 / 4.0);
+          almost_eq!(left.sample(s, r), surface.sample(s * t, r));
+          almost_eq!(right.sample(s, r), surface.sample(t + s * (1.0 - t), r));
+          almost_eq!(bottom.sample(s, r), surface.sample(s, r * t));
+          almost_eq!(top.sample(s, r), surface.sample(s, t + r * (1.0 - t)));
+        }
+      }
+    }
+    assert!(surface.split_u(0.0).is_none());
+    assert!(surface.split_v(1.0).is_none());
+  }
+
 }
@@ -39,6 +39,27 @@ pub enum CurveSurfaceIntersectionType {
 }
 
 impl CurveSurfaceIntersectionType {
+  pub fn new(isect: CurveSurfaceIntersection) -> Self {
+    Self::new_closed(isect, false)
+  }
+
+  // Closed curves wrap around their seam, which they cross instead of piercing
+  fn new_closed(mut isect: CurveSurfaceIntersection, closed: bool) -> Self {
+    if closed {
+      isect.t = isect.t.rem_euclid(1.0);
+      if isect.t.almost(1.0) { isect.t = 0.0 }
+    }
+    if is_between(isect.t, 0.0, 1.0) {
+      if !closed && (isect.t.almost(0.0) || isect.t.almost(1.0)) {
+        Self::Pierce(isect)
+      } else {
+        Self::Cross(isect)
+      }
+    } else {
+      Self::Extended(isect)
+    }
+  }
+
   pub fn get_intersection(&self, include_extended: bool) -> Option<&CurveSurfaceIntersection> {
     match self {
       Self::Contained
@@ -66,27 +87,24 @@ impl CurveSurfaceIntersectionType {
 /// Geometric intersection between a curve and a surface.
 /// * `point` - Point of intersection
 /// * `t` - Parameter on the curve
+/// * `u` - First parameter on the surface
+/// * `v` - Second parameter on the surface
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CurveSurfaceIntersection {
   pub point: Point3,
   pub t: f64,
-  // pub u: f64,
-  // pub v: f64,
+  pub u: f64,
+  pub v: f64,
 }
 
 impl CurveSurfaceIntersection {
-  pub fn new(point: Point3, t: f64) -> Self {
-    Self {
-      point,
-      t,
-      // u, v,
-    }
+  pub fn new(point: Point3, t: f64, u: f64, v: f64) -> Self {
+    Self { point, t, u, v }
   }
 }
 
 
-#[allow(dead_code)]
 pub fn line_plane(line: &Line, plane: &Plane) -> Option<CurveSurfaceIntersectionType> {
   let n = plane.normal();
   let u = line.points.1 - line.points.0;
@@ -100,22 +118,212 @@ pub fn line_plane(line: &Line, plane: &Plane) -> Option<CurveSurfaceIntersection
       None
     }
   } else {
+    // Parameters outside the segment hit the plane with the ray along the given line
     let s = n.dot(plane.origin - line.points.0) / n_dot_u;
     let p = line.points.0 + u * s;
-    if is_between(s, 0.0, 1.0) {
-      // Line segment intersects plane
-      if s.almost(0.0) || s.almost(1.0) {
-        Some(CurveSurfaceIntersectionType::Pierce(CurveSurfaceIntersection::new(p, s)))
-      } else {
-        Some(CurveSurfaceIntersectionType::Cross(CurveSurfaceIntersection::new(p, s)))
-      }
-    } else {
-      // The ray along the given line intersects plane
-      Some(CurveSurfaceIntersectionType::Extended(CurveSurfaceIntersection::new(p, s)))
+    let Point2 { x, y } = plane.unsample(p);
+    Some(CurveSurfaceIntersectionType::new(CurveSurfaceIntersection::new(p, s, x, y)))
+  }
+}
+
+
+pub fn circle_plane(circle: &Circle, plane: &Plane) -> Vec<CurveSurfaceIntersectionType> {
+  let n = plane.normal();
+  // Height above the plane is offset + a * cos(angle) + b * sin(angle)
+  let offset = n.dot(circle.plane.origin - plane.origin);
+  let a = n.dot(circle.plane.u) * circle.radius;
+  let b = n.dot(circle.plane.v) * circle.radius;
+  let amplitude = (a * a + b * b).sqrt();
+  if amplitude.almost(0.0) {
+    // Circle is parallel to plane
+    return if offset.almost(0.0) { vec![CurveSurfaceIntersectionType::Contained] } else { vec![] }
+  }
+  let ratio = -offset / amplitude;
+  if ratio.abs() > 1.0 && !ratio.abs().almost(1.0) { return vec![] }
+  let phase = b.atan2(a);
+  let spread = ratio.clamp(-1.0, 1.0).acos();
+  let angles = if spread.almost(0.0) { vec![phase] } else { vec![phase - spread, phase + spread] };
+  angles.into_iter().map(|angle| {
+    let t = angle / std::f64::consts::TAU;
+    let p = circle.sample(t);
+    let Point2 { x, y } = plane.unsample(p);
+    CurveSurfaceIntersectionType::new_closed(CurveSurfaceIntersection::new(p, t, x, y), true)
+  }).collect()
+}
+
+
+pub fn arc_plane(arc: &Arc, plane: &Plane) -> Vec<CurveSurfaceIntersectionType> {
+  let circle = Circle::from_plane(arc.plane.clone(), arc.radius);
+  circle_plane(&circle, plane).into_iter().map(|intersection| match intersection {
+    CurveSurfaceIntersectionType::Contained
+    => intersection,
+
+    CurveSurfaceIntersectionType::Pierce(mut isect)
+    | CurveSurfaceIntersectionType::Cross(mut isect)
+    | CurveSurfaceIntersectionType::Extended(mut isect)
+    => {
+      isect.t = arc.unsample(isect.point);
+      CurveSurfaceIntersectionType::new(isect)
+    },
+  }).collect()
+}
+
+
+// Analytic intersections of lines with cylinders, cones and spheres
+// Returns None for generators that don't form quadrics
+pub fn line_revolution(line: &Line, surface: &RevolutionSurface) -> Option<Vec<CurveSurfaceIntersectionType>> {
+  let to_local = surface.axis.as_transform().invert().unwrap();
+  let origin = to_local.transform_point(line.points.0);
+  let direction = to_local.transform_vector(line.points.1 - line.points.0);
+  let roots = match &surface.curve.base {
+    CurveType::Line(generator) => {
+      let (bottom, top) = generator.points;
+      if (top.z - bottom.z).almost(0.0) { return None }
+      // Radius changes linearly along the axis, x² + y² = (radius + slope * z)²
+      let slope = (top.x - bottom.x) / (top.z - bottom.z);
+      let radius = bottom.x - slope * bottom.z;
+      let start_radius = radius + slope * origin.z;
+      let radius_change = slope * direction.z;
+      quadratic_roots(
+        direction.x * direction.x + direction.y * direction.y - radius_change * radius_change,
+        2.0 * (origin.x * direction.x + origin.y * direction.y - start_radius * radius_change),
+        origin.x * origin.x + origin.y * origin.y - start_radius * start_radius,
+      ).map(|roots| roots.into_iter()
+        // Ignore the mirrored nappe of cones
+        .filter(|s| start_radius + radius_change * s >= -EPSILON )
+        .collect::<Vec<f64>>()
+      )
+    },
+    CurveType::Arc(Arc { plane, radius, .. })
+    | CurveType::Circle(Circle { plane, radius, .. })
+    => {
+      // Circles centered on the axis revolve into spheres
+      if !plane.origin.x.almost(0.0) || !plane.origin.y.almost(0.0) { return None }
+      let offset = origin - plane.origin;
+      quadratic_roots(
+        direction.dot(direction),
+        2.0 * offset.dot(direction),
+        offset.dot(offset) - radius * radius,
+      )
+    },
+    CurveType::Spline(_) | CurveType::Ellipse(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => return None,
+  };
+  Some(match roots {
+    Some(roots) => roots.into_iter().map(|s| {
+      let p = line.sample(s);
+      let (u, v) = surface.unsample(p);
+      CurveSurfaceIntersectionType::new(CurveSurfaceIntersection::new(p, s, u, v))
+    }).collect(),
+    None => vec![CurveSurfaceIntersectionType::Contained],
+  })
+}
+
+// Real roots of a * x² + b * x + c, or None if every value is a root
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Option<Vec<f64>> {
+  if a.almost(0.0) {
+    if b.almost(0.0) {
+      return if c.almost(0.0) { None } else { Some(vec![]) }
     }
+    return Some(vec![-c / b])
   }
+  let discriminant = b * b - 4.0 * a * c;
+  Some(if discriminant.almost(0.0) {
+    vec![-b / (2.0 * a)]
+  } else if discriminant < 0.0 {
+    vec![]
+  } else {
+    let root = discriminant.sqrt();
+    vec![(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+  })
 }
 
+// Number of samples along curves, that seed their intersections with arbitrary surfaces
+const CURVE_SAMPLES: usize = 64;
+
+// Intersections of arbitrary curves and surfaces
+// Samples close to the surface are refined by Newton iteration on both parameter spaces
+pub fn curve_surface(curve: &CurveType, surface: &SurfaceType) -> Vec<CurveSurfaceIntersectionType> {
+  let hitter = CurveHitter::new(curve, surface);
+  let grid = hitter.patch.grid();
+  let samples: Vec<(f64, Point3)> = (0..=CURVE_SAMPLES).map(|i| {
+    let t = i as f64 / CURVE_SAMPLES as f64;
+    (t, curve.as_curve().sample(t))
+  }).collect();
+  let spacing = samples.windows(2).map(|pair| pair[0].1.distance(pair[1].1) ).fold(0.0, f64::max);
+  let tolerance = if grid.is_empty() { spacing } else { spacing.max(grid_spacing(&grid)) };
+  let mut seeds: Vec<[f64; 3]> = samples.iter().filter_map(|(t, p)| {
+    let params = hitter.patch.params_near(*p, &grid);
+    if hitter.patch.sample(params).distance(*p) > tolerance { return None }
+    Some([*t, params[0], params[1]])
+  }).collect();
+  // Curve lies on the surface if all of its samples do
+  if seeds.len() == samples.len() && seeds.iter().all(|seed| hitter.refine(*seed, true).is_some() ) {
+    return vec![CurveSurfaceIntersectionType::Contained]
+  }
+  // Lines hit surfaces beyond their end points as well
+  if let CurveType::Line(line) = curve {
+    seeds.extend(grid.iter().map(|(params, p)| [line.unsample(*p), params[0], params[1]] ));
+  }
+  let hits: Vec<[f64; 3]> = seeds.into_iter().filter_map(|seed| hitter.refine(seed, false) ).fold(vec![], |mut hits, hit| {
+    let p = curve.as_curve().sample(hit[0]);
+    if !hits.iter().any(|other| curve.as_curve().sample(other[0]).almost(p) ) { hits.push(hit) }
+    hits
+  });
+  let closed = matches!(curve, CurveType::Circle(_) | CurveType::Ellipse(_));
+  let mut intersections: Vec<CurveSurfaceIntersectionType> = hits.into_iter().map(|[t, u, v]| {
+    let [u, v] = hitter.patch.wrap([u, v]);
+    CurveSurfaceIntersectionType::new_closed(CurveSurfaceIntersection::new(curve.as_curve().sample(t), t, u, v), closed)
+  }).collect();
+  intersections.sort_by(|a, b| a.get_intersection(true).unwrap().t.partial_cmp(&b.get_intersection(true).unwrap().t).unwrap() );
+  intersections
+}
+
+// Solves curve(t) = surface(u, v) for the parameters [t, u, v]
+struct CurveHitter<'a> {
+  curve: &'a CurveType,
+  patch: Patch<'a>,
+  bounds: Option<(f64, f64)>,
+}
+
+impl<'a> CurveHitter<'a> {
+  fn new(curve: &'a CurveType, surface: &'a SurfaceType) -> Self {
+    let bounds = match curve {
+      // Lines extend beyond their end points, circles and ellipses wrap around
+      CurveType::Line(_) | CurveType::Circle(_) | CurveType::Ellipse(_) => None,
+      CurveType::Arc(_) | CurveType::Spline(_) | CurveType::EllipticalArc(_) | CurveType::Helix(_) => Some((0.0, 1.0)),
+    };
+    Self { curve, patch: Patch::new(surface), bounds }
+  }
+
+  fn sample(&self, t: f64) -> Point3 {
+    self.curve.as_curve().sample(t)
+  }
+
+  fn derivative(&self, t: f64) -> Vec3 {
+    self.curve.derivative(self.bounds.map_or(t, |(start, end)| t.clamp(start, end) ))
+  }
+
+  // Newton iteration that keeps the curve parameter fixed if asked to
+  fn refine(&self, mut x: [f64; 3], fix_curve: bool) -> Option<[f64; 3]> {
+    for _ in 0..MARCHING_ITERATIONS {
+      if let Some((start, end)) = self.bounds { x[0] = x[0].clamp(start, end) }
+      for i in 0..2 {
+        if let Some((start, end)) = self.patch.bounds[i] { x[i + 1] = x[i + 1].clamp(start, end) }
+      }
+      let residual = self.sample(x[0]) - self.patch.sample([x[1], x[2]]);
+      if residual.magnitude() < MARCHING_TOLERANCE { return Some(x) }
+      let [du, dv] = self.patch.derivatives([x[1], x[2]]);
+      let columns = [if fix_curve { Vec3::zero() } else { self.derivative(x[0]) }, -du, -dv];
+      let normal_matrix = columns.iter().fold(Matrix3::zero(), |acc, column| acc + outer_product(*column, *column) );
+      let damping = Matrix3::identity() * normal_matrix.trace() * MARCHING_DAMPING;
+      let y = (normal_matrix + damping).invert()? * residual;
+      for (param, column) in x.iter_mut().zip(columns) {
+        *param -= column.dot(y);
+      }
+    }
+    None
+  }
+}
 
 pub fn plane_plane(plane: &Plane, other: &Plane) -> Option<SurfaceIntersectionType> {
   let normal = plane.normal();
@@ -136,6 +344,468 @@ pub fn plane_plane(plane: &Plane, other: &Plane) -> Option<SurfaceIntersectionTy
 }
 
 
+// Analytic intersections of planes with cylinders, cones and spheres
+// Returns None for conic sections that can't be represented exactly
+pub fn plane_revolution(plane: &Plane, surface: &RevolutionSurface) -> Option<Vec<SurfaceIntersectionType>> {
+  // Work in the coordinate space of the axis, where the generatrix lies on the XZ plane
+  let to_world = surface.axis.as_transform();
+  let to_local = to_world.invert().unwrap();
+  let origin = to_local.transform_point(plane.origin);
+  let normal = to_local.transform_vector(plane.normal());
+  // Circles are clipped to the heights covered by the generatrix and to the revolved angle
+  let circles = |center: Point3, radius: f64, heights: (f64, f64)| {
+    let local = Circle::from_plane(Plane {
+      origin: center,
+      u: to_local.transform_vector(plane.u),
+      v: to_local.transform_vector(plane.v),
+    }, radius);
+    let world = Plane { origin: to_world.transform_point(center), u: plane.u, v: plane.v };
+    clip_circle(&local, heights, surface.u_bounds).into_iter().map(|range| match range {
+      None => Circle::from_plane(world.clone(), radius).into_enum(),
+      Some((start, end)) => Arc::from_plane(world.clone(), radius, start, end).into_enum(),
+    }).map(SurfaceIntersectionType::Cross).collect::<Vec<_>>()
+  };
+  let revolved = |p: Point3| within_turns(p, surface.u_bounds);
+  let line = |start: Point3, end: Point3| Line::new(to_world.transform_point(start), to_world.transform_point(end)).into_enum();
+  match &surface.curve.base {
+    CurveType::Line(_) => {
+      let (start, end) = surface.curve.bounds;
+      if start.z.almost(end.z) { return None }
+      let heights = (start.z.min(end.z), start.z.max(end.z));
+      let radius_at = |z: f64| start.x + (end.x - start.x) * (z - start.z) / (end.z - start.z);
+      if normal.z.abs().almost(1.0) {
+        // Plane orthogonal to the axis
+        let radius = radius_at(origin.z).abs();
+        if radius.almost(0.0) { return Some(vec![]) }
+        Some(circles(Point3::new(0.0, 0.0, origin.z), radius, heights))
+      } else if normal.z.almost(0.0) {
+        // Plane parallel to the axis
+        let distance = -origin.to_vec().dot(normal);
+        let foot = Point3::origin() - normal * distance;
+        let side = Vec3::unit_z().cross(normal).normalize();
+        let ruling = |offset: Vec3, radius: (f64, f64)| line(
+          foot + offset * radius.0 + Vec3::unit_z() * start.z,
+          foot + offset * radius.1 + Vec3::unit_z() * end.z,
+        );
+        // Rulings only exist within the revolved angle
+        let rulings = |sides: Vec<Vec3>, radius: (f64, f64), touch: bool| sides.into_iter()
+          .filter(|side| revolved(foot + side * (radius.0 + radius.1)) )
+          .map(|side| if touch {
+            SurfaceIntersectionType::Touch(ruling(side, radius))
+          } else {
+            SurfaceIntersectionType::Cross(ruling(side, radius))
+          }).collect::<Vec<_>>();
+        if start.x.almost(end.x) {
+          // Cylinders are cut along two rulings, or touched along one
+          let radius = start.x.abs();
+          if distance.abs() > radius + EPSILON { return Some(vec![]) }
+          let half_chord = (radius * radius - distance * distance).max(0.0).sqrt();
+          if half_chord.almost(0.0) {
+            return Some(rulings(vec![side], (0.0, 0.0), true))
+          }
+          Some(rulings(vec![side, -side], (half_chord, half_chord), false))
+        } else if distance.almost(0.0) {
+          // Cones are cut along two rulings by planes that contain the axis
+          Some(rulings(vec![side, -side], (start.x, end.x), false))
+        } else { None }
+      } else { None }
+    },
+    // Spheres are generated by circles around a point on the axis
+    CurveType::Arc(Arc { plane: circle_plane, radius, .. })
+    | CurveType::Circle(Circle { plane: circle_plane, radius, .. })
+    if circle_plane.origin.x.almost(0.0) && circle_plane.origin.y.almost(0.0) => {
+      let center = circle_plane.origin;
+      let distance = (center - origin).dot(normal);
+      if distance.abs() >= *radius - EPSILON { return Some(vec![]) }
+      //XXX Assumes that arcs run from one pole towards the other without turning back
+      let heights = if let CurveType::Circle(_) = surface.curve.base {
+        (center.z - radius, center.z + radius)
+      } else {
+        let (start, end) = surface.curve.bounds;
+        (start.z.min(end.z), start.z.max(end.z))
+      };
+      Some(circles(center - normal * distance, (radius * radius - distance * distance).sqrt(), heights))
+    },
+    _ => None,
+  }
+}
+
+// Number of samples along generatrices, that are compared to find coincident surfaces of revolution
+const GENERATRIX_SAMPLES: usize = 8;
+
+// Coincident surfaces of revolution, which share their axis and generatrix
+// Returns None for all other pairs, whose intersections have to be marched
+pub fn revolution_revolution(surface: &RevolutionSurface, other: &RevolutionSurface) -> Option<Vec<SurfaceIntersectionType>> {
+  let direction = surface.axis.direction.normalize();
+  let offset = other.axis.origin - surface.axis.origin;
+  let is_coaxial = direction.cross(other.axis.direction.normalize()).magnitude().almost(0.0) &&
+    (offset - direction * offset.dot(direction)).magnitude().almost(0.0);
+  if is_coaxial && (generatrix_on(other, surface) || generatrix_on(surface, other)) {
+    Some(vec![SurfaceIntersectionType::Contained])
+  } else {
+    None
+  }
+}
+
+// Whether the generatrix of a surface, rotated into the half plane of the other generatrix, lies on it.
+// Straight generatrices are extended, so that overlapping cylinders and cones are found as well.
+fn generatrix_on(surface: &RevolutionSurface, other: &RevolutionSurface) -> bool {
+  let to_world = surface.axis.as_transform();
+  let to_local = other.axis.as_transform().invert().unwrap();
+  let profile = |p: Point3| Point3::new(p.x.hypot(p.y), 0.0, p.z);
+  (0..=GENERATRIX_SAMPLES).all(|i| {
+    let p = surface.curve.sample(i as f64 / GENERATRIX_SAMPLES as f64);
+    let p = profile(to_local.transform_point(to_world.transform_point(p)));
+    if let CurveType::Line(line) = &other.curve.base {
+      let start = profile(line.points.0);
+      let direction = (profile(line.points.1) - start).normalize();
+      let offset = p - start;
+      (offset - direction * offset.dot(direction)).magnitude().almost(0.0)
+    } else {
+      other.curve.contains_point(p)
+    }
+  })
+}
+
+// Whether a point in the local space of a revolution surface lies within its revolved angle
+fn within_turns(p: Point3, u_bounds: (f64, f64)) -> bool {
+  let span = u_bounds.1 - u_bounds.0;
+  let turn = p.y.atan2(p.x) / std::f64::consts::TAU;
+  span >= 1.0 - EPSILON || (turn - u_bounds.0 + EPSILON).rem_euclid(1.0) <= span + 2.0 * EPSILON
+}
+
+// Parameter ranges of a circle in the local space of a revolution surface that lie between
+// the given heights and within the revolved angle, with None standing for the whole circle
+fn clip_circle(circle: &Circle, heights: (f64, f64), u_bounds: (f64, f64)) -> Vec<Option<(f64, f64)>> {
+  let inside = |t: f64| {
+    let p = circle.sample(t);
+    p.z > heights.0 - EPSILON && p.z < heights.1 + EPSILON && within_turns(p, u_bounds)
+  };
+  let mut planes = vec![
+    Plane::from_normal(Point3::new(0.0, 0.0, heights.0), Vec3::unit_z()),
+    Plane::from_normal(Point3::new(0.0, 0.0, heights.1), Vec3::unit_z()),
+  ];
+  if u_bounds.1 - u_bounds.0 < 1.0 - EPSILON {
+    planes.extend([u_bounds.0, u_bounds.1].map(|turn| {
+      let angle = turn * std::f64::consts::TAU;
+      Plane::from_normal(Point3::origin(), Vec3::new(-angle.sin(), angle.cos(), 0.0))
+    }));
+  }
+  let mut cuts: Vec<f64> = planes.iter()
+    .flat_map(|plane| circle_plane(circle, plane) )
+    .filter_map(|isect| isect.get_intersection(false).map(|hit| hit.t ) )
+    .collect();
+  cuts.sort_by(|a, b| a.partial_cmp(b).unwrap() );
+  cuts.dedup_by(|a, b| a.almost(*b) );
+  if cuts.is_empty() {
+    return if inside(0.0) { vec![None] } else { vec![] }
+  }
+  // Keep the pieces between consecutive cuts that lie inside, joining neighbors
+  let mut ranges: Vec<(f64, f64)> = vec![];
+  for (i, &start) in cuts.iter().enumerate() {
+    let end = if i + 1 < cuts.len() { cuts[i + 1] } else { cuts[0] + 1.0 };
+    if !inside((start + end) / 2.0) { continue }
+    match ranges.last_mut() {
+      Some(last) if last.1.almost(start) => last.1 = end,
+      _ => ranges.push((start, end)),
+    }
+  }
+  if ranges.len() > 1 && ranges.last().unwrap().1.almost(ranges[0].0 + 1.0) {
+    let last = ranges.pop().unwrap();
+    ranges[0].0 = last.0 - 1.0;
+  }
+  if let [range] = ranges[..] {
+    if (range.1 - range.0).almost(1.0) { return vec![None] }
+  }
+  ranges.into_iter().map(Some).collect()
+}
+
+
+// Resolution of the parameter grid that seeds marching along intersection curves
+const MARCHING_GRID: usize = 16;
+
+// Number of marching steps that span the extent of the smaller surface
+const MARCHING_RESOLUTION: f64 = 64.0;
+
+// Upper limit for the number of points along a single intersection curve
+const MAX_MARCHING_STEPS: usize = 4096;
+
+// Maximum number of Newton iterations used to pull points onto both surfaces
+const MARCHING_ITERATIONS: usize = 20;
+
+// Maximum distance between both surfaces at points of an intersection curve
+const MARCHING_TOLERANCE: f64 = 1.0e-10;
+
+// Relative damping of the Newton iteration, for points where several parameters are fixed
+const MARCHING_DAMPING: f64 = 1.0e-12;
+
+// Maximum number of points that intersection splines are interpolated through
+const MAX_FIT_POINTS: usize = 33;
+
+// Intersection curves of arbitrary surfaces, approximated by splines
+// The curves are traced by marching along them from seed points of a parameter grid
+pub fn march(surface: &SurfaceType, other: &SurfaceType) -> Vec<SurfaceIntersectionType> {
+  let patches = [Patch::new(surface), Patch::new(other)];
+  let grids = [patches[0].grid(), patches[1].grid()];
+  let extent = grids.iter().filter(|grid| !grid.is_empty() ).map(|grid| grid_extent(grid) ).fold(f64::MAX, f64::min);
+  if extent == f64::MAX || extent.almost(0.0) { return vec![] }
+  let marcher = Marcher { patches, step: extent / MARCHING_RESOLUTION };
+  let curves: Vec<Vec<Point3>> = marcher.seeds(&grids).into_iter().fold(vec![], |mut curves, seed| {
+    let p = marcher.point(&seed);
+    if !curves.iter().flatten().any(|q| q.distance(p) < marcher.step ) {
+      curves.push(marcher.trace(seed).iter().map(|x| marcher.point(x) ).collect());
+    }
+    curves
+  });
+  curves.into_iter()
+    .filter(|points| points.len() > 1 )
+    .map(|points| SurfaceIntersectionType::Cross(fit_spline(&points).into_enum()) )
+    .collect()
+}
+
+// Surface together with the parameter ranges it isn't defined beyond
+struct Patch<'a> {
+  surface: &'a SurfaceType,
+  bounds: [Option<(f64, f64)>; 2],
+}
+
+impl<'a> Patch<'a> {
+  fn new(surface: &'a SurfaceType) -> Self {
+    let bounds = match surface {
+      SurfaceType::Planar(_) => [None, None],
+      // Full revolutions can be followed around their axis
+      SurfaceType::Revolution(surf) => [
+        if (surf.u_bounds.1 - surf.u_bounds.0).abs() >= 1.0 - EPSILON { None } else { Some((0.0, 1.0)) },
+        Some((0.0, 1.0)),
+      ],
+      SurfaceType::Spline(_) => [Some((0.0, 1.0)), Some((0.0, 1.0))],
+    };
+    Self { surface, bounds }
+  }
+
+  fn sample(&self, params: [f64; 2]) -> Point3 {
+    self.surface.as_surface().sample(params[0], params[1])
+  }
+
+  fn derivatives(&self, params: [f64; 2]) -> [Vec3; 2] {
+    let [u, v] = [0, 1].map(|i| self.bounds[i].map_or(params[i], |(start, end)| params[i].clamp(start, end) ) );
+    let (du, dv) = self.surface.derivatives(u, v);
+    [du, dv]
+  }
+
+  // Samples at uniform parameters, which planes don't have as they are unbounded
+  fn grid(&self) -> Vec<([f64; 2], Point3)> {
+    if let SurfaceType::Planar(_) = self.surface { return vec![] }
+    (0..=MARCHING_GRID).flat_map(|j| (0..=MARCHING_GRID).map(move |i| [i as f64 / MARCHING_GRID as f64, j as f64 / MARCHING_GRID as f64] ) )
+      .map(|params| (params, self.sample(params)) )
+      .collect()
+  }
+
+  // Move parameters of full revolutions back into their natural range
+  fn wrap(&self, params: [f64; 2]) -> [f64; 2] {
+    match (self.surface, self.bounds[0]) {
+      (SurfaceType::Revolution(_), None) => [params[0].rem_euclid(1.0), params[1]],
+      _ => params,
+    }
+  }
+
+  // Parameters of the closest grid point, or of the projection onto planes
+  fn params_near(&self, p: Point3, grid: &[([f64; 2], Point3)]) -> [f64; 2] {
+    if let SurfaceType::Planar(plane) = self.surface {
+      let (u, v) = plane.unsample(p);
+      return [u, v]
+    }
+    grid.iter().min_by(|a, b| a.1.distance2(p).partial_cmp(&b.1.distance2(p)).unwrap() ).unwrap().0
+  }
+}
+
+// Largest distance between neighbouring grid points
+fn grid_spacing(grid: &[([f64; 2], Point3)]) -> f64 {
+  let width = MARCHING_GRID + 1;
+  (0..grid.len()).flat_map(|i| [i + 1, i + width].into_iter()
+    .filter(move |&j| j < grid.len() && (j == i + width || j % width != 0) )
+    .map(move |j| grid[i].1.distance(grid[j].1) )
+  ).fold(0.0, f64::max)
+}
+
+// Diagonal of the bounding box of a grid
+fn grid_extent(grid: &[([f64; 2], Point3)]) -> f64 {
+  let (min, max) = grid.iter().fold((grid[0].1, grid[0].1), |(min, max), (_, p)| (
+    Point3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+    Point3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+  ));
+  min.distance(max)
+}
+
+// Points on intersection curves are represented by their parameters on both surfaces
+type Params = [f64; 4];
+
+struct Marcher<'a> {
+  patches: [Patch<'a>; 2],
+  step: f64,
+}
+
+impl<'a> Marcher<'a> {
+  fn point(&self, x: &Params) -> Point3 {
+    self.patches[0].sample([x[0], x[1]])
+  }
+
+  fn residual(&self, x: &Params) -> Vec3 {
+    self.patches[0].sample([x[0], x[1]]) - self.patches[1].sample([x[2], x[3]])
+  }
+
+  // Derivatives of the residual with respect to all parameters
+  fn columns(&self, x: &Params) -> [Vec3; 4] {
+    let [du1, dv1] = self.patches[0].derivatives([x[0], x[1]]);
+    let [du2, dv2] = self.patches[1].derivatives([x[2], x[3]]);
+    [du1, dv1, -du2, -dv2]
+  }
+
+  fn bounds(&self, i: usize) -> Option<(f64, f64)> {
+    self.patches[i / 2].bounds[i % 2]
+  }
+
+  // Clamp parameters to their ranges, returning those that were clamped
+  fn clamp(&self, x: &mut Params) -> [bool; 4] {
+    let mut clamped = [false; 4];
+    for (i, is_clamped) in clamped.iter_mut().enumerate() {
+      if let Some((start, end)) = self.bounds(i) {
+        *is_clamped = x[i] <= start || x[i] >= end;
+        x[i] = x[i].clamp(start, end);
+      }
+    }
+    clamped
+  }
+
+  // Minimum norm Newton iteration onto both surfaces
+  // Parameters that reach the end of their range stay fixed there
+  fn correct(&self, mut x: Params) -> Option<(Params, bool)> {
+    let mut fixed = self.clamp(&mut x);
+    for _ in 0..MARCHING_ITERATIONS {
+      let residual = self.residual(&x);
+      if residual.magnitude() < MARCHING_TOLERANCE {
+        return Some((x, fixed.iter().any(|is_fixed| *is_fixed )))
+      }
+      let mut columns = self.columns(&x);
+      for (column, is_fixed) in columns.iter_mut().zip(fixed) {
+        if is_fixed { *column = Vec3::zero() }
+      }
+      // Slight damping keeps the system solvable when fixed parameters leave too few directions
+      let normal_matrix = columns.iter().fold(Matrix3::zero(), |acc, column| acc + outer_product(*column, *column) );
+      let damping = Matrix3::identity() * normal_matrix.trace() * MARCHING_DAMPING;
+      let y = (normal_matrix + damping).invert()? * residual;
+      for (param, column) in x.iter_mut().zip(columns) {
+        *param -= column.dot(y);
+      }
+      for (is_fixed, is_clamped) in fixed.iter_mut().zip(self.clamp(&mut x)) {
+        *is_fixed |= is_clamped;
+      }
+    }
+    None
+  }
+
+  // Find points on the intersection near pairs of close grid points
+  fn seeds(&self, grids: &[Vec<([f64; 2], Point3)>; 2]) -> Vec<Params> {
+    let (from, to) = if grids[0].is_empty() { (1, 0) } else { (0, 1) };
+    let mut tolerance = grid_spacing(&grids[from]);
+    if !grids[to].is_empty() { tolerance = tolerance.max(grid_spacing(&grids[to])) }
+    grids[from].iter().filter_map(|(params, p)| {
+      let other = self.patches[to].params_near(*p, &grids[to]);
+      if self.patches[to].sample(other).distance(*p) > tolerance { return None }
+      let x = if from == 0 {
+        [params[0], params[1], other[0], other[1]]
+      } else {
+        [other[0], other[1], params[0], params[1]]
+      };
+      self.correct(x).map(|(x, _)| x )
+    }).collect()
+  }
+
+  // Follow the intersection through the seed in both directions, unless it closes on itself
+  fn trace(&self, seed: Params) -> Vec<Params> {
+    let (forward, is_closed) = self.walk(seed, 1.0);
+    if is_closed { return forward }
+    let (backward, _) = self.walk(seed, -1.0);
+    backward.into_iter().rev().chain(forward.into_iter().skip(1)).collect()
+  }
+
+  // March along the intersection until it leaves a surface or returns to the start
+  fn walk(&self, start: Params, direction: f64) -> (Vec<Params>, bool) {
+    let mut points = vec![start];
+    let mut x = start;
+    let mut heading: Option<Vec3> = None;
+    for _ in 0..MAX_MARCHING_STEPS {
+      let columns = self.columns(&x);
+      let tangent = columns[0].cross(columns[1]).cross(columns[2].cross(columns[3]));
+      // Surfaces touching each other have no unique direction
+      if tangent.magnitude() < EPSILON { break }
+      let mut tangent = tangent.normalize() * direction;
+      if let Some(heading) = heading {
+        if tangent.dot(heading) < 0.0 { tangent = -tangent }
+      }
+      // Predict parameters that move both surfaces along the tangent
+      let delta = [
+        solve_tangential(columns[0], columns[1], tangent * self.step),
+        solve_tangential(-columns[2], -columns[3], tangent * self.step),
+      ];
+      let predicted = [x[0] + delta[0].0, x[1] + delta[0].1, x[2] + delta[1].0, x[3] + delta[1].1];
+      let (next, is_boundary) = match self.correct(predicted) {
+        Some(result) => result,
+        None => break,
+      };
+      let offset = self.point(&next) - self.point(&x);
+      if offset.magnitude() < self.step * 0.1 { break }
+      heading = Some(offset);
+      points.push(next);
+      if is_boundary { break }
+      if points.len() > 3 && self.point(&next).distance(self.point(&start)) < self.step * 0.75 {
+        *points.last_mut().unwrap() = start;
+        return (points, true)
+      }
+      x = next;
+    }
+    (points, false)
+  }
+}
+
+// Least squares parameter step along two derivatives, that moves by the given vector
+fn solve_tangential(du: Vec3, dv: Vec3, vec: Vec3) -> (f64, f64) {
+  let (a, b, c) = (du.dot(du), du.dot(dv), dv.dot(dv));
+  let det = a * c - b * b;
+  if det.abs() < f64::EPSILON { return (0.0, 0.0) }
+  let (rhs_u, rhs_v) = (du.dot(vec), dv.dot(vec));
+  ((c * rhs_u - b * rhs_v) / det, (a * rhs_v - b * rhs_u) / det)
+}
+
+fn outer_product(a: Vec3, b: Vec3) -> Matrix3 {
+  Matrix3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+// Cubic spline interpolating evenly spaced points of a marched curve
+// Uses chord length parameters and averaged knots to avoid wiggles
+fn fit_spline(points: &[Point3]) -> Spline {
+  let count = points.len().min(MAX_FIT_POINTS);
+  let points: Vec<Point3> = (0..count).map(|i| points[(i * (points.len() - 1) + (count - 1) / 2) / (count - 1).max(1)] ).collect();
+  let degree = (count - 1).min(3);
+  let lengths: Vec<f64> = points.windows(2).map(|pair| pair[0].distance(pair[1]) ).collect();
+  let total: f64 = lengths.iter().sum();
+  let params: Vec<f64> = std::iter::once(0.0).chain(lengths.iter().scan(0.0, |length, delta| {
+    *length += delta;
+    Some(*length / total)
+  })).collect();
+  let knots = [
+    vec![0.0; degree + 1],
+    (1..count - degree).map(|j| params[j..j + degree].iter().sum::<f64>() / degree as f64 ).collect(),
+    vec![1.0; degree + 1],
+  ].concat();
+  let matrix = params.iter().map(|&t| SplineSurface::get_basis_function(degree, t, &knots) ).collect();
+  let rows = points.iter().map(|p| vec![p.to_vec().extend(1.0)] ).collect();
+  let mut spline = Spline::new(SplineSurface::solve_controls(matrix, rows).into_iter().map(|row| Point3::from_vec(row[0].truncate() / row[0].w) ).collect());
+  spline.degree = degree;
+  spline.knots = knots;
+  spline
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -154,4 +824,286 @@ mod tests {
       } else { panic!("Intersection was no line") }
     } else { panic!("No intersection detected") }
   }
+
+  fn cylinder() -> RevolutionSurface {
+    RevolutionSurface::cylinder(Axis::new(Point3::origin(), Vec3::unit_z()), 1.0, 2.0)
+  }
+
+  fn curve_points(isect: &SurfaceIntersectionType) -> Vec<Point3> {
+    match isect {
+      SurfaceIntersectionType::Cross(curve) | SurfaceIntersectionType::Touch(curve) => (0..=10).map(|i| curve.as_curve().sample(i as f64 / 10.0) ).collect(),
+      _ => panic!("Intersection was no curve"),
+    }
+  }
+
+  #[test]
+  fn plane_cylinder() {
+    let cylinder = cylinder();
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 0.5), Vec3::unit_z()), &cylinder).unwrap();
+    assert_eq!(isects.len(), 1);
+    if let SurfaceIntersectionType::Cross(CurveType::Circle(circle)) = &isects[0] {
+      almost_eq!(circle.plane.origin, Point3::new(0.0, 0.0, 0.5));
+      almost_eq!(circle.radius, 1.0);
+    } else { panic!("Intersection was no circle") }
+    // Rulings at both sides of the axis
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.5, 0.0, 0.0), Vec3::unit_x()), &cylinder).unwrap();
+    assert_eq!(isects.len(), 2);
+    for isect in &isects {
+      let line = isect.get_line().unwrap();
+      almost_eq!(line.points.0.x, 0.5);
+      almost_eq!(line.points.0.y.abs(), 0.75f64.sqrt());
+      almost_eq!(line.points.1.z - line.points.0.z, 2.0);
+    }
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(1.0, 0.0, 0.0), Vec3::unit_x()), &cylinder).unwrap();
+    assert!(matches!(isects[..], [SurfaceIntersectionType::Touch(_)]));
+    assert!(plane_revolution(&Plane::from_normal(Point3::new(2.0, 0.0, 0.0), Vec3::unit_x()), &cylinder).unwrap().is_empty());
+    // Oblique planes cut ellipses
+    assert!(plane_revolution(&Plane::from_normal(Point3::origin(), Vec3::new(1.0, 0.0, 1.0).normalize()), &cylinder).is_none());
+  }
+
+  #[test]
+  fn plane_cone() {
+    let cone = RevolutionSurface::cone(Axis::new(Point3::new(1.0, 0.0, 0.0), Vec3::unit_z()), 1.0, 0.5, 1.0);
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 0.5), Vec3::unit_z()), &cone).unwrap();
+    if let [SurfaceIntersectionType::Cross(CurveType::Circle(circle))] = &isects[..] {
+      almost_eq!(circle.plane.origin, Point3::new(1.0, 0.0, 0.5));
+      almost_eq!(circle.radius, 0.75);
+    } else { panic!("Intersection was no circle") }
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(1.0, 0.0, 0.0), Vec3::unit_y()), &cone).unwrap();
+    assert_eq!(isects.len(), 2);
+    for isect in &isects {
+      let line = isect.get_line().unwrap();
+      almost_eq!((line.points.0.x - 1.0).abs(), 1.0);
+      almost_eq!((line.points.1.x - 1.0).abs(), 0.5);
+    }
+  }
+
+  #[test]
+  fn plane_sphere() {
+    let arc = Arc::from_plane(Plane { origin: Point3::origin(), u: -Vec3::unit_z(), v: Vec3::unit_x() }, 1.0, 0.0, 0.5);
+    let sphere = RevolutionSurface { axis: Axis::new(Point3::new(0.0, 0.0, 1.0), Vec3::unit_z()), curve: TrimmedCurve::new(arc.into_enum()), u_bounds: (0.0, 1.0) };
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 1.5), Vec3::unit_z()), &sphere).unwrap();
+    if let [SurfaceIntersectionType::Cross(CurveType::Circle(circle))] = &isects[..] {
+      almost_eq!(circle.plane.origin, Point3::new(0.0, 0.0, 1.5));
+      almost_eq!(circle.radius, 0.75f64.sqrt());
+    } else { panic!("Intersection was no circle") }
+    assert!(plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 2.5), Vec3::unit_z()), &sphere).unwrap().is_empty());
+  }
+
+  #[test]
+  fn plane_revolution_bounds() {
+    // Planes beyond the ends of the generatrix miss the cylinder
+    let cylinder = cylinder();
+    assert!(plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 2.5), Vec3::unit_z()), &cylinder).unwrap().is_empty());
+    assert!(plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, -0.5), Vec3::unit_z()), &cylinder).unwrap().is_empty());
+    // Half cylinder is cut along an arc and a single ruling
+    let half = RevolutionSurface { u_bounds: (0.0, 0.5), ..cylinder };
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, 0.5), Vec3::unit_z()), &half).unwrap();
+    if let [SurfaceIntersectionType::Cross(CurveType::Arc(arc))] = &isects[..] {
+      almost_eq!(arc.sample(0.0), Point3::new(1.0, 0.0, 0.5));
+      almost_eq!(arc.sample(0.5), Point3::new(0.0, 1.0, 0.5));
+      almost_eq!(arc.sample(1.0), Point3::new(-1.0, 0.0, 0.5));
+    } else { panic!("Intersection was no arc {:?}", isects) }
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.5, 0.0, 0.0), Vec3::unit_x()), &half).unwrap();
+    assert_eq!(isects.len(), 1);
+    almost_eq!(isects[0].get_line().unwrap().points.0.y, 0.75f64.sqrt());
+    // Hemisphere is cut along half a circle by planes parallel to its axis
+    let arc = Arc::from_plane(Plane { origin: Point3::origin(), u: -Vec3::unit_z(), v: Vec3::unit_x() }, 1.0, 0.25, 0.5);
+    let dome = RevolutionSurface { axis: Axis::new(Point3::origin(), Vec3::unit_z()), curve: TrimmedCurve::new(arc.into_enum()), u_bounds: (0.0, 1.0) };
+    let isects = plane_revolution(&Plane::from_normal(Point3::new(0.5, 0.0, 0.0), Vec3::unit_x()), &dome).unwrap();
+    if let [SurfaceIntersectionType::Cross(curve)] = &isects[..] {
+      assert!(matches!(curve, CurveType::Arc(_)));
+      let points: Vec<Point3> = (0..=10).map(|i| curve.as_curve().sample(i as f64 / 10.0) ).collect();
+      assert!(points.iter().all(|p| p.z > -EPSILON && p.x.almost(0.5) ));
+      almost_eq!(points[0].z, 0.0);
+      almost_eq!(points[10].z, 0.0);
+      almost_eq!(points[5].z, 0.75f64.sqrt());
+    } else { panic!("Intersection was no arc {:?}", isects) }
+    assert!(plane_revolution(&Plane::from_normal(Point3::new(0.0, 0.0, -0.5), Vec3::unit_z()), &dome).unwrap().is_empty());
+  }
+
+  #[test]
+  fn coincident_revolutions() {
+    // Stacked cylinders of equal radius overlap, even with opposite axes
+    let cylinder = cylinder();
+    let stacked = RevolutionSurface::cylinder(Axis::new(Point3::new(0.0, 0.0, 3.0), -Vec3::unit_z()), 1.0, 2.0);
+    assert_eq!(revolution_revolution(&cylinder, &stacked).unwrap(), vec![SurfaceIntersectionType::Contained]);
+    let sphere = |radius: f64| {
+      let arc = Arc::from_plane(Plane { origin: Point3::origin(), u: -Vec3::unit_z(), v: Vec3::unit_x() }, radius, 0.0, 0.5);
+      RevolutionSurface { axis: Axis::new(Point3::new(0.0, 0.0, 1.0), Vec3::unit_z()), curve: TrimmedCurve::new(arc.into_enum()), u_bounds: (0.0, 1.0) }
+    };
+    assert_eq!(revolution_revolution(&sphere(1.0), &sphere(1.0)).unwrap(), vec![SurfaceIntersectionType::Contained]);
+    // Surfaces with different radii or axes need to be marched
+    assert!(revolution_revolution(&sphere(1.0), &sphere(0.5)).is_none());
+    let wider = RevolutionSurface::cylinder(Axis::new(Point3::origin(), Vec3::unit_z()), 1.5, 2.0);
+    assert!(revolution_revolution(&cylinder, &wider).is_none());
+    let shifted = RevolutionSurface::cylinder(Axis::new(Point3::new(1.0, 0.0, 0.0), Vec3::unit_z()), 1.0, 2.0);
+    assert!(revolution_revolution(&cylinder, &shifted).is_none());
+  }
+
+  #[test]
+  fn march_plane_cylinder() {
+    let plane = Plane::from_normal(Point3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 2.0).normalize());
+    let isects = SurfaceType::Planar(PlanarSurface::new(plane.clone())).intersect(&cylinder().into_enum());
+    assert_eq!(isects.len(), 1);
+    let points = curve_points(&isects[0]);
+    // Closed ellipse
+    assert!(points[0].distance(points[10]) < 1.0e-9);
+    for p in points {
+      assert!((p - plane.origin).dot(plane.normal()).abs() < 1.0e-4);
+      assert!(((p.x * p.x + p.y * p.y).sqrt() - 1.0).abs() < 1.0e-4);
+    }
+  }
+
+  #[test]
+  fn march_splines() {
+    // Parabolic cylinder y = x - x^2 / 2, crossed by an inclined sheet z = 0.5 + x / 2
+    let parabola = Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0), Point3::new(2.0, 0.0, 0.0)]);
+    let sheet = Spline::new(vec![Point3::new(0.0, -1.0, 0.5), Point3::new(2.0, -1.0, 1.5)]);
+    let surface = SplineSurface::tabulated(&parabola, Vec3::new(0.0, 0.0, 2.0)).into_enum();
+    let other = SplineSurface::tabulated(&sheet, Vec3::new(0.0, 3.0, 0.0)).into_enum();
+    let isects = surface.intersect(&other);
+    assert_eq!(isects.len(), 1);
+    let points = curve_points(&isects[0]);
+    for p in &points {
+      assert!((p.z - (0.5 + p.x / 2.0)).abs() < 1.0e-4);
+      assert!((p.y - (p.x - p.x * p.x / 2.0)).abs() < 1.0e-4);
+    }
+    // The curve runs across the full width of the parabolic cylinder
+    let (start, end) = (points[0].x.min(points[10].x), points[0].x.max(points[10].x));
+    assert!(start.abs() < 1.0e-6 && (end - 2.0).abs() < 1.0e-6);
+    // Cylinder crossing the sheet along an ellipse
+    let isects = cylinder().into_enum().intersect(&other);
+    assert_eq!(isects.len(), 1);
+    for p in curve_points(&isects[0]) {
+      assert!((p.z - (0.5 + p.x / 2.0)).abs() < 1.0e-4);
+      assert!(((p.x * p.x + p.y * p.y).sqrt() - 1.0).abs() < 1.0e-4);
+    }
+  }
+
+
+  fn get_hits(isects: &[CurveSurfaceIntersectionType]) -> Vec<&CurveSurfaceIntersection> {
+    isects.iter().map(|isect| isect.get_intersection(true).unwrap() ).collect()
+  }
+
+  #[test]
+  fn line_cylinder() {
+    let line = Line::new(Point3::new(-2.0, 0.0, 1.0), Point3::new(2.0, 0.0, 1.0));
+    let isects = line_revolution(&line, &cylinder()).unwrap();
+    assert!(matches!(isects[..], [CurveSurfaceIntersectionType::Cross(_), CurveSurfaceIntersectionType::Cross(_)]));
+    let hits = get_hits(&isects);
+    almost_eq!(hits[0].point, Point3::new(-1.0, 0.0, 1.0));
+    almost_eq!(hits[0].t, 0.25);
+    almost_eq!(hits[0].u, 0.5);
+    almost_eq!(hits[0].v, 0.5);
+    almost_eq!(hits[1].t, 0.75);
+    almost_eq!(hits[1].u, 0.0);
+    // Rulings lie on the cylinder
+    let line = Line::new(Point3::new(0.0, 1.0, 0.0), Point3::new(0.0, 1.0, 1.0));
+    assert_eq!(line_revolution(&line, &cylinder()).unwrap(), vec![CurveSurfaceIntersectionType::Contained]);
+  }
+
+  #[test]
+  fn line_cone() {
+    let cone = RevolutionSurface::cone(Axis::new(Point3::origin(), Vec3::unit_z()), 1.0, 0.5, 1.0);
+    // Line along the x axis reaches the cone beyond its end points
+    let line = Line::new(Point3::new(0.0, 0.0, 0.5), Point3::new(0.5, 0.0, 0.5));
+    almost_eq!(cone.sample(0.0, 0.5), Point3::new(0.75, 0.0, 0.5));
+    let isects = line_revolution(&line, &cone).unwrap();
+    assert!(isects.iter().all(|isect| matches!(isect, CurveSurfaceIntersectionType::Extended(_)) ));
+    let hits = get_hits(&isects);
+    assert_eq!(hits.len(), 2);
+    almost_eq!(hits[0].t, -1.5);
+    almost_eq!(hits[1].t, 1.5);
+    almost_eq!(hits[1].v, 0.5);
+  }
+
+  #[test]
+  fn line_sphere() {
+    let arc = Arc::from_plane(Plane { origin: Point3::origin(), u: -Vec3::unit_z(), v: Vec3::unit_x() }, 1.0, 0.0, 0.5);
+    let sphere = RevolutionSurface { axis: Axis::new(Point3::origin(), Vec3::unit_z()), curve: TrimmedCurve::new(arc.into_enum()), u_bounds: (0.0, 1.0) };
+    let line = Line::new(Point3::new(0.0, -2.0, 0.0), Point3::new(0.0, 2.0, 0.0));
+    let isects = line_revolution(&line, &sphere).unwrap();
+    let hits = get_hits(&isects);
+    almost_eq!(hits[0].point, Point3::new(0.0, -1.0, 0.0));
+    almost_eq!(hits[0].u, 0.75);
+    almost_eq!(hits[0].v, 0.5);
+    almost_eq!(hits[1].point, Point3::new(0.0, 1.0, 0.0));
+    almost_eq!(hits[1].u, 0.25);
+    // Tangent lines touch the sphere once
+    let line = Line::new(Point3::new(-1.0, 0.0, 1.0), Point3::new(1.0, 0.0, 1.0));
+    assert_eq!(line_revolution(&line, &sphere).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn circle_cut_by_plane() {
+    let circle = Circle::new(Point3::origin(), 1.0);
+    let plane = Plane::from_normal(Point3::new(0.5, 0.0, 0.0), Vec3::unit_x());
+    let isects = circle_plane(&circle, &plane);
+    let hits = get_hits(&isects);
+    assert_eq!(hits.len(), 2);
+    for hit in &hits {
+      almost_eq!(hit.point.x, 0.5);
+      almost_eq!(hit.point.y.abs(), 0.75f64.sqrt());
+      almost_eq!(circle.sample(hit.t), hit.point);
+      almost_eq!(plane.sample(hit.u, hit.v), hit.point);
+    }
+    assert!(circle_plane(&circle, &Plane::from_normal(Point3::new(2.0, 0.0, 0.0), Vec3::unit_x())).is_empty());
+    // Hits at the seam of the circle cross the plane
+    let seam = Plane::from_normal(Point3::origin(), Vec3::unit_y());
+    let isects = circle_plane(&circle, &seam);
+    assert!(matches!(isects[..], [CurveSurfaceIntersectionType::Cross(_), CurveSurfaceIntersectionType::Cross(_)]));
+    let isects = circle.clone().into_enum().intersect_surface(&PlanarSurface::new(seam).into_enum());
+    assert!(matches!(isects[..], [CurveSurfaceIntersectionType::Cross(_), CurveSurfaceIntersectionType::Cross(_)]));
+    let hits = get_hits(&isects);
+    almost_eq!(hits[0].t, 0.0);
+    almost_eq!(hits[1].t, 0.5);
+    assert_eq!(circle_plane(&circle, &Plane::default()), vec![CurveSurfaceIntersectionType::Contained]);
+    // Only one of both hits lies on the arc
+    let arc = Arc::new(Point3::origin(), 1.0, 0.0, 0.5);
+    let isects = arc_plane(&arc, &plane);
+    assert_eq!(isects.iter().filter(|isect| matches!(isect, CurveSurfaceIntersectionType::Cross(_)) ).count(), 1);
+    assert_eq!(isects.iter().filter(|isect| matches!(isect, CurveSurfaceIntersectionType::Extended(_)) ).count(), 1);
+  }
+
+  #[test]
+  fn spline_cut_by_plane() {
+    let spline = Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 0.0), Point3::new(2.0, 0.0, 0.0)]);
+    let plane = PlanarSurface::new(Plane::from_normal(Point3::new(0.0, 0.5, 0.0), Vec3::unit_y())).into_enum();
+    let isects = spline.clone().into_enum().intersect_surface(&plane);
+    let hits = get_hits(&isects);
+    assert_eq!(hits.len(), 2);
+    for hit in &hits {
+      almost_eq!(hit.point.y, 0.5);
+      almost_eq!(plane.as_surface().sample(hit.u, hit.v), hit.point);
+    }
+    almost_eq!(hits[0].t + hits[1].t, 1.0);
+  }
+
+  #[test]
+  fn curves_cut_by_curved_surfaces() {
+    // Tilted circle crossing the cylinder wall
+    let circle = Circle::from_plane(Plane { origin: Point3::new(1.0, 0.0, 1.0), u: Vec3::unit_x(), v: Vec3::unit_z() }, 0.5);
+    let surface = cylinder().into_enum();
+    let isects = circle.into_enum().intersect_surface(&surface);
+    let hits = get_hits(&isects);
+    assert_eq!(hits.len(), 2);
+    for hit in &hits {
+      almost_eq!((hit.point.x * hit.point.x + hit.point.y * hit.point.y).sqrt(), 1.0);
+      almost_eq!(surface.as_surface().sample(hit.u, hit.v), hit.point);
+    }
+    // Circles around the axis lie on the cylinder
+    let circle = Circle::new(Point3::new(0.0, 0.0, 1.0), 1.0).into_enum();
+    assert_eq!(circle.intersect_surface(&surface), vec![CurveSurfaceIntersectionType::Contained]);
+    // Line piercing a spline sheet
+    let sheet = SplineSurface::tabulated(&Spline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 1.0), Point3::new(2.0, 0.0, 0.0)]), Vec3::new(0.0, 2.0, 0.0)).into_enum();
+    let line = Line::new(Point3::new(1.0, 1.0, 2.0), Point3::new(1.0, 1.0, 1.5)).into_enum();
+    let isects = line.intersect_surface(&sheet);
+    let hits = get_hits(&isects);
+    assert_eq!(hits.len(), 1);
+    almost_eq!(hits[0].point, Point3::new(1.0, 1.0, 0.5));
+    almost_eq!(hits[0].t, 3.0);
+    almost_eq!(sheet.as_surface().sample(hits[0].u, hits[0].v), hits[0].point);
+  }
+
 }
@@ -165,8 +165,10 @@ impl Plane {
 
   pub fn from_curves(elems: &Vec<CurveType>) -> Result<Self, PlaneError> {
     for elem in elems.iter() {
-      if let CurveType::Circle(circle) = elem {
-        return Ok(circle.plane.clone())
+      match elem {
+        CurveType::Circle(circle) => return Ok(circle.plane.clone()),
+        CurveType::Ellipse(ellipse) => return Ok(ellipse.plane.clone()),
+        _ => {},
       }
     }
     let points = elems.iter().map(|curve|
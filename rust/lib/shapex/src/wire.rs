@@ -22,31 +22,14 @@ pub type Region = Vec<TrimmedCurve>;
 ///
 /// Wires fulfill all properties of [Region]s, but their element's
 /// bounds are ordered in the direction of the loop.
+/// Open wires, as created by [Wire::open], form a chain from one end to the other instead.
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wire(Vec<TrimmedCurve>);
 
 impl Wire {
   pub fn new(mut region: Region) -> Self {
-    if region.len() >= 2 {
-      // Find starting point from element order
-      let bounds = region[0].bounds;
-      let next_bounds = region[1].bounds;
-      let mut point = if bounds.0.almost(next_bounds.0) || bounds.0.almost(next_bounds.1) {
-        bounds.1
-      } else {
-        bounds.0
-      };
-      // Flip curves to flow consistently along element order
-      for tcurve in &mut region {
-        if tcurve.bounds.1.almost(point) {
-          point = tcurve.bounds.0;
-          tcurve.flip();
-        } else {
-          point = tcurve.bounds.1;
-        }
-      }
-    }
+    align_region(&mut region);
     #[cfg(debug_assertions)]
     {
       if region.len() == 0 { panic!("Wires may not be empty") }
@@ -57,6 +40,43 @@ impl Wire {
     Self(region)
   }
 
+  /// Sort the elements of `region` into a chain and orient them along it.
+  ///
+  /// Fails if the elements don't connect to a single chain without branches.
+
+  pub fn open(mut region: Region) -> Result<Self, String> {
+    if region.is_empty() { return Err("Wires may not be empty".into()) }
+    let is_loose = |region: &Region, i: usize, p: Point3| !region.iter().enumerate()
+      .any(|(j, other)| j != i && (other.bounds.0.almost(p) || other.bounds.1.almost(p)) );
+    // Start at a loose end, unless the elements form a loop
+    let start = (0..region.len())
+      .find(|&i| is_loose(&region, i, region[i].bounds.0) || is_loose(&region, i, region[i].bounds.1) )
+      .unwrap_or(0);
+    let flip = !is_loose(&region, start, region[start].bounds.0) && is_loose(&region, start, region[start].bounds.1);
+    let mut first = region.remove(start);
+    if flip { first.flip() }
+    let mut chain = vec![first];
+    while !region.is_empty() {
+      let end = chain.last().unwrap().bounds.1;
+      let next: Vec<usize> = (0..region.len())
+        .filter(|&i| region[i].bounds.0.almost(end) || region[i].bounds.1.almost(end) )
+        .collect();
+      match next.len() {
+        0 => return Err("Wire needs to be connected".into()),
+        1 => {},
+        _ => return Err("Wire must not branch".into()),
+      }
+      let mut tcurve = region.remove(next[0]);
+      if !tcurve.bounds.0.almost(end) { tcurve.flip() }
+      chain.push(tcurve);
+    }
+    Ok(Self(chain))
+  }
+
+  pub fn is_closed(&self) -> bool {
+    self.0[0].bounds.0.almost(self.0.last().unwrap().bounds.1)
+  }
+
   pub fn is_clockwise(&self) -> bool {
     geom2d::is_clockwise(&self.cage())
   }
@@ -157,6 +177,30 @@ impl DerefMut for Wire {
 }
 
 
+/// Flip curves in `region` to flow consistently along element order.
+
+pub fn align_region(region: &mut Region) {
+  if region.len() < 2 { return }
+  // Find starting point from element order, keeping the direction
+  // of the first element when it connects to the next one at both ends
+  let bounds = region[0].bounds;
+  let next_bounds = region[1].bounds;
+  let mut point = if bounds.1.almost(next_bounds.0) || bounds.1.almost(next_bounds.1) {
+    bounds.0
+  } else {
+    bounds.1
+  };
+  for tcurve in region {
+    if tcurve.bounds.1.almost(point) {
+      point = tcurve.bounds.0;
+      tcurve.flip();
+    } else {
+      point = tcurve.bounds.1;
+    }
+  }
+}
+
+
 /// Profiles use coplanar [wires](Wire) to form closed regions.
 ///
 /// Profiles must contain one or more wires, representing the outer and inner rings.
@@ -279,4 +323,38 @@ mod tests {
     assert!(rect.encloses(&inner_rect));
     assert!(!inner_rect.encloses(&rect));
   }
+
+  #[test]
+  fn align_two_elements() {
+    // Both ends of the line connect to the arc, so only element order decides the direction
+    let wire = Wire::new(vec![
+      TrimmedCurve::new(Line::new(Point3::new(-2.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0)).into_enum()),
+      TrimmedCurve::new(Arc::new(Point3::origin(), 2.0, 0.0, 0.5).into_enum()),
+    ]);
+    assert!(wire[0].bounds.0.almost(Point3::new(-2.0, 0.0, 0.0)));
+    assert!(wire[1].bounds.0.almost(Point3::new(2.0, 0.0, 0.0)));
+    assert!(wire.contains_point(Point3::new(0.0, 1.0, 0.0)));
+  }
+
+  #[test]
+  fn open_chain() {
+    let points = [
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 0.0, 0.0),
+      Point3::new(1.0, 1.0, 0.0),
+      Point3::new(2.0, 1.0, 0.0),
+    ];
+    let line = |i: usize, j: usize| TrimmedCurve::new(Line::new(points[i], points[j]).into_enum());
+    // Shuffled and partly reversed elements get sorted into a chain
+    let wire = Wire::open(vec![line(2, 1), line(2, 3), line(1, 0)]).unwrap();
+    assert!(!wire.is_closed());
+    let first_point = wire[0].bounds.0;
+    assert!(first_point.almost(points[0]) || first_point.almost(points[3]));
+    for pair in wire.windows(2) {
+      assert!(pair[0].bounds.1.almost(pair[1].bounds.0));
+    }
+    assert!(Wire::open(vec![line(0, 1), line(2, 3)]).is_err());
+    assert!(Wire::open(vec![line(0, 1), line(1, 2), line(1, 3)]).is_err());
+    assert!(Wire::open(vec![line(0, 1), line(1, 2), line(2, 0)]).unwrap().is_closed());
+  }
 }
@@ -41,7 +41,12 @@ pub enum FeatureType {
   CreateSketch(CreateSketchFeature),
   Extrusion(ExtrusionFeature),
   Revolution(RevolutionFeature),
+  Sweep(SweepFeature),
+  Loft(LoftFeature),
   Draft(DraftFeature),
+  Fillet(FilletFeature),
+  Chamfer(ChamferFeature),
+  Shell(ShellFeature),
 }
 
 impl FeatureType {
@@ -51,7 +56,12 @@ impl FeatureType {
       Self::CreateSketch(f) => f,
       Self::Extrusion(f) => f,
       Self::Revolution(f) => f,
+      Self::Sweep(f) => f,
+      Self::Loft(f) => f,
       Self::Draft(f) => f,
+      Self::Fillet(f) => f,
+      Self::Chamfer(f) => f,
+      Self::Shell(f) => f,
     }
   }
 
@@ -61,7 +71,12 @@ impl FeatureType {
       Self::CreateSketch(f) => f,
       Self::Extrusion(f) => f,
       Self::Revolution(f) => f,
+      Self::Sweep(f) => f,
+      Self::Loft(f) => f,
       Self::Draft(f) => f,
+      Self::Fillet(f) => f,
+      Self::Chamfer(f) => f,
+      Self::Shell(f) => f,
     }
   }
 }
@@ -188,11 +203,32 @@ impl FeatureTrait for CreateSketchFeature {
 }
 
 
+/// Where an [ExtrusionFeature] ends, measured along the normal of its profiles.
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExtrusionExtent {
+  /// Fixed distance, extruding to the back side for negative values
+  Distance(f64),
+  /// Total distance, split evenly between both sides
+  Symmetric(f64),
+  /// Separate distances to the front and the back side
+  TwoSided(f64, f64),
+  /// Up to the surface of a face
+  UpToFace(FaceRef),
+  /// Up to the first faces of the component, to the front side if true
+  UpToNext(bool),
+  /// Through all bodies of the component, to the front side if true
+  ThroughAll(bool),
+}
+
+
+// Deserialized in io.rs to load documents saved before extents existed
+#[derive(Debug, Clone, Serialize)]
 pub struct ExtrusionFeature {
   pub component_id: Uuid,
   pub profiles: Vec<ProfileRef>,
-  pub distance: f64,
+  pub extent: ExtrusionExtent,
+  pub taper: Deg<f64>,
   pub op: BooleanType,
 }
 
@@ -206,13 +242,28 @@ impl ExtrusionFeature {
     for profile_ref in profiles {
       let mut profile = profile_ref.profile.clone();
       profile.transform(&profile_ref.get_sketch(tree).unwrap().borrow().work_plane);
-      match features::extrude(&profile, self.distance) {
-        Ok(solid) => tool.join(solid.into_compound()),
-        Err(error) => return Err(FeatureError::Error(error)),
-      }
+      let solid = self.extrude(&profile, tree).map_err(FeatureError::Error)?;
+      tool.join(solid.into_compound()).map_err(FeatureError::Error)?;
     }
     Ok(tool)
   }
+
+  // Faces ending the extrusion trim its end cap, other extents extrude between offsets along the profile normal
+  fn extrude(&self, profile: &Profile, tree: &Component) -> Result<Solid, String> {
+    let body = &tree.find_child(&self.component_id).unwrap().compound;
+    let (start, end) = match &self.extent {
+      ExtrusionExtent::Distance(distance) => (0.0, *distance),
+      ExtrusionExtent::Symmetric(distance) => (-distance / 2.0, distance / 2.0),
+      ExtrusionExtent::TwoSided(front, back) => (-back, *front),
+      ExtrusionExtent::UpToFace(face_ref) => {
+        let face = face_ref.get_face(tree).ok_or("Target face was lost")?;
+        return features::extrude_to_face(profile, &face.borrow(), self.taper)
+      },
+      ExtrusionExtent::UpToNext(forward) => (0.0, features::distance_to_next(profile, body, *forward)?),
+      ExtrusionExtent::ThroughAll(forward) => (0.0, features::distance_through_all(profile, body, *forward)?),
+    };
+    features::extrude_tapered_between(profile, start, end, self.taper)
+  }
 }
 
 impl FeatureTrait for ExtrusionFeature {
@@ -232,7 +283,7 @@ impl FeatureTrait for ExtrusionFeature {
     }
     let tool = self.make_tool(&profiles, tree)?;
     let comp = tree.find_child_mut(&self.component_id).unwrap();
-    comp.compound.boolean(tool.clone(), self.op);
+    comp.compound.boolean(tool.clone(), self.op).map_err(FeatureError::Error)?;
     result
   }
 
@@ -268,7 +319,7 @@ impl RevolutionFeature {
         let mut profile = profile_ref.profile.clone();
         profile.transform(&profile_ref.get_sketch(tree).unwrap().borrow().work_plane);
         match features::revolve(&profile, axis.clone(), self.angle) {
-          Ok(solid) => tool.join(solid.into_compound()),
+          Ok(solid) => tool.join(solid.into_compound()).map_err(FeatureError::Error)?,
           Err(error) => return Err(FeatureError::Error(error)),
         }
       }
@@ -293,7 +344,7 @@ impl FeatureTrait for RevolutionFeature {
     }
     let tool = self.make_tool(&profiles, tree)?;
     let comp = tree.find_child_mut(&self.component_id).unwrap();
-    comp.compound.boolean(tool.clone(), self.op);
+    comp.compound.boolean(tool.clone(), self.op).map_err(FeatureError::Error)?;
     self.preview_compound = Some(tool);
     result
   }
@@ -308,6 +359,133 @@ impl FeatureTrait for RevolutionFeature {
 }
 
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepFeature {
+  pub component_id: Uuid,
+  pub profiles: Vec<ProfileRef>,
+  pub path: Vec<CurveRef>,
+  pub orientation: features::SweepOrientation,
+  pub op: BooleanType,
+}
+
+impl SweepFeature {
+  pub fn into_enum(self) -> FeatureType {
+    FeatureType::Sweep(self)
+  }
+
+  fn make_tool(&self, profiles: &Vec<ProfileRef>, tree: &Component) -> Result<Compound, FeatureError> {
+    let path = Wire::open(self.path.iter().map(|curve_ref| curve_ref.get_curve() ).collect()).map_err(FeatureError::Error)?;
+    let mut tool = Compound::default();
+    for profile_ref in profiles {
+      let mut profile = profile_ref.profile.clone();
+      profile.transform(&profile_ref.get_sketch(tree).unwrap().borrow().work_plane);
+      // Start path at the end closest to the profile
+      let mut path = path.clone();
+      let distance = |p: Point3| (p - profile.plane.origin).dot(profile.plane.normal()).abs();
+      if distance(path.last().unwrap().bounds.1) < distance(path[0].bounds.0) {
+        path.reverse();
+      }
+      match features::sweep(&profile, &path, self.orientation) {
+        Ok(solid) => tool.join(solid.into_compound()).map_err(FeatureError::Error)?,
+        Err(error) => return Err(FeatureError::Error(error)),
+      }
+    }
+    Ok(tool)
+  }
+}
+
+impl FeatureTrait for SweepFeature {
+  fn preview(&self, tree: &Component) -> Option<Compound> {
+    let mut profiles = self.profiles.clone();
+    match update_profiles(&mut profiles, tree) {
+      Err(FeatureError::Error(_)) => None,
+      Err(FeatureError::Warning(_)) | Ok(_) => self.make_tool(&profiles, tree).ok(),
+    }
+  }
+
+  fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
+    let mut profiles = self.profiles.clone();
+    let result = update_profiles(&mut profiles, tree);
+    if let Err(FeatureError::Error(_)) = result {
+      return result;
+    }
+    let tool = self.make_tool(&profiles, tree)?;
+    let comp = tree.find_child_mut(&self.component_id).unwrap();
+    comp.compound.boolean(tool.clone(), self.op).map_err(FeatureError::Error)?;
+    result
+  }
+
+  fn modified_components(&self) -> Vec<CompRef> {
+    vec![self.component_id]
+  }
+
+  fn repair(&mut self, tree: &Component) {
+    update_profiles(&mut self.profiles, tree).ok();
+  }
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoftFeature {
+  pub component_id: Uuid,
+  pub profiles: Vec<ProfileRef>,
+  pub rail: Vec<CurveRef>,
+  pub ruled: bool,
+  pub op: BooleanType,
+}
+
+impl LoftFeature {
+  pub fn into_enum(self) -> FeatureType {
+    FeatureType::Loft(self)
+  }
+
+  fn make_tool(&self, profiles: &Vec<ProfileRef>, tree: &Component) -> Result<Compound, FeatureError> {
+    let profiles: Vec<Profile> = profiles.iter().map(|profile_ref| {
+      let mut profile = profile_ref.profile.clone();
+      profile.transform(&profile_ref.get_sketch(tree).unwrap().borrow().work_plane);
+      profile
+    }).collect();
+    let solid = if self.rail.is_empty() {
+      features::loft(&profiles, self.ruled)
+    } else {
+      let rail: Region = self.rail.iter().map(|curve_ref| curve_ref.get_curve() ).collect();
+      features::loft_guided(&profiles, &rail, self.ruled)
+    }.map_err(FeatureError::Error)?;
+    Ok(solid.into_compound())
+  }
+}
+
+impl FeatureTrait for LoftFeature {
+  fn preview(&self, tree: &Component) -> Option<Compound> {
+    let mut profiles = self.profiles.clone();
+    match update_profiles(&mut profiles, tree) {
+      Err(FeatureError::Error(_)) => None,
+      Err(FeatureError::Warning(_)) | Ok(_) => self.make_tool(&profiles, tree).ok(),
+    }
+  }
+
+  fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
+    let mut profiles = self.profiles.clone();
+    let result = update_profiles(&mut profiles, tree);
+    if let Err(FeatureError::Error(_)) = result {
+      return result;
+    }
+    let tool = self.make_tool(&profiles, tree)?;
+    let comp = tree.find_child_mut(&self.component_id).unwrap();
+    comp.compound.boolean(tool.clone(), self.op).map_err(FeatureError::Error)?;
+    result
+  }
+
+  fn modified_components(&self) -> Vec<CompRef> {
+    vec![self.component_id]
+  }
+
+  fn repair(&mut self, tree: &Component) {
+    update_profiles(&mut self.profiles, tree).ok();
+  }
+}
+
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftFeature {
   pub fixed_plane: PlanarRef,
@@ -324,7 +502,7 @@ impl DraftFeature {
 impl FeatureTrait for DraftFeature {
   fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
     if let Some(plane) = self.fixed_plane.get_plane(tree) {
-      let found_faces = self.faces.iter().filter_map(|face| face.get_face(tree) ).cloned().collect();
+      let found_faces: Vec<Ref<Face>> = self.faces.iter().filter_map(|face| face.get_face(tree) ).cloned().collect();
       let result = features::draft(&found_faces, &plane, self.angle)
       .map_err(|error| FeatureError::Error(error) );
       if found_faces.len() == self.faces.len() {
@@ -348,3 +526,139 @@ impl FeatureTrait for DraftFeature {
     self.faces.retain(|face| face.get_face(tree).is_some() );
   }
 }
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilletFeature {
+  pub edges: Vec<EdgeRef>,
+  pub radius: f64,
+}
+
+impl FilletFeature {
+  pub fn into_enum(self) -> FeatureType {
+    FeatureType::Fillet(self)
+  }
+}
+
+impl FeatureTrait for FilletFeature {
+  fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
+    let radius = self.radius;
+    blend_edges(self.modified_components(), &self.edges, tree, |solid, edges| features::fillet(solid, edges, radius) )
+  }
+
+  fn modified_components(&self) -> Vec<CompRef> {
+    let mut ids: Vec<Uuid> = self.edges.iter().map(|edge| edge.component_id ).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+  }
+
+  fn repair(&mut self, tree: &Component) {
+    self.edges.retain(|edge| edge.get_edge(tree).is_some() );
+  }
+}
+
+// Apply a blend operation to all referenced edges, grouped by solid
+fn blend_edges<F>(components: Vec<CompRef>, edge_refs: &[EdgeRef], tree: &mut Component, blend: F) -> Result<(), FeatureError>
+where
+  F: Fn(&mut Solid, &[Ref<Edge>]) -> Result<(), String>,
+{
+  let mut num_found = 0;
+  for component_id in components {
+    let comp = tree.find_child_mut(&component_id).unwrap();
+    for solid in &mut comp.compound.solids {
+      let edges: Vec<Ref<Edge>> = edge_refs.iter()
+        .filter(|edge| edge.component_id == component_id )
+        .filter_map(|edge| solid.find_edge_from_bounds(&edge.bounds) )
+        .cloned().collect();
+      if edges.is_empty() { continue }
+      num_found += edges.len();
+      blend(solid, &edges).map_err(FeatureError::Error)?;
+    }
+  }
+  if num_found == edge_refs.len() {
+    Ok(())
+  } else {
+    Err(FeatureError::Warning("Some edges could not be found".into()))
+  }
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChamferFeature {
+  pub edges: Vec<EdgeRef>,
+  pub mode: features::ChamferMode,
+}
+
+impl ChamferFeature {
+  pub fn into_enum(self) -> FeatureType {
+    FeatureType::Chamfer(self)
+  }
+}
+
+impl FeatureTrait for ChamferFeature {
+  fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
+    let mode = self.mode;
+    blend_edges(self.modified_components(), &self.edges, tree, |solid, edges| features::chamfer(solid, edges, mode) )
+  }
+
+  fn modified_components(&self) -> Vec<CompRef> {
+    let mut ids: Vec<Uuid> = self.edges.iter().map(|edge| edge.component_id ).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+  }
+
+  fn repair(&mut self, tree: &Component) {
+    self.edges.retain(|edge| edge.get_edge(tree).is_some() );
+  }
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellFeature {
+  pub faces: Vec<FaceRef>,
+  pub thickness: f64,
+}
+
+impl ShellFeature {
+  pub fn into_enum(self) -> FeatureType {
+    FeatureType::Shell(self)
+  }
+}
+
+impl FeatureTrait for ShellFeature {
+  fn execute(&mut self, tree: &mut Component) -> Result<(), FeatureError> {
+    // Solids are selected through their open faces
+    let mut num_found = 0;
+    for component_id in self.modified_components() {
+      let comp = tree.find_child_mut(&component_id).unwrap();
+      for solid in &mut comp.compound.solids {
+        let faces: Vec<Ref<Face>> = self.faces.iter()
+          .filter(|face| face.component_id == component_id )
+          .filter_map(|face| solid.find_face_from_bounds(&face.bounds) )
+          .cloned().collect();
+        if faces.is_empty() { continue }
+        num_found += faces.len();
+        features::shell(solid, &faces, self.thickness).map_err(FeatureError::Error)?;
+      }
+    }
+    if num_found == self.faces.len() {
+      Ok(())
+    } else {
+      Err(FeatureError::Warning("Some faces could not be found".into()))
+    }
+  }
+
+  fn modified_components(&self) -> Vec<CompRef> {
+    let mut ids: Vec<Uuid> = self.faces.iter().map(|face| face.component_id ).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+  }
+
+  fn repair(&mut self, tree: &Component) {
+    self.faces.retain(|face| face.get_face(tree).is_some() );
+  }
+}
+
@@ -1,9 +1,13 @@
+use std::collections::HashSet;
+
 use serde::{Serialize, Serializer, Deserialize, Deserializer};
 
-use shapex::Ref;
+use shapex::{Ref, Deg, BooleanType};
 
 use crate::Feature;
 use crate::document;
+use crate::{ExtrusionFeature, ExtrusionExtent, ProfileRef, EdgeRef, CompRef};
+use crate::Uuid;
 
 
 impl Serialize for document::Document {
@@ -31,6 +35,68 @@ struct Document {
 }
 
 
+impl<'de> Deserialize<'de> for ExtrusionFeature {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let dummy = Extrusion::deserialize(deserializer)?;
+    let extent = match (dummy.extent, dummy.distance) {
+      (Some(extent), _) => extent,
+      (None, Some(distance)) => ExtrusionExtent::Distance(distance),
+      (None, None) => return Err(serde::de::Error::missing_field("extent")),
+    };
+    Ok(Self {
+      component_id: dummy.component_id,
+      profiles: dummy.profiles,
+      extent,
+      taper: dummy.taper,
+      op: dummy.op,
+    })
+  }
+}
+
+
+// Extrusions saved before extents existed only store a distance
+#[derive(Debug, Deserialize)]
+struct Extrusion {
+  pub component_id: Uuid,
+  pub profiles: Vec<ProfileRef>,
+  #[serde(default, deserialize_with = "some")]
+  pub extent: Option<ExtrusionExtent>,
+  #[serde(default, deserialize_with = "some")]
+  pub distance: Option<f64>,
+  #[serde(default = "no_taper")]
+  pub taper: Deg<f64>,
+  pub op: BooleanType,
+}
+
+
+impl<'de> Deserialize<'de> for EdgeRef {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let dummy = Edge::deserialize(deserializer)?;
+    let bounds = match (dummy.bounds, dummy.edge_id) {
+      (Some(bounds), _) => bounds,
+      // Edge ids don't survive rebuilds, so these references are left dangling
+      (None, Some(_)) => (HashSet::new(), HashSet::new()),
+      (None, None) => return Err(serde::de::Error::missing_field("bounds")),
+    };
+    Ok(Self {
+      component_id: dummy.component_id,
+      bounds,
+    })
+  }
+}
+
+
+// Edge references saved before they were tracked by their faces only store an edge id
+#[derive(Debug, Deserialize)]
+struct Edge {
+  pub component_id: CompRef,
+  #[serde(default, deserialize_with = "some")]
+  pub bounds: Option<(HashSet<Uuid>, HashSet<Uuid>)>,
+  #[serde(default, deserialize_with = "some")]
+  pub edge_id: Option<Uuid>,
+}
+
+
 // pub fn export_ron(comp: &crate::Component) -> String {
 //   ron::to_string(&dump_component(comp, false)).unwrap()
 // }
@@ -89,3 +155,43 @@ struct Document {
 //   pub bodies: Vec<String>,
 //   pub children: Vec<Self>,
 // }
+
+
+fn no_taper() -> Deg<f64> {
+  Deg(0.0)
+}
+
+// Optional fields are stored without Some(..) in RON
+fn some<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+  T::deserialize(deserializer).map(Some)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::FeatureType;
+
+  #[test]
+  fn load_extrusion_distance() {
+    let dump = "(features:[(id:\"bae57bdb-877f-4c76-82d5-43f3fab568d8\",error:None,feature_type:Extrusion((component_id:\"167b1e8f-3c66-4fab-9fc5-198b333a927e\",profiles:[],distance:2.5,op:Join)))])";
+    let doc: document::Document = ron::from_str(dump).unwrap();
+    let feature = doc.features[0].borrow();
+    if let FeatureType::Extrusion(extrusion) = &feature.feature_type {
+      assert!(matches!(extrusion.extent, ExtrusionExtent::Distance(distance) if distance == 2.5));
+      assert_eq!(extrusion.taper, Deg(0.0));
+      assert_eq!(ron::to_string(&doc).unwrap(), dump.replace("distance:2.5", "extent:Distance(2.5),taper:(0.0)"));
+    } else { panic!("Extrusion was loaded as {:?}", feature.feature_type) }
+  }
+
+  #[test]
+  fn load_edge_id() {
+    let dump = "(component_id:\"167b1e8f-3c66-4fab-9fc5-198b333a927e\",edge_id:\"bae57bdb-877f-4c76-82d5-43f3fab568d8\")";
+    let edge: EdgeRef = ron::from_str(dump).unwrap();
+    assert!(edge.bounds.0.is_empty() && edge.bounds.1.is_empty());
+    let dump = ron::to_string(&edge).unwrap();
+    assert_eq!(dump, "(component_id:\"167b1e8f-3c66-4fab-9fc5-198b333a927e\",bounds:([],[]))");
+    assert!(ron::from_str::<EdgeRef>(&dump).is_ok());
+    assert!(ron::from_str::<EdgeRef>("(component_id:\"167b1e8f-3c66-4fab-9fc5-198b333a927e\")").is_err());
+  }
+}
@@ -54,10 +54,17 @@ impl FaceRef {
 }
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EdgeRef {
   pub component_id: CompRef,
-  pub edge_id: Uuid,
+  pub bounds: (HashSet<Uuid>, HashSet<Uuid>), // Edge ids of the faces on both sides
+}
+
+impl EdgeRef {
+  pub fn get_edge<'a>(&self, tree: &'a Component) -> Option<&'a Ref<Edge>> {
+    let comp = tree.find_child(&self.component_id).unwrap();
+    comp.compound.find_edge_from_bounds(&self.bounds)
+  }
 }
 
 
@@ -67,6 +74,14 @@ pub struct CurveRef {
   pub curve: Ref<CurveType>,
 }
 
+impl CurveRef {
+  pub fn get_curve(&self) -> TrimmedCurve {
+    let mut tcurve = TrimmedCurve::new(self.curve.borrow().clone());
+    tcurve.transform(&self.sketch.borrow().work_plane);
+    tcurve
+  }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PlanarRef {
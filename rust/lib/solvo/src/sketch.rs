@@ -482,4 +482,36 @@ mod tests {
     assert_eq!(profiles[0].rings.len(), 2);
     assert_eq!(profiles[1].rings.len(), 1);
   }
+
+  #[test]
+  fn split_overlapping_circles() {
+    let mut sketch = Sketch::default();
+    let circle = Circle::new(Point3::origin(), 1.0);
+    let other_circle = Circle::new(Point3::new(1.0, 0.0, 0.0), 1.0);
+    sketch.elements.push(rc(circle.into_enum()));
+    sketch.elements.push(rc(other_circle.into_enum()));
+    let cut_elements = Sketch::all_split(&sketch.elements);
+    assert_eq!(cut_elements.len(), 4, "{} cut_elements found instead of 4", cut_elements.len());
+    let islands = Sketch::build_islands(&cut_elements);
+    assert_eq!(islands.len(), 1, "{} islands found instead of 1", islands.len());
+    assert!(cut_elements.iter().all(|elem| !elem.is_closed() ), "Circles should have been split into arcs");
+  }
+
+  #[test]
+  fn region_spline_split_by_line() {
+    let mut sketch = make_sketch(vec![
+      Line::new(Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0)),
+      Line::new(Point3::new(1.0, -1.0, 0.0), Point3::new(1.0, 3.0, 0.0)),
+    ]);
+    let spline = Spline::new(vec![
+      Point3::new(0.0, 0.0, 0.0),
+      Point3::new(1.0, 2.0, 0.0),
+      Point3::new(2.0, 0.0, 0.0),
+    ]);
+    sketch.elements.push(rc(spline.into_enum()));
+    let cut_elements = Sketch::all_split(&sketch.elements);
+    assert_eq!(cut_elements.len(), 7, "{} cut_elements found instead of 7", cut_elements.len());
+    let regions = Sketch::get_wires(cut_elements, false);
+    assert_eq!(regions.len(), 2, "{} regions found instead of 2", regions.len());
+  }
 }
@@ -62,6 +62,11 @@ impl JsComponent {
     ).collect()
   }
 
+  pub fn mass_properties(&self, density: f64) -> JsValue {
+    let doc = self.document.borrow();
+    JsValue::from_serde(&self.get_comp(&doc).compound.mass_properties(density)).unwrap()
+  }
+
   pub fn export_stl(&self, title: &str) -> String {
     let doc = self.document.borrow();
     let comp = self.get_comp(&doc);
@@ -83,6 +83,95 @@ impl Controllable for Circle {
 }
 
 
+impl Controllable for Ellipse {
+  fn handles(&self) -> Vec<Point3> {
+    vec![self.plane.origin, self.sample(0.0), self.sample(0.25)]
+  }
+
+  fn set_handles(&mut self, handles: Vec<Point3>) {
+    self.plane.origin = handles[0];
+    if handles.len() > 1 {
+      // Major handle turns the ellipse within its plane
+      let major = handles[1] - handles[0];
+      let radius = major.magnitude();
+      if !radius.almost(0.0) {
+        let normal = self.plane.normal();
+        self.plane.u = major / radius;
+        self.plane.v = normal.cross(self.plane.u);
+        self.radii.0 = radius;
+      }
+    }
+    if handles.len() > 2 {
+      self.radii.1 = handles[0].distance(handles[2]);
+    }
+  }
+
+  fn snap_points(&self) -> Vec<Point3> {
+    let mut points = vec![self.plane.origin];
+    points.append(&mut (0..4).map(|i| self.sample(i as f64 / 4.0) ).collect());
+    points
+  }
+}
+
+
+impl Controllable for EllipticalArc {
+  fn handles(&self) -> Vec<Point3> {
+    let endpoints = self.endpoints();
+    vec![endpoints.0, endpoints.1]
+  }
+
+  fn set_handles(&mut self, handles: Vec<Point3>) {
+    // Endpoints slide along the ellipse, keeping the winding of the arc
+    let ellipse = self.ellipse();
+    let start = ellipse.unsample(handles[0]);
+    let mut end = ellipse.unsample(handles[1]);
+    if self.range() > 0.0 && end <= start {
+      end += 1.0;
+    } else if self.range() < 0.0 && end >= start {
+      end -= 1.0;
+    }
+    self.bounds = (start, end);
+  }
+
+  fn snap_points(&self) -> Vec<Point3> {
+    let endpoints = self.endpoints();
+    vec![self.plane.origin, endpoints.0, endpoints.1, self.midpoint()]
+  }
+}
+
+
+impl Controllable for Helix {
+  fn handles(&self) -> Vec<Point3> {
+    let (start, end) = self.endpoints();
+    vec![self.plane.origin, start, end]
+  }
+
+  fn set_handles(&mut self, handles: Vec<Point3>) {
+    self.plane.origin = handles[0];
+    let normal = self.plane.normal();
+    if handles.len() > 1 {
+      // Start handle sets the radius and turns the helix around its axis
+      let offset = handles[1] - handles[0];
+      let radial = offset - normal * offset.dot(normal);
+      let radius = radial.magnitude();
+      if !radius.almost(0.0) {
+        self.plane.u = radial / radius;
+        self.plane.v = normal.cross(self.plane.u);
+        self.radius = radius;
+      }
+    }
+    if handles.len() > 2 && !self.turns.almost(0.0) {
+      // End handle sets the height, keeping the number of turns
+      self.pitch = (handles[2] - handles[0]).dot(normal) / self.turns;
+    }
+  }
+
+  fn snap_points(&self) -> Vec<Point3> {
+    self.handles()
+  }
+}
+
+
 impl Controllable for Spline {
   fn handles(&self) -> Vec<Point3> {
     self.controls.clone()
@@ -106,6 +195,9 @@ pub fn as_controllable(elem: &CurveType) -> &dyn Controllable {
     CurveType::Arc(arc) => arc,
     CurveType::Circle(circle) => circle,
     CurveType::Spline(spline) => spline,
+    CurveType::Ellipse(ellipse) => ellipse,
+    CurveType::EllipticalArc(arc) => arc,
+    CurveType::Helix(helix) => helix,
   }
 }
 
@@ -115,5 +207,8 @@ pub fn as_controllable_mut(elem: &mut CurveType) -> &mut dyn Controllable {
     CurveType::Arc(arc) => arc,
     CurveType::Circle(circle) => circle,
     CurveType::Spline(spline) => spline,
+    CurveType::Ellipse(ellipse) => ellipse,
+    CurveType::EllipticalArc(arc) => arc,
+    CurveType::Helix(helix) => helix,
   }
 }
@@ -13,6 +13,7 @@ use crate::utils::point_to_js;
 use crate::controllable::as_controllable_mut;
 use crate::controllable::as_controllable;
 use crate::feature::JsAxialRef;
+use crate::feature::JsCurveRef;
 
 
 #[wasm_bindgen]
@@ -43,6 +44,9 @@ impl JsCurve {
       CurveType::Arc(_) => "Arc",
       CurveType::Circle(_) => "Circle",
       CurveType::Spline(_) => "Spline",
+      CurveType::Ellipse(_) => "Ellipse",
+      CurveType::EllipticalArc(_) => "EllipticalArc",
+      CurveType::Helix(_) => "Helix",
     }.to_string()
   }
 
@@ -56,6 +60,7 @@ impl JsCurve {
     match &*self.real.borrow() {
       CurveType::Circle(c) => c.radius,
       CurveType::Arc(a) => a.radius,
+      CurveType::Helix(h) => h.radius,
       _ => 0.0
     }
   }
@@ -63,6 +68,7 @@ impl JsCurve {
   pub fn area(&self) -> f64 {
     let area = match &*self.real.borrow() {
       CurveType::Circle(c) => c.area(),
+      CurveType::Ellipse(e) => e.area(),
       _ => 0.0
     };
    area
@@ -116,4 +122,11 @@ impl JsCurve {
       _ => unreachable!(),
     }
   }
+
+  pub fn make_curve_reference(&self) -> JsCurveRef {
+    JsCurveRef::new(CurveRef {
+      curve: self.real.clone(),
+      sketch: self.sketch.clone(),
+    })
+  }
 }
@@ -8,6 +8,7 @@ use crate::internal::*;
 use crate::document::JsDocument;
 use crate::region::JsRegion;
 use crate::solid::JsFace;
+use crate::solid::JsEdge;
 use crate::curve::JsCurve;
 use crate::buffer_geometry::JsBufferGeometry;
 use crate::construction_helper::JsConstructionHelper;
@@ -87,6 +88,28 @@ impl JsAxialRef {
 }
 
 
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct JsCurveRef(CurveRef);
+
+impl JsCurveRef {
+  pub fn new(real: CurveRef) -> Self {
+    Self(real)
+  }
+}
+
+#[wasm_bindgen]
+impl JsCurveRef {
+  pub fn item(&self) -> JsCurve {
+    JsCurve::from(self.0.curve.clone(), self.0.sketch.clone())
+  }
+
+  pub fn item_id(&self) -> JsValue {
+    JsValue::from_serde(&self.0.curve.borrow().id()).unwrap()
+  }
+}
+
+
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 pub struct JsFaceRef {
@@ -123,6 +146,42 @@ impl JsFaceRef {
 }
 
 
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct JsEdgeRef {
+  real: EdgeRef,
+  document: Ref<Document>,
+}
+
+impl JsEdgeRef {
+  pub fn new(real: EdgeRef, document: Ref<Document>) -> Self {
+    Self {
+      real,
+      document,
+    }
+  }
+}
+
+#[wasm_bindgen]
+impl JsEdgeRef {
+  pub fn item(&self) -> JsValue {
+    if let Some(edge) = self.real.get_edge(self.document.borrow().tree()) {
+      JsValue::from(JsEdge::from(edge, self.real.component_id, self.document.clone()))
+    } else {
+      JsValue::undefined()
+    }
+  }
+
+  pub fn item_id(&self) -> JsValue {
+    if let Some(edge) = self.real.get_edge(self.document.borrow().tree()) {
+      JsValue::from_serde(&edge.borrow().id).unwrap()
+    } else {
+      JsValue::undefined()
+    }
+  }
+}
+
+
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 pub struct JsProfileRef {
@@ -211,6 +270,48 @@ impl JsFaceRefList {
 }
 
 
+#[wasm_bindgen]
+pub struct JsEdgeRefList {
+  edges: Vec<JsEdgeRef>,
+}
+
+#[wasm_bindgen]
+impl JsEdgeRefList {
+
+  #[wasm_bindgen(constructor)]
+  pub fn new() -> Self {
+    Self {
+      edges: vec![],
+    }
+  }
+
+  pub fn push(&mut self, edge: &JsEdgeRef) {
+    self.edges.push(edge.clone());
+  }
+}
+
+
+#[wasm_bindgen]
+pub struct JsCurveRefList {
+  curves: Vec<JsCurveRef>,
+}
+
+#[wasm_bindgen]
+impl JsCurveRefList {
+
+  #[wasm_bindgen(constructor)]
+  pub fn new() -> Self {
+    Self {
+      curves: vec![],
+    }
+  }
+
+  pub fn push(&mut self, curve: &JsCurveRef) {
+    self.curves.push(curve.clone());
+  }
+}
+
+
 #[wasm_bindgen]
 pub struct JsFeature {
   document: Ref<Document>,
@@ -300,13 +401,14 @@ impl JsFeature {
     self.process_feature(Feature::new(feature.into_enum()));
   }
 
-  pub fn extrusion(&mut self, comp_ref: JsValue, profiles: JsProfileRefList, distance: f64, op: &str) {
+  pub fn extrusion(&mut self, comp_ref: JsValue, profiles: JsProfileRefList, extent: &str, distance: f64, other: f64, target: JsFaceRefList, taper: f64, op: &str) {
     let profiles = &profiles.profiles;
     let feature = Feature::new(
       ExtrusionFeature {
         component_id: comp_ref.into_serde().unwrap(),
         profiles: profiles.iter().map(|profile| profile.real.clone() ).collect(),
-        distance,
+        extent: get_extrusion_extent(extent, distance, other, target.faces.first().map(|face| face.real.clone() )),
+        taper: Deg(taper),
         op: get_op(op),
       }.into_enum(),
     );
@@ -328,6 +430,34 @@ impl JsFeature {
     self.process_feature(feature);
   }
 
+  pub fn sweep(&mut self, comp_ref: JsValue, profiles: JsProfileRefList, path: JsCurveRefList, frenet: bool, op: &str) {
+    let profiles = &profiles.profiles;
+    let feature = Feature::new(
+      SweepFeature {
+        component_id: comp_ref.into_serde().unwrap(),
+        profiles: profiles.iter().map(|profile| profile.real.clone() ).collect(),
+        path: path.curves.iter().map(|curve| curve.0.clone() ).collect(),
+        orientation: if frenet { features::SweepOrientation::Frenet } else { features::SweepOrientation::Fixed },
+        op: get_op(op),
+      }.into_enum(),
+    );
+    self.process_feature(feature);
+  }
+
+  pub fn loft(&mut self, comp_ref: JsValue, profiles: JsProfileRefList, rail: JsCurveRefList, ruled: bool, op: &str) {
+    let profiles = &profiles.profiles;
+    let feature = Feature::new(
+      LoftFeature {
+        component_id: comp_ref.into_serde().unwrap(),
+        profiles: profiles.iter().map(|profile| profile.real.clone() ).collect(),
+        rail: rail.curves.iter().map(|curve| curve.0.clone() ).collect(),
+        ruled,
+        op: get_op(op),
+      }.into_enum(),
+    );
+    self.process_feature(feature);
+  }
+
   pub fn draft(&mut self, faces: JsFaceRefList, ref_plane: &JsPlanarRef, angle: f64) {
     let faces = &faces.faces;
     let feature = Feature::new(
@@ -340,6 +470,39 @@ impl JsFeature {
     self.process_feature(feature);
   }
 
+  pub fn fillet(&mut self, edges: JsEdgeRefList, radius: f64) {
+    let edges = &edges.edges;
+    let feature = Feature::new(
+      FilletFeature {
+        edges: edges.iter().map(|edge| edge.real.clone() ).collect(),
+        radius,
+      }.into_enum(),
+    );
+    self.process_feature(feature);
+  }
+
+  pub fn chamfer(&mut self, edges: JsEdgeRefList, mode: &str, distance: f64, other: f64) {
+    let edges = &edges.edges;
+    let feature = Feature::new(
+      ChamferFeature {
+        edges: edges.iter().map(|edge| edge.real.clone() ).collect(),
+        mode: get_chamfer_mode(mode, distance, other),
+      }.into_enum(),
+    );
+    self.process_feature(feature);
+  }
+
+  pub fn shell(&mut self, faces: JsFaceRefList, thickness: f64) {
+    let faces = &faces.faces;
+    let feature = Feature::new(
+      ShellFeature {
+        faces: faces.iter().map(|face| face.real.clone() ).collect(),
+        thickness,
+      }.into_enum(),
+    );
+    self.process_feature(feature);
+  }
+
   fn process_feature(&mut self, mut feature: Feature) {
     let mut doc = self.document.borrow_mut();
     if let Some(this) = &mut self.real {
@@ -388,11 +551,26 @@ impl JsFeature {
 
   pub fn face_refs(&self) -> Array {
     if let Some(real) = &self.real {
-      if let FeatureType::Draft(feature) = &real.borrow().feature_type {
-        feature.faces.iter().map(|face_ref| JsValue::from(JsFaceRef::new(face_ref.clone(), self.document.clone())) ).collect()
-      } else {
-        Array::new()
-      }
+      let faces = match &real.borrow().feature_type {
+        FeatureType::Draft(feature) => feature.faces.clone(),
+        FeatureType::Shell(feature) => feature.faces.clone(),
+        FeatureType::Extrusion(ExtrusionFeature { extent: ExtrusionExtent::UpToFace(face), .. }) => vec![face.clone()],
+        _ => vec![],
+      };
+      faces.into_iter().map(|face_ref| JsValue::from(JsFaceRef::new(face_ref, self.document.clone())) ).collect()
+    } else {
+      Array::new()
+    }
+  }
+
+  pub fn edge_refs(&self) -> Array {
+    if let Some(real) = &self.real {
+      let edges = match &real.borrow().feature_type {
+        FeatureType::Fillet(feature) => feature.edges.clone(),
+        FeatureType::Chamfer(feature) => feature.edges.clone(),
+        _ => vec![],
+      };
+      edges.into_iter().map(|edge_ref| JsValue::from(JsEdgeRef::new(edge_ref, self.document.clone())) ).collect()
     } else {
       Array::new()
     }
@@ -408,3 +586,23 @@ fn get_op(str: &str) -> BooleanType {
     _ => BooleanType::Create,
   }
 }
+
+// The sign of distance selects the side for extents without distances
+fn get_extrusion_extent(str: &str, distance: f64, other: f64, target: Option<FaceRef>) -> ExtrusionExtent {
+  match (str, target) {
+    ("symmetric", _) => ExtrusionExtent::Symmetric(distance.abs()),
+    ("two_sided", _) => ExtrusionExtent::TwoSided(distance, other),
+    ("face", Some(face)) => ExtrusionExtent::UpToFace(face),
+    ("next", _) => ExtrusionExtent::UpToNext(distance >= 0.0),
+    ("all", _) => ExtrusionExtent::ThroughAll(distance >= 0.0),
+    _ => ExtrusionExtent::Distance(distance),
+  }
+}
+
+fn get_chamfer_mode(str: &str, distance: f64, other: f64) -> features::ChamferMode {
+  match str {
+    "distances" => features::ChamferMode::TwoDistances(distance, other),
+    "angle" => features::ChamferMode::DistanceAngle(distance, Deg(other)),
+    _ => features::ChamferMode::Distance(distance),
+  }
+}
@@ -51,6 +51,7 @@ impl JsRegion {
       Point3::origin(),
       |acc, elem| acc + match &elem.base {
         CurveType::Circle(circle) => circle.plane.origin.to_vec() * 2.0,
+        CurveType::Ellipse(ellipse) => ellipse.plane.origin.to_vec() * 2.0,
         _ => elem.bounds.0.to_vec() + elem.bounds.1.to_vec(),
       }
     ) / (self.profile.rings[0].len() as f64 * 2.0);
@@ -83,6 +83,14 @@ impl JsSketch {
     JsCurve::from(sketch.elements.last().unwrap().clone(), self.real.clone())
   }
 
+  pub fn add_ellipse(&mut self, center: JsValue, radius_x: f64, radius_y: f64) -> JsCurve {
+    let mut sketch = self.real.borrow_mut();
+    let center = point_from_js(center);
+    let ellipse = Ellipse::new(sketch.work_plane.transform_point(center), (radius_x, radius_y));
+    sketch.elements.push(rc(ellipse.into_enum()));
+    JsCurve::from(sketch.elements.last().unwrap().clone(), self.real.clone())
+  }
+
   pub fn add_arc(&mut self, p1: JsValue, p2: JsValue, p3: JsValue) -> Result<JsCurve, JsValue> {
     let points = vec![point_from_js(p1), point_from_js(p2), point_from_js(p3)];
     let mut sketch = self.real.borrow_mut();
@@ -7,6 +7,7 @@ use shapex::*;
 
 use crate::feature::JsPlanarRef;
 use crate::feature::JsFaceRef;
+use crate::feature::JsEdgeRef;
 use crate::buffer_geometry::JsBufferGeometry;
 use crate::utils::point_to_js;
 use crate::utils::points_to_js;
@@ -110,25 +111,50 @@ impl JsFace {
 
 
 #[wasm_bindgen]
+#[derive(Debug, Clone)]
 pub struct JsEdge {
+  component_id: Uuid,
   real: Ref<Edge>,
+
+  #[wasm_bindgen(skip)]
+  pub document: Ref<Document>,
 }
 
-#[wasm_bindgen]
 impl JsEdge {
-  fn from(edge: &Ref<Edge>) -> Self {
+  pub fn from(edge: &Ref<Edge>, component_id: Uuid, document: Ref<Document>) -> Self {
     Self {
+      component_id,
       real: edge.clone(),
+      document,
     }
   }
+}
 
+#[wasm_bindgen]
+impl JsEdge {
   pub fn id(&self) -> JsValue {
     JsValue::from_serde(&self.real.borrow().id).unwrap()
   }
 
+  pub fn center(&self) -> JsValue {
+    point_to_js(self.real.borrow().left_half.borrow().make_curve().sample(0.5))
+  }
+
   pub fn tesselate(&self) -> Array {
     points_to_js(self.real.borrow().left_half.borrow().make_curve().tesselate())
   }
+
+  pub fn make_edge_reference(&self) -> JsValue {
+    let edge = self.real.borrow();
+    JsValue::from(JsEdgeRef::new(EdgeRef {
+      component_id: self.component_id,
+      bounds: (edge.left_face().borrow().edge_ids(), edge.right_face().borrow().edge_ids()),
+    }, self.document.clone()))
+  }
+
+  pub fn duplicate(&self) -> Self {
+    self.clone()
+  }
 }
 
 
@@ -152,7 +178,7 @@ impl JsSolid {
       if edge.borrow().is_inner() {
         None
       } else {
-        Some(JsValue::from(JsEdge::from(edge)))
+        Some(JsValue::from(JsEdge::from(edge, component_id, document.clone())))
       }
     }).collect();
     // Faces